@@ -1,10 +1,10 @@
-use c9watch_lib::session::SessionDetector;
+use c9watch_lib::session::{claude_dirs, SessionDetector};
 
 fn main() {
     println!("Claude Session Detector Test\n");
 
     // Create a new detector
-    let mut detector = match SessionDetector::new() {
+    let mut detector = match SessionDetector::new(claude_dirs()) {
         Ok(d) => d,
         Err(e) => {
             eprintln!("Failed to create detector: {}", e);
@@ -1,4 +1,9 @@
+use crate::config::StopConfig;
+use crate::session::child_processes;
+use serde::{Deserialize, Serialize};
 use std::process::Command;
+use std::time::{Duration, Instant};
+use sysinfo::{Pid, ProcessRefreshKind, ProcessesToUpdate, System, UpdateKind};
 
 /// Open a session by focusing its terminal or IDE window
 ///
@@ -361,32 +366,300 @@ fn get_app_name(comm: &str) -> Option<&'static str> {
     }
 }
 
-/// Stop a session by sending SIGTERM to the process
+/// Signal that finally ended a stopped session
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+pub enum StopStage {
+    /// Exited after SIGINT interrupted its turn
+    Interrupt,
+    /// Exited after SIGTERM
+    Terminate,
+    /// Had to be killed with SIGKILL
+    Kill,
+}
+
+/// How a stopped session ended
+#[derive(Debug, Clone, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct StopOutcome {
+    pub pid: u32,
+    pub ended_by: StopStage,
+    /// Time from the first signal until the process was gone
+    pub elapsed_ms: u64,
+    /// Whether signals went to the whole process group rather than the process alone
+    pub process_group: bool,
+    /// Processes the session spawned that were still running after it exited and were stopped
+    pub orphans_stopped: u32,
+}
+
+/// How long to wait for a SIGKILLed process to disappear before giving up
+const KILL_WAIT: Duration = Duration::from_secs(2);
+
+/// Stop a session, escalating from SIGINT to SIGTERM to SIGKILL
 ///
-/// This gracefully terminates the Claude process by sending a SIGTERM signal.
-/// SIGTERM is preferred over SIGINT as Claude Code may trap SIGINT for its own use.
-pub fn stop_session(pid: u32) -> Result<(), String> {
+/// Each stage waits for the process to actually exit before escalating.
+/// When the session leads its own process group (as it does when started
+/// from an interactive shell), SIGTERM and SIGKILL go to the whole group;
+/// processes it spawned in other groups are stopped after it exits.
+pub fn stop_session(pid: u32, config: &StopConfig) -> Result<StopOutcome, String> {
     eprintln!("[stop_session] Stopping PID: {}", pid);
+    validate_pid(pid)?;
+    if !is_running(pid) {
+        return Err(format!("Failed to stop process {}: no such process", pid));
+    }
 
-    // First try SIGTERM (signal 15) - graceful termination
-    let output = Command::new("kill")
-        .arg("-15") // SIGTERM
-        .arg(pid.to_string())
-        .output()
-        .map_err(|e| format!("Failed to execute kill command: {}", e))?;
+    // The tree is gone once the session exits and its children are reparented
+    let children: Vec<u32> = child_processes(pid)
+        .unwrap_or_default()
+        .iter()
+        .map(|child| child.pid)
+        .collect();
+    let process_group = leads_process_group(pid);
+    let started = Instant::now();
+
+    let stages = [
+        (StopStage::Interrupt, ProcessSignal::Interrupt, config.interrupt_grace_ms),
+        (StopStage::Terminate, ProcessSignal::Terminate, config.terminate_grace_ms),
+        (StopStage::Kill, ProcessSignal::Kill, KILL_WAIT.as_millis() as u64),
+    ];
+    let mut ended_by = None;
+    for (stage, signal, grace_ms) in stages {
+        // SIGINT only interrupts the turn; it isn't meant for the tool processes
+        let result = if process_group && signal != ProcessSignal::Interrupt {
+            signal_process_group(pid, signal)
+        } else {
+            signal_process(pid, signal)
+        };
+        if let Err(e) = result {
+            // Exited between the check and the signal
+            if !is_running(pid) {
+                ended_by = Some(stage);
+                break;
+            }
+            return Err(e);
+        }
+        eprintln!("[stop_session] {:?} sent", signal);
 
-    if !output.status.success() {
-        let error = String::from_utf8_lossy(&output.stderr);
-        eprintln!("[stop_session] SIGTERM failed: {}", error);
+        if wait_for_exit(pid, Duration::from_millis(grace_ms)) {
+            ended_by = Some(stage);
+            break;
+        }
+    }
+    let ended_by =
+        ended_by.ok_or_else(|| format!("Process {} is still running after SIGKILL", pid))?;
+    let elapsed_ms = started.elapsed().as_millis() as u64;
+
+    let orphans_stopped = stop_orphans(&children, config);
+    eprintln!(
+        "[stop_session] PID {} ended by {:?} after {}ms, {} orphans stopped",
+        pid, ended_by, elapsed_ms, orphans_stopped
+    );
+
+    Ok(StopOutcome {
+        pid,
+        ended_by,
+        elapsed_ms,
+        process_group,
+        orphans_stopped,
+    })
+}
+
+/// SIGTERM, then SIGKILL, the spawned processes that outlived their session
+fn stop_orphans(children: &[u32], config: &StopConfig) -> u32 {
+    let orphans: Vec<u32> = children.iter().copied().filter(|pid| is_running(*pid)).collect();
+    for orphan in &orphans {
+        let _ = signal_process(*orphan, ProcessSignal::Terminate);
+    }
+
+    let deadline = Instant::now() + Duration::from_millis(config.terminate_grace_ms);
+    for orphan in &orphans {
+        let remaining = deadline.saturating_duration_since(Instant::now());
+        if !wait_for_exit(*orphan, remaining) {
+            let _ = signal_process(*orphan, ProcessSignal::Kill);
+        }
+    }
+    orphans.len() as u32
+}
+
+/// Poll until `pid` is gone or `timeout` elapsed; true if it exited
+fn wait_for_exit(pid: u32, timeout: Duration) -> bool {
+    let deadline = Instant::now() + timeout;
+    loop {
+        if !is_running(pid) {
+            return true;
+        }
+        if Instant::now() >= deadline {
+            return false;
+        }
+        std::thread::sleep(Duration::from_millis(50));
+    }
+}
+
+/// Check that `pid` names a single process other than init
+///
+/// kill() treats 0 as the caller's own process group, so a session without a
+/// real process (like a replay) must never reach it.
+pub fn validate_pid(pid: u32) -> Result<libc::pid_t, String> {
+    match libc::pid_t::try_from(pid) {
+        Ok(pid) if pid > 1 => Ok(pid),
+        _ => Err(format!("Invalid PID: {}", pid)),
+    }
+}
+
+/// Whether `pid` exists and hasn't exited; zombies count as exited
+fn is_running(pid: u32) -> bool {
+    let Ok(pid) = validate_pid(pid) else {
+        return false;
+    };
+    // SAFETY: signal 0 only checks that the process exists
+    if unsafe { libc::kill(pid, 0) } != 0 {
+        // EPERM means it exists but belongs to someone else
+        return std::io::Error::last_os_error().raw_os_error() == Some(libc::EPERM);
+    }
+
+    #[cfg(target_os = "linux")]
+    if let Ok(stat) = std::fs::read_to_string(format!("/proc/{}/stat", pid)) {
+        // The state follows the parenthesised command name
+        let state = stat.rsplit_once(')').and_then(|(_, rest)| rest.trim_start().chars().next());
+        return state != Some('Z');
+    }
+    true
+}
+
+/// Whether `pid` is the leader of its process group
+fn leads_process_group(pid: u32) -> bool {
+    let Ok(pid) = libc::pid_t::try_from(pid) else {
+        return false;
+    };
+    // SAFETY: getpgid() only reads its argument
+    unsafe { libc::getpgid(pid) == pid }
+}
+
+/// Send `signal` to every process in the group `pgid` leads
+fn signal_process_group(pgid: u32, signal: ProcessSignal) -> Result<(), String> {
+    let pgid = validate_pid(pgid)?;
+    // SAFETY: killpg() only reads its arguments
+    if unsafe { libc::killpg(pgid, signal.number()) } != 0 {
+        return Err(format!(
+            "Failed to send {:?} to process group {}: {}",
+            signal,
+            pgid,
+            std::io::Error::last_os_error()
+        ));
+    }
+    Ok(())
+}
+
+/// How an interrupt reached a session
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+#[serde(tag = "kind", rename_all = "camelCase")]
+pub enum InterruptMethod {
+    /// Escape typed into the tmux pane the session runs in
+    TmuxKeys { pane: String },
+    /// SIGINT sent to the claude process, like Ctrl-C
+    Signal,
+}
+
+/// Stop the current turn of a session without ending it
+///
+/// Sessions running in tmux get an Escape keystroke, exactly what pressing
+/// Esc in the terminal does; everything else gets SIGINT.
+pub fn interrupt_session(pid: u32) -> Result<InterruptMethod, String> {
+    if let Some(pane) = tmux_pane(pid) {
+        let sent = Command::new("tmux")
+            .args(["send-keys", "-t", &pane, "Escape"])
+            .output()
+            .is_ok_and(|output| output.status.success());
+        if sent {
+            eprintln!("[interrupt_session] Sent Escape to tmux pane {}", pane);
+            return Ok(InterruptMethod::TmuxKeys { pane });
+        }
+        // The pane is gone (e.g. the server restarted); fall back to the signal
+        eprintln!("[interrupt_session] tmux pane {} unreachable", pane);
+    }
+
+    signal_process(pid, ProcessSignal::Interrupt)?;
+    eprintln!("[interrupt_session] Sent SIGINT to PID {}", pid);
+    Ok(InterruptMethod::Signal)
+}
+
+/// tmux pane a process runs in, from its `TMUX_PANE` environment variable
+fn tmux_pane(pid: u32) -> Option<String> {
+    let pid = Pid::from_u32(pid);
+    let mut system = System::new();
+    system.refresh_processes_specifics(
+        ProcessesToUpdate::Some(&[pid]),
+        true,
+        ProcessRefreshKind::new().with_environ(UpdateKind::Always),
+    );
+
+    system.process(pid)?.environ().iter().find_map(|var| {
+        var.to_str()?
+            .strip_prefix("TMUX_PANE=")
+            .filter(|pane| !pane.is_empty())
+            .map(str::to_string)
+    })
+}
 
-        // If SIGTERM fails, the process might not exist or we don't have permission
-        return Err(format!("Failed to stop process {}: {}", pid, error));
+/// Signals the UI may send to a process
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum ProcessSignal {
+    #[serde(rename = "SIGINT")]
+    Interrupt,
+    #[serde(rename = "SIGTERM")]
+    Terminate,
+    #[serde(rename = "SIGKILL")]
+    Kill,
+    #[serde(rename = "SIGHUP")]
+    Hangup,
+}
+
+impl ProcessSignal {
+    fn number(self) -> libc::c_int {
+        match self {
+            ProcessSignal::Interrupt => libc::SIGINT,
+            ProcessSignal::Terminate => libc::SIGTERM,
+            ProcessSignal::Kill => libc::SIGKILL,
+            ProcessSignal::Hangup => libc::SIGHUP,
+        }
     }
+}
 
-    eprintln!("[stop_session] SIGTERM sent successfully");
+/// Send `signal` to a single process
+pub fn signal_process(pid: u32, signal: ProcessSignal) -> Result<(), String> {
+    let pid = validate_pid(pid)?;
+    // SAFETY: kill() only reads its arguments; a positive pid targets one process
+    if unsafe { libc::kill(pid, signal.number()) } != 0 {
+        return Err(format!(
+            "Failed to send {:?} to process {}: {}",
+            signal,
+            pid,
+            std::io::Error::last_os_error()
+        ));
+    }
     Ok(())
 }
 
+/// Signal a process a session spawned, leaving the session itself running
+///
+/// Refuses pids outside the session's process tree, so a stale list in the
+/// UI can't hit an unrelated process that reused the pid.
+pub fn signal_child(session_pid: u32, child_pid: u32, signal: ProcessSignal) -> Result<(), String> {
+    let children = child_processes(session_pid)
+        .ok_or_else(|| format!("Session process {} is not running", session_pid))?;
+    if !children.iter().any(|child| child.pid == child_pid) {
+        return Err(format!(
+            "Process {} is not a child of session process {}",
+            child_pid, session_pid
+        ));
+    }
+
+    eprintln!(
+        "[signal_child] Sending {:?} to PID {} (session PID {})",
+        signal, child_pid, session_pid
+    );
+    signal_process(child_pid, signal)
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -394,10 +667,92 @@ mod tests {
     #[test]
     fn test_stop_session_invalid_pid() {
         // Try to stop a non-existent process
-        let result = stop_session(999999);
+        let result = stop_session(999999, &StopConfig::default());
         assert!(result.is_err());
     }
 
+    #[test]
+    fn test_stop_session_rejects_own_group_and_init() {
+        // kill(0, ...) would signal c9watch's own process group
+        for pid in [0, 1] {
+            assert_eq!(
+                stop_session(pid, &StopConfig::default()).unwrap_err(),
+                format!("Invalid PID: {}", pid)
+            );
+            assert!(!is_running(pid));
+            assert!(signal_process(pid, ProcessSignal::Interrupt).is_err());
+        }
+    }
+
+    /// Spawn `script` in its own process group and reap it in the background
+    fn spawn_session(script: &str) -> u32 {
+        use std::os::unix::process::CommandExt;
+
+        let mut command = Command::new("sh");
+        command.args(["-c", script]).process_group(0);
+        // SAFETY: signal() is async-signal-safe. Undo a SIGINT ignored by
+        // whatever runs the tests, which the shell would otherwise inherit
+        unsafe {
+            command.pre_exec(|| {
+                libc::signal(libc::SIGINT, libc::SIG_DFL);
+                Ok(())
+            });
+        }
+        let mut child = command.spawn().unwrap();
+        let pid = child.id();
+        std::thread::spawn(move || child.wait());
+        // Give the shell time to install its traps
+        std::thread::sleep(Duration::from_millis(200));
+        pid
+    }
+
+    #[test]
+    fn test_stop_session_escalates() {
+        let config = StopConfig {
+            interrupt_grace_ms: 200,
+            terminate_grace_ms: 200,
+        };
+
+        // Exits on the first SIGINT
+        let pid = spawn_session("exec sleep 30");
+        let outcome = stop_session(pid, &config).unwrap();
+        assert_eq!(outcome.ended_by, StopStage::Interrupt);
+        assert!(outcome.process_group);
+
+        // Ignores SIGINT and SIGTERM
+        let pid = spawn_session("trap '' INT TERM; while true; do sleep 0.05; done");
+        let outcome = stop_session(pid, &config).unwrap();
+        assert_eq!(outcome.ended_by, StopStage::Kill);
+        assert!(!is_running(pid));
+    }
+
+    #[test]
+    fn test_signal_child_only_reaches_descendants() {
+        let mut child = Command::new("sleep").arg("30").spawn().unwrap();
+        let own_pid = std::process::id();
+
+        // The session itself is not its own child
+        assert!(signal_child(own_pid, own_pid, ProcessSignal::Terminate).is_err());
+        assert!(signal_child(child.id(), own_pid, ProcessSignal::Terminate).is_err());
+
+        signal_child(own_pid, child.id(), ProcessSignal::Kill).unwrap();
+        let status = child.wait().unwrap();
+        assert!(!status.success());
+    }
+
+    #[test]
+    fn test_interrupt_session_finds_tmux_pane() {
+        let mut child = Command::new("sleep")
+            .arg("30")
+            .env("TMUX_PANE", "%42")
+            .spawn()
+            .unwrap();
+        assert_eq!(tmux_pane(child.id()).as_deref(), Some("%42"));
+
+        let _ = child.kill();
+        let _ = child.wait();
+    }
+
     #[test]
     #[ignore] // This test requires manual verification
     fn test_open_session() {
@@ -0,0 +1,255 @@
+//! Ranking of the sessions that are waiting on the user
+//!
+//! Status groups tell which sessions need the user, not which one to handle
+//! first. The queue scores each waiting session on its status, how long it
+//! has been waiting, what the pending tool could do, and the user's own
+//! pinned/priority/project importance settings.
+
+use crate::config::{self, AttentionConfig};
+use crate::polling::Session;
+use crate::session::{RiskLevel, SessionStatus};
+use chrono::{DateTime, Utc};
+use serde::Serialize;
+use std::collections::HashMap;
+use std::path::Path;
+
+/// Points for the status itself; a blocked tool call outranks a finished turn
+const PERMISSION_POINTS: i64 = 100;
+const INPUT_POINTS: i64 = 50;
+
+/// Points per minute spent waiting, counted for up to an hour
+const WAITING_POINTS_PER_MINUTE: i64 = 1;
+const MAX_WAITING_MINUTES: i64 = 60;
+
+const PINNED_POINTS: i64 = 30;
+/// Points per step of session priority and of project importance
+const PRIORITY_POINTS: i64 = 10;
+const IMPORTANCE_POINTS: i64 = 10;
+
+/// A session waiting on the user and what its rank is made of
+#[derive(Debug, Clone, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct AttentionItem {
+    pub session_id: String,
+    pub pid: u32,
+    pub project_path: String,
+    pub status: SessionStatus,
+    /// Seconds since the session entered its status
+    pub waiting_secs: u64,
+    pub score: i64,
+    /// Readable parts of the score, largest first, e.g. "Bash destructive (+40)"
+    pub reasons: Vec<String>,
+}
+
+/// Rank the sessions waiting on the user, the one to handle first at the top
+///
+/// `status_since` maps session IDs to when they entered their current status
+/// (RFC 3339); sessions missing from it count from their last modification.
+pub fn attention_queue(
+    sessions: &[Session],
+    status_since: &HashMap<String, String>,
+    config: &AttentionConfig,
+    now: DateTime<Utc>,
+) -> Vec<AttentionItem> {
+    let mut queue: Vec<AttentionItem> = sessions
+        .iter()
+        .filter_map(|session| {
+            let since = status_since.get(&session.id).unwrap_or(&session.modified);
+            score(session, waiting_secs(since, now), config)
+        })
+        .collect();
+
+    queue.sort_by(|a, b| {
+        b.score
+            .cmp(&a.score)
+            .then(b.waiting_secs.cmp(&a.waiting_secs))
+            .then_with(|| a.session_id.cmp(&b.session_id))
+    });
+    queue
+}
+
+/// Rank `sessions` with the configured project importance and the recorded status transitions
+pub fn current_queue(sessions: &[Session]) -> Vec<AttentionItem> {
+    let status_since = crate::store::global()
+        .and_then(|store| store.status_since().map_err(|e| e.to_string()))
+        .unwrap_or_else(|e| {
+            eprintln!("[attention] Status transitions unavailable: {}", e);
+            HashMap::new()
+        });
+    attention_queue(sessions, &status_since, &config::current().attention, Utc::now())
+}
+
+fn waiting_secs(since: &str, now: DateTime<Utc>) -> u64 {
+    DateTime::parse_from_rfc3339(since)
+        .map(|since| (now - since.with_timezone(&Utc)).num_seconds().max(0) as u64)
+        .unwrap_or(0)
+}
+
+/// Score a session, or None when it isn't waiting on the user
+fn score(session: &Session, waiting_secs: u64, config: &AttentionConfig) -> Option<AttentionItem> {
+    let mut parts: Vec<(String, i64)> = Vec::new();
+
+    match session.status {
+        SessionStatus::NeedsPermission => {
+            parts.push(("needs permission".to_string(), PERMISSION_POINTS));
+            // Several tools in one request rank by the riskiest of them
+            if let Some(tool) = session.pending_tools.iter().max_by_key(|tool| tool.risk.level) {
+                let level = tool.risk.level;
+                parts.push((format!("{} {}", tool.name, level.label()), risk_points(level)));
+            }
+        }
+        SessionStatus::WaitingForInput => parts.push(("waiting for input".to_string(), INPUT_POINTS)),
+        SessionStatus::Working | SessionStatus::Connecting => return None,
+    }
+
+    let minutes = (waiting_secs / 60) as i64;
+    if minutes > 0 {
+        let points = minutes.min(MAX_WAITING_MINUTES) * WAITING_POINTS_PER_MINUTE;
+        parts.push((format!("waiting {} min", minutes), points));
+    }
+    if session.pinned {
+        parts.push(("pinned".to_string(), PINNED_POINTS));
+    }
+    if session.priority != 0 {
+        parts.push(("priority".to_string(), session.priority as i64 * PRIORITY_POINTS));
+    }
+    let importance = config.importance_of(Path::new(&session.project_path));
+    if importance != 0 {
+        parts.push(("project importance".to_string(), importance as i64 * IMPORTANCE_POINTS));
+    }
+
+    parts.sort_by_key(|(_, points)| std::cmp::Reverse(*points));
+    Some(AttentionItem {
+        session_id: session.id.clone(),
+        pid: session.pid,
+        project_path: session.project_path.clone(),
+        status: session.status.clone(),
+        waiting_secs,
+        score: parts.iter().map(|(_, points)| points).sum(),
+        reasons: parts
+            .into_iter()
+            .map(|(reason, points)| format!("{} ({:+})", reason, points))
+            .collect(),
+    })
+}
+
+/// Ranking points for what a pending tool call could do
+fn risk_points(level: RiskLevel) -> i64 {
+    match level {
+        RiskLevel::ReadOnly => 0,
+        RiskLevel::LocalWrite => 10,
+        RiskLevel::Network => 30,
+        RiskLevel::Secrets => 35,
+        RiskLevel::Destructive => 40,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::session::{PendingTool, PendingToolUse, RiskRule};
+    use serde_json::{json, Value};
+
+    fn session(id: &str, status: SessionStatus) -> Session {
+        Session {
+            id: id.to_string(),
+            pid: 100,
+            session_name: id.to_string(),
+            custom_title: None,
+            project_path: "/work/api".to_string(),
+            git_branch: None,
+            first_prompt: String::new(),
+            summary: None,
+            message_count: 2,
+            modified: "2026-01-01T12:00:00+00:00".to_string(),
+            status,
+            latest_message: String::new(),
+            pending_tool_name: None,
+            pending_tools: vec![],
+            tags: vec![],
+            note: None,
+            pinned: false,
+            priority: 0,
+            context: None,
+            compaction_count: 0,
+            model: None,
+            model_switches: vec![],
+            cli_version: None,
+            conflicts: vec![],
+            git: None,
+            resources: None,
+            embedded_terminal: false,
+        }
+    }
+
+    fn permission(id: &str, tool: &str, input: Value) -> Session {
+        let pending = PendingToolUse { id: "toolu_1", name: tool, input: &input };
+        Session {
+            pending_tool_name: Some(tool.to_string()),
+            pending_tools: vec![PendingTool::new(&pending, &RiskRule::defaults())],
+            ..session(id, SessionStatus::NeedsPermission)
+        }
+    }
+
+    fn now() -> DateTime<Utc> {
+        DateTime::parse_from_rfc3339("2026-01-01T12:10:00+00:00")
+            .unwrap()
+            .with_timezone(&Utc)
+    }
+
+    fn ranked(sessions: &[Session], config: &AttentionConfig) -> Vec<String> {
+        attention_queue(sessions, &HashMap::new(), config, now())
+            .into_iter()
+            .map(|item| item.session_id)
+            .collect()
+    }
+
+    #[test]
+    fn test_risky_tools_rank_first() {
+        let sessions = vec![
+            session("working", SessionStatus::Working),
+            session("input", SessionStatus::WaitingForInput),
+            permission("edit", "Edit", json!({ "file_path": "/work/api/src/lib.rs" })),
+            permission("push", "Bash", json!({ "command": "cargo test && git push origin main" })),
+            permission("rm", "Bash", json!({ "command": "sudo rm -rf target" })),
+            permission("env", "Edit", json!({ "file_path": "/work/api/.env" })),
+        ];
+
+        assert_eq!(
+            ranked(&sessions, &AttentionConfig::default()),
+            vec!["rm", "env", "push", "edit", "input"]
+        );
+    }
+
+    #[test]
+    fn test_waiting_time_pins_and_importance() {
+        let mut since = HashMap::new();
+        // "old" has waited 10 minutes, "new" entered its status just now
+        since.insert("new".to_string(), now().to_rfc3339());
+        let sessions = vec![
+            session("new", SessionStatus::WaitingForInput),
+            session("old", SessionStatus::WaitingForInput),
+        ];
+        let queue = attention_queue(&sessions, &since, &AttentionConfig::default(), now());
+        assert_eq!(queue[0].session_id, "old");
+        assert_eq!(queue[0].waiting_secs, 600);
+        assert_eq!(queue[0].score, INPUT_POINTS + 10);
+        assert_eq!(queue[0].reasons, vec!["waiting for input (+50)", "waiting 10 min (+10)"]);
+
+        let pinned = Session {
+            pinned: true,
+            ..session("pinned", SessionStatus::WaitingForInput)
+        };
+        let elsewhere = Session {
+            project_path: "/scratch/tool".to_string(),
+            ..permission("elsewhere", "Edit", json!({}))
+        };
+        let mut config = AttentionConfig::default();
+        config.project_importance.insert("/scratch".to_string(), -10);
+
+        assert_eq!(
+            ranked(&[elsewhere, pinned, session("plain", SessionStatus::WaitingForInput)], &config),
+            vec!["pinned", "plain", "elsewhere"]
+        );
+    }
+}
@@ -0,0 +1,635 @@
+use crate::session::{RiskRule, SessionStatus};
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, OnceLock, RwLock};
+use std::time::SystemTime;
+use thiserror::Error;
+
+#[derive(Error, Debug)]
+pub enum ConfigError {
+    #[error("Config I/O error: {0}")]
+    Io(#[from] std::io::Error),
+
+    #[error("Invalid config file: {0}")]
+    Parse(#[from] toml::de::Error),
+
+    #[error("Failed to serialize config: {0}")]
+    Serialize(#[from] toml::ser::Error),
+
+    #[error("Invalid config: {0}")]
+    Invalid(String),
+
+    #[error("Failed to get config directory")]
+    ConfigDirectoryNotFound,
+}
+
+/// Application configuration, stored as `config.toml` in the app config directory
+///
+/// Every field has a default, so a partial file (or none at all) is valid.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, Default)]
+#[serde(default)]
+pub struct AppConfig {
+    pub polling: PollingConfig,
+    pub status: StatusConfig,
+    pub display: DisplayConfig,
+    pub notifications: NotificationConfig,
+    pub context: ContextConfig,
+    pub resources: ResourceConfig,
+    pub stop: StopConfig,
+    pub launch: LaunchConfig,
+    pub attention: AttentionConfig,
+    pub risk: RiskConfig,
+}
+
+/// How often and how deep session files are read
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct PollingConfig {
+    /// Delay between two polling cycles
+    pub interval_ms: u64,
+
+    /// Number of trailing JSONL entries parsed per session
+    pub tail_entries: usize,
+}
+
+impl Default for PollingConfig {
+    fn default() -> Self {
+        Self {
+            interval_ms: 2000,
+            tail_entries: 20,
+        }
+    }
+}
+
+/// Recency windows used to tell an active session from an idle one
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct StatusConfig {
+    /// A tool result younger than this means Claude is still working
+    pub tool_result_recent_secs: i64,
+
+    /// A user prompt younger than this means Claude is still responding
+    pub user_prompt_recent_secs: i64,
+
+    /// Assistant text younger than this means Claude may still be streaming
+    pub assistant_recent_secs: i64,
+}
+
+impl Default for StatusConfig {
+    fn default() -> Self {
+        Self {
+            tool_result_recent_secs: 15,
+            user_prompt_recent_secs: 30,
+            assistant_recent_secs: 10,
+        }
+    }
+}
+
+/// Truncation lengths (in characters) for text shown in the UI
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct DisplayConfig {
+    pub first_prompt_max_chars: usize,
+    pub latest_message_max_chars: usize,
+    pub notification_title_max_chars: usize,
+}
+
+impl Default for DisplayConfig {
+    fn default() -> Self {
+        Self {
+            first_prompt_max_chars: 100,
+            latest_message_max_chars: 200,
+            notification_title_max_chars: 60,
+        }
+    }
+}
+
+/// Status transitions that fire a native notification
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct NotificationConfig {
+    pub transitions: Vec<NotificationTransition>,
+}
+
+impl Default for NotificationConfig {
+    fn default() -> Self {
+        Self {
+            transitions: vec![
+                NotificationTransition {
+                    from: SessionStatus::Working,
+                    to: SessionStatus::NeedsPermission,
+                },
+                NotificationTransition {
+                    from: SessionStatus::Working,
+                    to: SessionStatus::WaitingForInput,
+                },
+            ],
+        }
+    }
+}
+
+impl NotificationConfig {
+    /// Whether moving from `from` to `to` should notify
+    pub fn should_notify(&self, from: &SessionStatus, to: &SessionStatus) -> bool {
+        self.transitions
+            .iter()
+            .any(|t| &t.from == from && &t.to == to)
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct NotificationTransition {
+    pub from: SessionStatus,
+    pub to: SessionStatus,
+}
+
+/// Context window sizes and the fill level worth a notification
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct ContextConfig {
+    /// Notify when a session's context fill reaches this percentage (0 disables)
+    pub notify_at_percent: u8,
+
+    /// Window size for models without an entry in `model_windows`
+    pub default_window_tokens: u64,
+
+    /// Window size per model name prefix; the longest matching prefix wins
+    pub model_windows: BTreeMap<String, u64>,
+}
+
+impl Default for ContextConfig {
+    fn default() -> Self {
+        Self {
+            notify_at_percent: 80,
+            default_window_tokens: 200_000,
+            model_windows: BTreeMap::new(),
+        }
+    }
+}
+
+impl ContextConfig {
+    /// Context window of `model`, in tokens
+    pub fn window_for(&self, model: &str) -> u64 {
+        self.model_windows
+            .iter()
+            .filter(|(prefix, _)| model.starts_with(prefix.as_str()))
+            .max_by_key(|(prefix, _)| prefix.len())
+            .map(|(_, tokens)| *tokens)
+            .unwrap_or(if model.ends_with("[1m]") {
+                1_000_000
+            } else {
+                self.default_window_tokens
+            })
+    }
+}
+
+/// Per-session budgets for the claude process and everything it spawned
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct ResourceConfig {
+    /// Notify when a session's process tree holds more resident memory than this (0 disables)
+    pub memory_budget_mb: u64,
+
+    /// Notify when a session's process tree averages more CPU than this; 100
+    /// is one full core (0 disables)
+    pub cpu_budget_percent: u32,
+
+    /// Polling cycles the CPU average covers
+    pub sample_window: usize,
+}
+
+impl Default for ResourceConfig {
+    fn default() -> Self {
+        Self {
+            memory_budget_mb: 8192,
+            cpu_budget_percent: 400,
+            sample_window: 15,
+        }
+    }
+}
+
+/// How long stopping a session waits at each stage before escalating
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct StopConfig {
+    /// Wait after SIGINT (which interrupts the current turn) before sending SIGTERM
+    pub interrupt_grace_ms: u64,
+
+    /// Wait after SIGTERM before sending SIGKILL
+    pub terminate_grace_ms: u64,
+}
+
+impl Default for StopConfig {
+    fn default() -> Self {
+        Self {
+            interrupt_grace_ms: 1500,
+            terminate_grace_ms: 3000,
+        }
+    }
+}
+
+/// How new sessions are started
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct LaunchConfig {
+    /// Claude Code executable, looked up on PATH unless it is a path
+    pub claude_command: String,
+
+    /// tmux session that new windows are opened in; created when missing
+    pub tmux_session: String,
+
+    /// Terminal emulator and the arguments that precede the command it
+    /// should run, e.g. `["kitty", "--"]`. Empty uses Terminal.app on macOS.
+    pub terminal_command: Vec<String>,
+}
+
+impl Default for LaunchConfig {
+    fn default() -> Self {
+        let terminal_command = if cfg!(target_os = "macos") {
+            vec![]
+        } else {
+            vec!["x-terminal-emulator".to_string(), "-e".to_string()]
+        };
+        Self {
+            claude_command: "claude".to_string(),
+            tmux_session: "c9watch".to_string(),
+            terminal_command,
+        }
+    }
+}
+
+/// How the attention queue ranks sessions that are waiting on the user
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, Default)]
+#[serde(default)]
+pub struct AttentionConfig {
+    /// Importance of projects by path, from -10 to 10; a session takes the
+    /// value of the longest path its project is in
+    pub project_importance: BTreeMap<String, i32>,
+}
+
+impl AttentionConfig {
+    /// Importance of the project at `project_path`, 0 when none is configured
+    pub fn importance_of(&self, project_path: &Path) -> i32 {
+        self.project_importance
+            .iter()
+            .filter(|(path, _)| project_path.starts_with(path))
+            .max_by_key(|(path, _)| path.len())
+            .map(|(_, importance)| *importance)
+            .unwrap_or(0)
+    }
+}
+
+/// Rules that classify pending tool calls by what they could do
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct RiskConfig {
+    /// Replaces the built-in rules when set; a call gets the highest level
+    /// among its tool's own and every matching rule
+    pub rules: Vec<RiskRule>,
+}
+
+impl Default for RiskConfig {
+    fn default() -> Self {
+        Self {
+            rules: RiskRule::defaults(),
+        }
+    }
+}
+
+impl AppConfig {
+    /// Reject values that would stall polling or make the UI unusable
+    pub fn validate(&self) -> Result<(), ConfigError> {
+        let invalid = |msg: &str| Err(ConfigError::Invalid(msg.to_string()));
+
+        if !(250..=60_000).contains(&self.polling.interval_ms) {
+            return invalid("polling.interval_ms must be between 250 and 60000");
+        }
+        if !(1..=1000).contains(&self.polling.tail_entries) {
+            return invalid("polling.tail_entries must be between 1 and 1000");
+        }
+
+        let status = &self.status;
+        if [
+            status.tool_result_recent_secs,
+            status.user_prompt_recent_secs,
+            status.assistant_recent_secs,
+        ]
+        .iter()
+        .any(|secs| !(1..=3600).contains(secs))
+        {
+            return invalid("status thresholds must be between 1 and 3600 seconds");
+        }
+
+        let display = &self.display;
+        if [
+            display.first_prompt_max_chars,
+            display.latest_message_max_chars,
+            display.notification_title_max_chars,
+        ]
+        .contains(&0)
+        {
+            return invalid("display lengths must be greater than 0");
+        }
+
+        if self.notifications.transitions.iter().any(|t| t.from == t.to) {
+            return invalid("notification transitions must change status");
+        }
+
+        let context = &self.context;
+        if context.notify_at_percent > 100 {
+            return invalid("context.notify_at_percent must be between 0 and 100");
+        }
+        if context.default_window_tokens == 0 || context.model_windows.values().any(|t| *t == 0) {
+            return invalid("context window sizes must be greater than 0");
+        }
+
+        if !(1..=600).contains(&self.resources.sample_window) {
+            return invalid("resources.sample_window must be between 1 and 600");
+        }
+
+        if [self.stop.interrupt_grace_ms, self.stop.terminate_grace_ms]
+            .iter()
+            .any(|ms| *ms > 60_000)
+        {
+            return invalid("stop grace periods must be at most 60000 ms");
+        }
+
+        if self.launch.claude_command.trim().is_empty() || self.launch.tmux_session.trim().is_empty() {
+            return invalid("launch.claude_command and launch.tmux_session must not be empty");
+        }
+
+        if self.attention.project_importance.values().any(|i| !(-10..=10).contains(i)) {
+            return invalid("attention.project_importance values must be between -10 and 10");
+        }
+
+        if !self.risk.rules.iter().all(RiskRule::is_valid) {
+            return invalid("risk rules need exactly one non-empty command or contains pattern");
+        }
+
+        Ok(())
+    }
+
+    /// Parse and validate a TOML document
+    pub fn from_toml(content: &str) -> Result<Self, ConfigError> {
+        let config: Self = toml::from_str(content)?;
+        config.validate()?;
+        Ok(config)
+    }
+
+    /// Load from `path`, using defaults when the file doesn't exist
+    pub fn load(path: &Path) -> Result<Self, ConfigError> {
+        match fs::read_to_string(path) {
+            Ok(content) => Self::from_toml(&content),
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => Ok(Self::default()),
+            Err(e) => Err(e.into()),
+        }
+    }
+
+    /// Validate and write to `path` via a temp file and rename
+    pub fn save(&self, path: &Path) -> Result<(), ConfigError> {
+        self.validate()?;
+
+        if let Some(parent) = path.parent() {
+            fs::create_dir_all(parent)?;
+        }
+
+        let content = toml::to_string_pretty(self)?;
+        let tmp_path = path.with_extension(format!("toml.tmp-{}", std::process::id()));
+        fs::write(&tmp_path, content)?;
+        fs::rename(&tmp_path, path).inspect_err(|_| {
+            let _ = fs::remove_file(&tmp_path);
+        })?;
+        Ok(())
+    }
+
+    /// Default location of `config.toml`
+    pub fn default_path() -> Result<PathBuf, ConfigError> {
+        let config_dir = dirs::config_dir().ok_or(ConfigError::ConfigDirectoryNotFound)?;
+        Ok(config_dir.join("com.minchenlee.c9watch").join("config.toml"))
+    }
+}
+
+/// The active config plus what's needed to notice edits to its file
+struct LoadedConfig {
+    config: Arc<AppConfig>,
+    path: Option<PathBuf>,
+    modified: Option<SystemTime>,
+}
+
+static CONFIG: OnceLock<RwLock<LoadedConfig>> = OnceLock::new();
+
+fn loaded() -> &'static RwLock<LoadedConfig> {
+    CONFIG.get_or_init(|| {
+        // Unit tests always run against the defaults, never the developer's file
+        let path = if cfg!(test) {
+            None
+        } else {
+            AppConfig::default_path()
+                .inspect_err(|e| eprintln!("[config] {}", e))
+                .ok()
+        };
+
+        let config = match &path {
+            Some(path) => AppConfig::load(path).unwrap_or_else(|e| {
+                eprintln!("[config] {}: {}. Using defaults.", path.display(), e);
+                AppConfig::default()
+            }),
+            None => AppConfig::default(),
+        };
+        let modified = path.as_deref().and_then(modified_time);
+
+        RwLock::new(LoadedConfig {
+            config: Arc::new(config),
+            path,
+            modified,
+        })
+    })
+}
+
+fn modified_time(path: &Path) -> Option<SystemTime> {
+    fs::metadata(path).and_then(|m| m.modified()).ok()
+}
+
+/// Snapshot of the active config
+pub fn current() -> Arc<AppConfig> {
+    match loaded().read() {
+        Ok(guard) => Arc::clone(&guard.config),
+        Err(poisoned) => Arc::clone(&poisoned.into_inner().config),
+    }
+}
+
+/// Reload the config file if it changed on disk since it was last read
+///
+/// Returns the new config when it changed. An invalid edit is logged and the
+/// previous config stays active; deleting the file restores the defaults.
+pub fn reload_if_changed() -> Option<Arc<AppConfig>> {
+    let mut guard = loaded().write().unwrap_or_else(|p| p.into_inner());
+    let path = guard.path.clone()?;
+
+    let modified = modified_time(&path);
+    if modified == guard.modified {
+        return None;
+    }
+    guard.modified = modified;
+
+    match AppConfig::load(&path) {
+        Ok(config) if config != *guard.config => {
+            guard.config = Arc::new(config);
+            Some(Arc::clone(&guard.config))
+        }
+        Ok(_) => None,
+        Err(e) => {
+            eprintln!("[config] Ignoring invalid edit to {}: {}", path.display(), e);
+            None
+        }
+    }
+}
+
+/// Validate, persist and activate a new config
+pub fn update(config: AppConfig) -> Result<Arc<AppConfig>, ConfigError> {
+    config.validate()?;
+
+    let mut guard = loaded().write().unwrap_or_else(|p| p.into_inner());
+    if let Some(path) = &guard.path {
+        config.save(path)?;
+        guard.modified = modified_time(path);
+    }
+    guard.config = Arc::new(config);
+    Ok(Arc::clone(&guard.config))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_defaults_match_previous_constants() {
+        let config = AppConfig::default();
+        assert_eq!(config.polling.interval_ms, 2000);
+        assert_eq!(config.polling.tail_entries, 20);
+        assert_eq!(config.status.assistant_recent_secs, 10);
+        assert!(config
+            .notifications
+            .should_notify(&SessionStatus::Working, &SessionStatus::NeedsPermission));
+        assert!(!config
+            .notifications
+            .should_notify(&SessionStatus::WaitingForInput, &SessionStatus::Working));
+        assert!(config.validate().is_ok());
+    }
+
+    #[test]
+    fn test_partial_file_keeps_other_defaults() {
+        let config = AppConfig::from_toml(
+            r#"
+            [polling]
+            interval_ms = 5000
+
+            [[notifications.transitions]]
+            from = "Working"
+            to = "NeedsPermission"
+            "#,
+        )
+        .unwrap();
+
+        assert_eq!(config.polling.interval_ms, 5000);
+        assert_eq!(config.polling.tail_entries, 20);
+        assert_eq!(config.display, DisplayConfig::default());
+        assert_eq!(config.notifications.transitions.len(), 1);
+    }
+
+    #[test]
+    fn test_context_window_lookup() {
+        let config = AppConfig::from_toml(
+            r#"
+            [context.model_windows]
+            "claude-sonnet" = 500000
+            "claude-sonnet-4-5" = 1000000
+            "#,
+        )
+        .unwrap()
+        .context;
+
+        assert_eq!(config.window_for("claude-sonnet-4-5-20250929"), 1_000_000);
+        assert_eq!(config.window_for("claude-sonnet-4-20250514"), 500_000);
+        assert_eq!(config.window_for("claude-opus-4-5-20251101"), 200_000);
+        assert_eq!(config.window_for("claude-opus-4-6[1m]"), 1_000_000);
+        assert_eq!(config.notify_at_percent, 80);
+    }
+
+    #[test]
+    fn test_project_importance_lookup() {
+        let config = AppConfig::from_toml(
+            r#"
+            [attention.project_importance]
+            "/work" = 2
+            "/work/api" = 5
+            "/work/scratch" = -3
+            "#,
+        )
+        .unwrap()
+        .attention;
+
+        assert_eq!(config.importance_of(Path::new("/work/api/server")), 5);
+        assert_eq!(config.importance_of(Path::new("/work/web")), 2);
+        assert_eq!(config.importance_of(Path::new("/work/scratch")), -3);
+        // Paths match whole components only
+        assert_eq!(config.importance_of(Path::new("/workshop")), 0);
+    }
+
+    #[test]
+    fn test_risk_rules_replace_the_defaults() {
+        let config = AppConfig::from_toml(
+            r#"
+            [[risk.rules]]
+            command = "terraform apply"
+            level = "destructive"
+
+            [[risk.rules]]
+            tools = ["WebFetch"]
+            contains = "internal.example.com"
+            level = "secrets"
+            "#,
+        )
+        .unwrap();
+
+        assert_eq!(config.risk.rules.len(), 2);
+        assert_eq!(config.risk.rules[0].tools, Vec::<String>::new());
+        assert_eq!(config.risk.rules[1].level, crate::session::RiskLevel::Secrets);
+        assert_eq!(AppConfig::default().risk.rules, RiskRule::defaults());
+    }
+
+    #[test]
+    fn test_invalid_values_are_rejected() {
+        assert!(AppConfig::from_toml("[polling]\ninterval_ms = 10").is_err());
+        assert!(AppConfig::from_toml("[display]\nfirst_prompt_max_chars = 0").is_err());
+        assert!(AppConfig::from_toml(
+            "[[notifications.transitions]]\nfrom = \"Working\"\nto = \"Working\""
+        )
+        .is_err());
+        assert!(AppConfig::from_toml("[polling]\ninterval_ms = \"fast\"").is_err());
+        assert!(AppConfig::from_toml("[context]\nnotify_at_percent = 150").is_err());
+        assert!(AppConfig::from_toml("[resources]\nsample_window = 0").is_err());
+        assert!(AppConfig::from_toml("[attention.project_importance]\n\"/work\" = 11").is_err());
+        assert!(AppConfig::from_toml("[[risk.rules]]\nlevel = \"network\"").is_err());
+    }
+
+    #[test]
+    fn test_save_and_load_round_trip() {
+        let dir = std::env::temp_dir().join(format!("c9watch-config-test-{}", std::process::id()));
+        let path = dir.join("config.toml");
+
+        let mut config = AppConfig::default();
+        config.polling.tail_entries = 50;
+        config.save(&path).unwrap();
+
+        assert_eq!(AppConfig::load(&path).unwrap(), config);
+        assert_eq!(
+            AppConfig::load(&dir.join("missing.toml")).unwrap(),
+            AppConfig::default()
+        );
+
+        let _ = fs::remove_dir_all(&dir);
+    }
+}
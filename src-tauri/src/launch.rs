@@ -0,0 +1,513 @@
+//! Starting new Claude Code sessions and resuming finished ones
+//!
+//! Every launch passes `--session-id` with an ID chosen here (or
+//! `--resume` with the existing one), which the detector reads back from
+//! the process's command line. That ties the pid to its session file before
+//! the file exists, whichever way claude was started.
+
+use crate::config::LaunchConfig;
+use crate::pty;
+use crate::session::{encode_project_dir, parse_all_entries, SessionEntry};
+use serde::{Deserialize, Serialize};
+use std::collections::{HashMap, HashSet};
+use std::io::Read;
+use std::path::{Path, PathBuf};
+use std::process::Command;
+use std::sync::Mutex;
+use thiserror::Error;
+
+#[derive(Error, Debug)]
+pub enum LaunchError {
+    #[error("Project directory does not exist: {0}")]
+    MissingProjectDir(PathBuf),
+
+    #[error("Failed to run {program}: {source}")]
+    Spawn {
+        program: String,
+        source: std::io::Error,
+    },
+
+    #[error("{program} failed: {stderr}")]
+    Failed { program: String, stderr: String },
+
+    #[error("No terminal configured; set launch.terminal_command")]
+    NoTerminal,
+
+    #[error(transparent)]
+    Pty(#[from] pty::PtyError),
+
+    #[error("Failed to generate a session ID: {0}")]
+    SessionId(std::io::Error),
+
+    #[error("Failed to read session transcript: {0}")]
+    Transcript(String),
+
+    #[error("Session transcript records no working directory")]
+    NoRecordedCwd,
+}
+
+/// Where the new claude process runs
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum LaunchTarget {
+    /// A new window in the configured tmux session
+    Tmux,
+    /// A window of the configured terminal emulator
+    Terminal,
+    /// A pseudo-terminal owned by c9watch, with no window
+    Detached,
+}
+
+/// Claude Code's `--permission-mode` values
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum PermissionMode {
+    Default,
+    AcceptEdits,
+    Plan,
+    BypassPermissions,
+}
+
+impl PermissionMode {
+    fn as_arg(self) -> &'static str {
+        match self {
+            PermissionMode::Default => "default",
+            PermissionMode::AcceptEdits => "acceptEdits",
+            PermissionMode::Plan => "plan",
+            PermissionMode::BypassPermissions => "bypassPermissions",
+        }
+    }
+}
+
+/// What to start and where
+#[derive(Debug, Clone, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct LaunchRequest {
+    pub project_dir: PathBuf,
+    pub prompt: Option<String>,
+    pub model: Option<String>,
+    pub permission_mode: Option<PermissionMode>,
+    pub target: LaunchTarget,
+}
+
+/// A session that was just started
+#[derive(Debug, Clone, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct LaunchedSession {
+    pub session_id: String,
+    /// None when a terminal emulator started claude; the detector finds it by session ID
+    pub pid: Option<u32>,
+    pub target: LaunchTarget,
+}
+
+/// Initial prompts of launched sessions, shown until their transcript has one
+static PROMPTS: Mutex<Option<HashMap<String, String>>> = Mutex::new(None);
+
+/// IDs of the sessions started by [`launch`]
+static LAUNCHED: Mutex<Option<HashSet<String>>> = Mutex::new(None);
+
+/// Start claude as described by `request`
+pub fn launch(request: &LaunchRequest, config: &LaunchConfig) -> Result<LaunchedSession, LaunchError> {
+    if !request.project_dir.is_dir() {
+        return Err(LaunchError::MissingProjectDir(request.project_dir.clone()));
+    }
+
+    let session_id = new_session_id()?;
+    let args = claude_args(request, &session_id);
+    let pid = start(request.target, &request.project_dir, &args, config)?;
+    eprintln!(
+        "[launch] Started session {} ({:?}, PID {:?})",
+        session_id, request.target, pid
+    );
+
+    LAUNCHED
+        .lock()
+        .unwrap_or_else(|p| p.into_inner())
+        .get_or_insert_with(HashSet::new)
+        .insert(session_id.clone());
+
+    if let Some(prompt) = &request.prompt {
+        let mut prompts = PROMPTS.lock().unwrap_or_else(|p| p.into_inner());
+        prompts
+            .get_or_insert_with(HashMap::new)
+            .insert(session_id.clone(), prompt.clone());
+    }
+
+    Ok(LaunchedSession {
+        session_id,
+        pid,
+        target: request.target,
+    })
+}
+
+/// Continue a finished session with `claude --resume` where it last ran
+///
+/// Claude Code looks the session up under the project directory of the
+/// working directory it starts in, so this resumes in the recorded cwd the
+/// transcript's project directory was named after. Claude Code keeps the
+/// session ID when resuming, so the custom name, title and other metadata
+/// stored under it carry over.
+pub fn resume(
+    session_id: &str,
+    transcript: &Path,
+    target: LaunchTarget,
+    config: &LaunchConfig,
+) -> Result<LaunchedSession, LaunchError> {
+    let entries = parse_all_entries(transcript).map_err(LaunchError::Transcript)?;
+    let cwd = recorded_cwd(&entries, transcript).ok_or(LaunchError::NoRecordedCwd)?;
+    if !cwd.is_dir() {
+        return Err(LaunchError::MissingProjectDir(cwd));
+    }
+
+    let args = vec!["--resume".to_string(), session_id.to_string()];
+    let pid = start(target, &cwd, &args, config)?;
+    eprintln!(
+        "[launch] Resumed session {} in {} ({:?}, PID {:?})",
+        session_id,
+        cwd.display(),
+        target,
+        pid
+    );
+
+    Ok(LaunchedSession {
+        session_id: session_id.to_string(),
+        pid,
+        target,
+    })
+}
+
+/// Directory the session was started in
+///
+/// Sessions can `cd` into subdirectories, so this is the recorded cwd that
+/// encodes to the transcript's project directory, or else the first one.
+fn recorded_cwd(entries: &[SessionEntry], transcript: &Path) -> Option<PathBuf> {
+    let project_dir = transcript.parent()?.file_name()?.to_string_lossy();
+    let cwds: Vec<&PathBuf> = entries
+        .iter()
+        .filter_map(|entry| entry.base()?.cwd.as_ref())
+        .collect();
+    cwds.iter()
+        .find(|cwd| encode_project_dir(cwd) == project_dir)
+        .or(cwds.first())
+        .map(|cwd| cwd.to_path_buf())
+}
+
+/// Run claude with `args` in `dir` on `target`, returning its pid when known
+fn start(
+    target: LaunchTarget,
+    dir: &Path,
+    args: &[String],
+    config: &LaunchConfig,
+) -> Result<Option<u32>, LaunchError> {
+    Ok(match target {
+        LaunchTarget::Tmux => Some(launch_in_tmux(config, dir, args)?),
+        LaunchTarget::Terminal => {
+            launch_in_terminal(config, dir, args)?;
+            None
+        }
+        LaunchTarget::Detached => Some(pty::spawn(&config.claude_command, args, dir)?),
+    })
+}
+
+/// Whether c9watch itself started the session with this ID
+pub fn was_launched(session_id: &str) -> bool {
+    let launched = LAUNCHED.lock().unwrap_or_else(|p| p.into_inner());
+    launched.as_ref().is_some_and(|ids| ids.contains(session_id))
+}
+
+/// Prompt a session was launched with, if it was launched from here
+pub fn initial_prompt(session_id: &str) -> Option<String> {
+    let prompts = PROMPTS.lock().unwrap_or_else(|p| p.into_inner());
+    prompts.as_ref()?.get(session_id).cloned()
+}
+
+/// Arguments for `claude`, the initial prompt last
+fn claude_args(request: &LaunchRequest, session_id: &str) -> Vec<String> {
+    let mut args = vec!["--session-id".to_string(), session_id.to_string()];
+    if let Some(model) = &request.model {
+        args.extend(["--model".to_string(), model.clone()]);
+    }
+    if let Some(mode) = request.permission_mode {
+        args.extend(["--permission-mode".to_string(), mode.as_arg().to_string()]);
+    }
+    if let Some(prompt) = &request.prompt {
+        // A prompt starting with a dash must not be read as an option
+        args.extend(["--".to_string(), prompt.clone()]);
+    }
+    args
+}
+
+/// Open a tmux window running claude and return claude's pid
+///
+/// The window goes into `config.tmux_session`, which is created (detached)
+/// when it doesn't exist yet.
+fn launch_in_tmux(config: &LaunchConfig, dir: &Path, args: &[String]) -> Result<u32, LaunchError> {
+    let session_exists = Command::new("tmux")
+        .args(["has-session", "-t", &config.tmux_session])
+        .output()
+        .is_ok_and(|output| output.status.success());
+
+    let mut tmux = Command::new("tmux");
+    if session_exists {
+        tmux.args(["new-window", "-t", &config.tmux_session]);
+    } else {
+        tmux.args(["new-session", "-d", "-s", &config.tmux_session]);
+    }
+    // `exec` makes claude the pane's process, so the pane pid is claude's
+    let command = format!("exec {}", shell_command(&config.claude_command, args));
+    tmux.arg("-c")
+        .arg(dir)
+        .args(["-P", "-F", "#{pane_pid}"])
+        .arg(command);
+
+    let stdout = run(&mut tmux, "tmux")?;
+    stdout.trim().parse().map_err(|_| LaunchError::Failed {
+        program: "tmux".to_string(),
+        stderr: format!("unexpected pane pid {:?}", stdout.trim()),
+    })
+}
+
+/// Open a terminal emulator window running claude
+fn launch_in_terminal(config: &LaunchConfig, dir: &Path, args: &[String]) -> Result<(), LaunchError> {
+    let script = format!(
+        "cd {} && exec {}",
+        shell_quote(&dir.to_string_lossy()),
+        shell_command(&config.claude_command, args)
+    );
+
+    let Some((program, prefix)) = config.terminal_command.split_first() else {
+        #[cfg(target_os = "macos")]
+        {
+            let apple_script = format!(
+                "tell application \"Terminal\" to do script \"{}\"",
+                script.replace('\\', "\\\\").replace('"', "\\\"")
+            );
+            run(Command::new("osascript").args(["-e", &apple_script]), "osascript")?;
+            return Ok(());
+        }
+        #[cfg(not(target_os = "macos"))]
+        return Err(LaunchError::NoTerminal);
+    };
+
+    // The terminal runs `sh -c <script>` after its own arguments (e.g. `-e`)
+    Command::new(program)
+        .args(prefix)
+        .args(["sh", "-c", &script])
+        .current_dir(dir)
+        .spawn()
+        .map_err(|source| LaunchError::Spawn {
+            program: program.clone(),
+            source,
+        })?;
+    Ok(())
+}
+
+/// Run a short-lived command, returning its stdout
+fn run(command: &mut Command, program: &str) -> Result<String, LaunchError> {
+    let output = command.output().map_err(|source| LaunchError::Spawn {
+        program: program.to_string(),
+        source,
+    })?;
+    if !output.status.success() {
+        return Err(LaunchError::Failed {
+            program: program.to_string(),
+            stderr: String::from_utf8_lossy(&output.stderr).trim().to_string(),
+        });
+    }
+    Ok(String::from_utf8_lossy(&output.stdout).to_string())
+}
+
+fn shell_command(program: &str, args: &[String]) -> String {
+    std::iter::once(program)
+        .chain(args.iter().map(String::as_str))
+        .map(shell_quote)
+        .collect::<Vec<_>>()
+        .join(" ")
+}
+
+/// Quote `word` for a POSIX shell
+fn shell_quote(word: &str) -> String {
+    let safe = !word.is_empty()
+        && word
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || "-_./=:@%+,".contains(c));
+    if safe {
+        word.to_string()
+    } else {
+        format!("'{}'", word.replace('\'', r"'\''"))
+    }
+}
+
+/// Random (version 4) UUID, the format Claude Code uses for session IDs
+fn new_session_id() -> Result<String, LaunchError> {
+    let mut bytes = [0u8; 16];
+    std::fs::File::open("/dev/urandom")
+        .and_then(|mut random| random.read_exact(&mut bytes))
+        .map_err(LaunchError::SessionId)?;
+    bytes[6] = (bytes[6] & 0x0f) | 0x40;
+    bytes[8] = (bytes[8] & 0x3f) | 0x80;
+
+    let hex: String = bytes.iter().map(|b| format!("{:02x}", b)).collect();
+    Ok(format!(
+        "{}-{}-{}-{}-{}",
+        &hex[0..8],
+        &hex[8..12],
+        &hex[12..16],
+        &hex[16..20],
+        &hex[20..32]
+    ))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_claude_args() {
+        let request = LaunchRequest {
+            project_dir: PathBuf::from("/work/api"),
+            prompt: Some("-fix the 'login' bug".to_string()),
+            model: Some("opus".to_string()),
+            permission_mode: Some(PermissionMode::AcceptEdits),
+            target: LaunchTarget::Tmux,
+        };
+
+        let args = claude_args(&request, "id");
+        assert_eq!(
+            args,
+            vec![
+                "--session-id",
+                "id",
+                "--model",
+                "opus",
+                "--permission-mode",
+                "acceptEdits",
+                "--",
+                "-fix the 'login' bug"
+            ]
+        );
+        assert_eq!(
+            shell_command("claude", &args[6..]),
+            r#"claude -- '-fix the '\''login'\'' bug'"#
+        );
+    }
+
+    #[test]
+    fn test_recorded_cwd_is_the_project_directory() {
+        use crate::session::parser::parse_jsonl_entries;
+
+        let user = |cwd: &str| {
+            format!(
+                r#"{{"type":"user","uuid":"u","timestamp":"2026-03-01T12:00:00Z","cwd":"{}","message":{{"role":"user","content":"hi"}}}}"#,
+                cwd
+            )
+        };
+        let entries = parse_jsonl_entries(vec![
+            user("/work/api/server"),
+            user("/work/api"),
+            user("/work/api/server"),
+            r#"{"type":"summary","summary":"Fixing login","leafUuid":"u"}"#.to_string(),
+        ]);
+        let transcript = Path::new("/home/dev/.claude/projects/-work-api/s1.jsonl");
+        assert_eq!(recorded_cwd(&entries, transcript), Some(PathBuf::from("/work/api")));
+
+        // Without a match, where the session started
+        let elsewhere = Path::new("/tmp/s1.jsonl");
+        assert_eq!(recorded_cwd(&entries, elsewhere), Some(PathBuf::from("/work/api/server")));
+        assert_eq!(recorded_cwd(&[], transcript), None);
+    }
+
+    #[test]
+    fn test_new_session_id() {
+        let id = new_session_id().unwrap();
+        assert_eq!(id.len(), 36);
+        assert_eq!(id.as_bytes()[14], b'4');
+        assert_ne!(id, new_session_id().unwrap());
+    }
+
+    #[test]
+    #[cfg(target_os = "linux")]
+    fn test_detached_launch_is_detected() {
+        use crate::session::SessionDetector;
+        use crate::test_support::{claude_processes_lock, SimulatedClaudeHome};
+
+        let _processes = claude_processes_lock();
+        let home = SimulatedClaudeHome::new("launch");
+        let project = home.workspace("api");
+        let config = LaunchConfig {
+            claude_command: home.fake_cli().unwrap().to_string_lossy().to_string(),
+            ..LaunchConfig::default()
+        };
+        let request = LaunchRequest {
+            project_dir: project.clone(),
+            prompt: Some("Add a health check".to_string()),
+            model: None,
+            permission_mode: None,
+            target: LaunchTarget::Detached,
+        };
+
+        let launched = launch(&request, &config).unwrap();
+        let pid = launched.pid.unwrap();
+        assert_eq!(initial_prompt(&launched.session_id).as_deref(), Some("Add a health check"));
+
+        // Known by its session ID before it wrote anything
+        let mut detected = Vec::new();
+        for _ in 0..50 {
+            detected = SessionDetector::new(&home.dirs()).unwrap().detect_sessions().unwrap();
+            if detected.iter().any(|s| s.pid == pid) {
+                break;
+            }
+            std::thread::sleep(std::time::Duration::from_millis(20));
+        }
+        let session = detected.iter().find(|s| s.pid == pid).unwrap();
+        assert_eq!(session.session_id.as_deref(), Some(launched.session_id.as_str()));
+        assert!(session.id_from_args);
+        assert_eq!(session.cwd, project);
+
+        crate::actions::signal_process(pid, crate::actions::ProcessSignal::Kill).unwrap();
+    }
+
+    #[test]
+    #[cfg(target_os = "linux")]
+    fn test_resumed_session_keeps_its_id() {
+        use crate::session::SessionDetector;
+        use crate::test_support::{claude_processes_lock, SimulatedClaudeHome};
+
+        let _processes = claude_processes_lock();
+        let home = SimulatedClaudeHome::new("resume");
+        let project = home.workspace("web");
+        let session_id = "5f0c6a9e-8d4b-4c1e-a2f3-0b9d7e6c5a41";
+        let project_dir = home.dirs().projects_dirs()[0].join(encode_project_dir(&project));
+        std::fs::create_dir_all(&project_dir).unwrap();
+        let transcript = project_dir.join(format!("{}.jsonl", session_id));
+        std::fs::write(
+            &transcript,
+            format!(
+                r#"{{"type":"user","uuid":"u","timestamp":"2026-03-01T12:00:00Z","cwd":"{}","message":{{"role":"user","content":"hi"}}}}"#,
+                project.display()
+            ) + "\n",
+        )
+        .unwrap();
+        let config = LaunchConfig {
+            claude_command: home.fake_cli().unwrap().to_string_lossy().to_string(),
+            ..LaunchConfig::default()
+        };
+
+        let resumed = resume(session_id, &transcript, LaunchTarget::Detached, &config).unwrap();
+        let pid = resumed.pid.unwrap();
+
+        let mut detected = Vec::new();
+        for _ in 0..50 {
+            detected = SessionDetector::new(&home.dirs()).unwrap().detect_sessions().unwrap();
+            if detected.iter().any(|s| s.pid == pid) {
+                break;
+            }
+            std::thread::sleep(std::time::Duration::from_millis(20));
+        }
+        let session = detected.iter().find(|s| s.pid == pid).unwrap();
+        assert_eq!(session.session_id.as_deref(), Some(session_id));
+        assert_eq!(session.project_path, project_dir);
+
+        crate::actions::signal_process(pid, crate::actions::ProcessSignal::Kill).unwrap();
+    }
+}
@@ -1,13 +1,36 @@
 pub mod actions;
+pub mod attention;
+pub mod config;
+pub mod launch;
 pub mod polling;
+pub mod pty;
+pub mod replay;
 pub mod session;
+pub mod store;
+#[cfg(all(test, target_os = "linux"))]
+pub(crate) mod test_support;
 
-use actions::{open_session as open_session_action, stop_session as stop_session_action};
-use polling::{detect_and_enrich_sessions, start_polling, Session};
-use session::{extract_messages, parse_all_entries, MessageType};
+use actions::{
+    interrupt_session as interrupt_session_action, open_session as open_session_action,
+    signal_child, stop_session as stop_session_action, validate_pid, InterruptMethod,
+    ProcessSignal, StopOutcome,
+};
+use attention::AttentionItem;
+use config::AppConfig;
+use launch::{LaunchRequest, LaunchTarget, LaunchedSession};
+use polling::{detect_and_enrich_sessions, start_polling, Session, SessionFilter, VersionUsage};
+use replay::ReplayInfo;
+use session::{
+    child_processes, extract_messages, files_touched, parse_all_entries,
+    ChildProcess, FileConflict, FilesTouched, MessageType, ParseDiagnostics, SessionEntry,
+    SessionStatus,
+};
 use serde::Serialize;
+use store::{NotificationRecord, StatusTransition};
+use std::path::PathBuf;
 use std::time::Duration;
 use tauri::{
+    menu::{Menu, MenuItem},
     tray::{MouseButton, MouseButtonState, TrayIconBuilder, TrayIconEvent},
     AppHandle, Emitter, Manager,
 };
@@ -18,76 +41,251 @@ fn greet(name: &str) -> String {
     format!("Hello, {}! You've been greeted from Rust!", name)
 }
 
-/// Get all active Claude sessions
+/// Get all active Claude sessions, optionally narrowed down by a filter
 #[tauri::command]
-async fn get_sessions() -> Result<Vec<Session>, String> {
+async fn get_sessions(filter: Option<SessionFilter>) -> Result<Vec<Session>, String> {
     // Use the same detection logic as the polling loop
-    polling::detect_and_enrich_sessions()
+    let mut sessions = polling::detect_and_enrich_sessions()?;
+    sessions.extend(replay::current_session());
+
+    Ok(match filter {
+        Some(filter) => sessions.into_iter().filter(|s| filter.matches(s)).collect(),
+        None => sessions,
+    })
+}
+
+/// Get how many running sessions use each Claude Code version, newest first
+#[tauri::command]
+async fn get_version_distribution() -> Result<Vec<VersionUsage>, String> {
+    let sessions = polling::detect_and_enrich_sessions()?;
+    Ok(polling::version_distribution(&sessions))
+}
+
+/// Load every entry of a session, live or replayed
+fn session_entries(session_id: &str) -> Result<Vec<SessionEntry>, String> {
+    // The replayed session only shows what has been "written" so far
+    if let Some(entries) = replay::entries_for(session_id) {
+        return Ok(entries);
+    }
+
+    // Search for the JSONL file directly across all project directories of every root
+    let session_file = session::claude_dirs()
+        .find_session_file(session_id)
+        .ok_or_else(|| format!("Session {} not found in any project directory", session_id))?;
+
+    // Found it - parse the full session file
+    parse_all_entries(&session_file).map_err(|e| format!("Failed to parse session file: {}", e))
 }
 
 /// Get the conversation history for a specific session
 #[tauri::command]
 async fn get_conversation(session_id: String) -> Result<Conversation, String> {
-    // Find the session's project directory
-    let home_dir = dirs::home_dir().ok_or("Failed to get home directory")?;
-    let claude_projects_dir = home_dir.join(".claude").join("projects");
+    let entries = session_entries(&session_id)?;
+    let messages = extract_messages(&entries);
 
-    // Fast path: search for the JSONL file directly across all project directories
-    let entries = std::fs::read_dir(&claude_projects_dir)
-        .map_err(|e| format!("Failed to read projects directory: {}", e))?;
+    // Convert to frontend format
+    let conversation_messages: Vec<ConversationMessage> = messages
+        .into_iter()
+        .map(|(timestamp, msg_type, content)| ConversationMessage {
+            timestamp,
+            message_type: msg_type,
+            content,
+        })
+        .collect();
 
-    let session_filename = format!("{}.jsonl", session_id);
+    Ok(Conversation {
+        session_id,
+        messages: conversation_messages,
+    })
+}
 
-    for entry in entries.flatten() {
-        let project_path = entry.path();
-        if !project_path.is_dir() {
-            continue;
-        }
+/// Get the files a session created, edited and deleted
+#[tauri::command]
+async fn get_files_touched(session_id: String) -> Result<FilesTouched, String> {
+    Ok(files_touched(&session_entries(&session_id)?))
+}
 
-        // Check if this project contains the session file directly
-        let session_file = project_path.join(&session_filename);
-        if session_file.exists() {
-            // Found it - parse the full session file for conversation view
-            let entries = parse_all_entries(&session_file)
-                .map_err(|e| format!("Failed to parse session file: {}", e))?;
-
-            let messages = extract_messages(&entries);
-
-            // Convert to frontend format
-            let conversation_messages: Vec<ConversationMessage> = messages
-                .into_iter()
-                .map(|(timestamp, msg_type, content)| ConversationMessage {
-                    timestamp,
-                    message_type: msg_type,
-                    content,
-                })
-                .collect();
+/// Get the files that more than one live session on the same tree and branch has changed
+#[tauri::command]
+async fn get_file_conflicts() -> Result<Vec<FileConflict>, String> {
+    Ok(polling::current_conflicts(&detect_and_enrich_sessions()?))
+}
 
-            return Ok(Conversation {
-                session_id,
-                messages: conversation_messages,
-            });
-        }
+/// Stop a session by process ID, escalating until it has actually exited
+#[tauri::command]
+async fn stop_session(app: AppHandle, pid: u32) -> Result<StopOutcome, String> {
+    validate_pid(pid)?;
+    // Escalation sleeps between signals, so keep it off the async runtime
+    let outcome = tauri::async_runtime::spawn_blocking(move || {
+        stop_session_action(pid, &config::current().stop)
+    })
+    .await
+    .map_err(|e| e.to_string())??;
+
+    // Emit updated sessions immediately so UI reflects the change
+    if let Ok(sessions) = detect_and_enrich_sessions() {
+        polling::emit_sessions(&app, sessions);
+    }
+
+    Ok(outcome)
+}
+
+/// How long `interrupt_session` waits for the transcript to show the turn ended
+const INTERRUPT_CONFIRM_TIMEOUT: Duration = Duration::from_secs(5);
+
+/// Result of interrupting a session's turn
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+struct InterruptOutcome {
+    method: InterruptMethod,
+    /// The transcript showed the session back at the prompt in time
+    confirmed: bool,
+    /// Status as last seen, None if the session exited
+    status: Option<SessionStatus>,
+}
+
+/// Stop what a session is doing (like pressing Esc) without ending it
+#[tauri::command]
+async fn interrupt_session(app: AppHandle, session_id: String) -> Result<InterruptOutcome, String> {
+    let session = detect_and_enrich_sessions()?
+        .into_iter()
+        .find(|s| s.id == session_id)
+        .ok_or_else(|| format!("Session not found: {}", session_id))?;
+    if session.status == SessionStatus::WaitingForInput {
+        return Err(format!("Session {} is not in a turn", session_id));
+    }
+
+    let method = interrupt_session_action(session.pid)?;
+    // Waiting polls the transcript with sleeps, so keep it off the async runtime
+    let status = tauri::async_runtime::spawn_blocking(move || {
+        polling::wait_for_status(
+            &session_id,
+            SessionStatus::WaitingForInput,
+            INTERRUPT_CONFIRM_TIMEOUT,
+        )
+    })
+    .await
+    .map_err(|e| e.to_string())??
+    .map(|s| s.status);
+
+    if let Ok(sessions) = detect_and_enrich_sessions() {
+        polling::emit_sessions(&app, sessions);
     }
 
-    Err(format!("Session {} not found in any project directory", session_id))
+    Ok(InterruptOutcome {
+        method,
+        confirmed: status == Some(SessionStatus::WaitingForInput),
+        status,
+    })
 }
 
-/// Stop a session by process ID
+/// List the processes a session spawned, e.g. dev servers started by its Bash tool
 #[tauri::command]
-async fn stop_session(app: AppHandle, pid: u32) -> Result<(), String> {
-    // Stop the session
-    stop_session_action(pid)?;
+async fn get_child_processes(pid: u32) -> Result<Vec<ChildProcess>, String> {
+    child_processes(pid).ok_or_else(|| format!("Session process {} is not running", pid))
+}
 
-    // Wait a brief moment for the process to terminate
-    std::thread::sleep(Duration::from_millis(300));
+/// Signal one process a session spawned without stopping the session
+#[tauri::command]
+async fn signal_child_process(
+    session_pid: u32,
+    child_pid: u32,
+    signal: ProcessSignal,
+) -> Result<(), String> {
+    signal_child(session_pid, child_pid, signal)
+}
 
-    // Emit updated sessions immediately so UI reflects the change
+/// Start a new Claude Code session in tmux, a terminal window or a detached pty
+#[tauri::command]
+async fn launch_session(app: AppHandle, request: LaunchRequest) -> Result<LaunchedSession, String> {
+    let launched =
+        launch::launch(&request, &config::current().launch).map_err(|e| e.to_string())?;
+
+    // Show the new card (as Connecting) without waiting for the next poll
     if let Ok(sessions) = detect_and_enrich_sessions() {
-        let _ = app.emit("sessions-updated", &sessions);
+        polling::emit_sessions(&app, sessions);
     }
 
-    Ok(())
+    Ok(launched)
+}
+
+/// Continue a finished session with `claude --resume` in the directory it ran in
+#[tauri::command]
+async fn resume_session(
+    app: AppHandle,
+    session_id: String,
+    target: LaunchTarget,
+) -> Result<LaunchedSession, String> {
+    if detect_and_enrich_sessions()?.iter().any(|s| s.id == session_id) {
+        return Err(format!("Session {} is still running", session_id));
+    }
+    let transcript = session::claude_dirs()
+        .find_session_file(&session_id)
+        .ok_or_else(|| format!("Session {} not found in any project directory", session_id))?;
+
+    let resumed = launch::resume(&session_id, &transcript, target, &config::current().launch)
+        .map_err(|e| e.to_string())?;
+
+    if let Ok(sessions) = detect_and_enrich_sessions() {
+        polling::emit_sessions(&app, sessions);
+    }
+
+    Ok(resumed)
+}
+
+/// Type into the terminal of a session c9watch launched detached
+///
+/// `data` is sent as-is, so keys are their terminal sequences ("\r" for Enter, "\x1b" for Escape).
+#[tauri::command]
+async fn pty_write(pid: u32, data: String) -> Result<(), String> {
+    pty::write_input(pid, data.as_bytes()).map_err(|e| e.to_string())
+}
+
+/// Resize the terminal of a session c9watch launched detached
+#[tauri::command]
+async fn pty_resize(pid: u32, cols: u16, rows: u16) -> Result<(), String> {
+    pty::resize(pid, cols, rows).map_err(|e| e.to_string())
+}
+
+/// Get the recent output of a detached session's terminal, to fill a newly opened pane
+#[tauri::command]
+async fn get_pty_scrollback(pid: u32) -> Result<String, String> {
+    pty::scrollback(pid).map_err(|e| e.to_string())
+}
+
+/// Rank the sessions waiting on the user, the one to handle first at the top
+#[tauri::command]
+async fn get_attention_queue() -> Result<Vec<AttentionItem>, String> {
+    Ok(attention::current_queue(&detect_and_enrich_sessions()?))
+}
+
+/// Open the top session of the attention queue, or the main window when none is waiting
+fn jump_to_next_session(app: &AppHandle) {
+    let next = detect_and_enrich_sessions()
+        .map(|sessions| attention::current_queue(&sessions).into_iter().next());
+
+    match next {
+        // Detached sessions run on our own pty, so there is no terminal to raise
+        Ok(Some(item)) if pty::is_detached(item.pid) => {
+            if let Some(window) = app.get_webview_window("main") {
+                let _ = window.show();
+                let _ = window.set_focus();
+            }
+            let _ = app.emit("expand-session", &item.session_id);
+        }
+        Ok(Some(item)) => {
+            if let Err(e) = open_session_action(item.pid, item.project_path) {
+                eprintln!("[tray] Failed to open session {}: {}", item.session_id, e);
+            }
+        }
+        Ok(None) => {
+            if let Some(window) = app.get_webview_window("main") {
+                let _ = window.show();
+                let _ = window.set_focus();
+            }
+        }
+        Err(e) => eprintln!("[tray] Failed to detect sessions: {}", e),
+    }
 }
 
 /// Open a session in its parent application
@@ -99,18 +297,138 @@ async fn open_session(pid: u32, project_path: String) -> Result<(), String> {
 /// Rename a session title
 #[tauri::command]
 async fn rename_session(app: AppHandle, session_id: String, new_name: String) -> Result<(), String> {
-    let mut custom_titles = session::CustomTitles::load();
-    custom_titles.set(session_id, new_name);
-    custom_titles.save()?;
+    let store = store::global()?;
+    store
+        .set_custom_title(&session_id, &new_name)
+        .map_err(|e| e.to_string())?;
 
     // Emit updated sessions immediately
     if let Ok(sessions) = detect_and_enrich_sessions() {
-        let _ = app.emit("sessions-updated", &sessions);
+        polling::emit_sessions(&app, sessions);
     }
 
     Ok(())
 }
 
+/// Update the tags, note, pinned flag and/or priority of a session
+///
+/// Only the fields that are provided are changed. An empty note clears it.
+#[tauri::command]
+async fn update_session_metadata(
+    app: AppHandle,
+    session_id: String,
+    tags: Option<Vec<String>>,
+    note: Option<String>,
+    pinned: Option<bool>,
+    priority: Option<i32>,
+) -> Result<(), String> {
+    let store = store::global()?;
+
+    if let Some(tags) = tags {
+        let mut tags: Vec<String> = tags
+            .iter()
+            .map(|t| t.trim().to_string())
+            .filter(|t| !t.is_empty())
+            .collect();
+        tags.sort();
+        tags.dedup();
+        store.set_tags(&session_id, &tags).map_err(|e| e.to_string())?;
+    }
+    if let Some(note) = note {
+        let note = note.trim();
+        let note = if note.is_empty() { None } else { Some(note) };
+        store.set_note(&session_id, note).map_err(|e| e.to_string())?;
+    }
+    if let Some(pinned) = pinned {
+        store.set_pinned(&session_id, pinned).map_err(|e| e.to_string())?;
+    }
+    if let Some(priority) = priority {
+        store
+            .set_priority(&session_id, priority)
+            .map_err(|e| e.to_string())?;
+    }
+
+    // Emit updated sessions immediately
+    if let Ok(sessions) = detect_and_enrich_sessions() {
+        polling::emit_sessions(&app, sessions);
+    }
+
+    Ok(())
+}
+
+/// Get the recorded status transitions of a session, oldest first
+#[tauri::command]
+async fn get_status_history(session_id: String) -> Result<Vec<StatusTransition>, String> {
+    store::global()?
+        .status_history(&session_id)
+        .map_err(|e| e.to_string())
+}
+
+/// Get the most recent notifications, newest first
+#[tauri::command]
+async fn get_notification_history(limit: Option<u32>) -> Result<Vec<NotificationRecord>, String> {
+    store::global()?
+        .notification_history(limit.unwrap_or(50))
+        .map_err(|e| e.to_string())
+}
+
+/// Get the active app configuration
+#[tauri::command]
+async fn get_config() -> Result<AppConfig, String> {
+    Ok((*config::current()).clone())
+}
+
+/// Validate, save and apply a new app configuration
+#[tauri::command]
+async fn set_config(app: AppHandle, config: AppConfig) -> Result<AppConfig, String> {
+    let updated = config::update(config).map_err(|e| e.to_string())?;
+    let _ = app.emit("config-updated", &*updated);
+    Ok((*updated).clone())
+}
+
+/// Replay a session transcript (or the built-in demo) as a live synthetic session
+#[tauri::command]
+async fn start_replay(
+    app: AppHandle,
+    path: Option<String>,
+    speed: Option<f64>,
+    looping: Option<bool>,
+) -> Result<ReplayInfo, String> {
+    let path = path.map(PathBuf::from);
+    replay::start(app, path.as_deref(), speed.unwrap_or(1.0), looping.unwrap_or(false))
+}
+
+/// Stop the running replay
+#[tauri::command]
+async fn stop_replay(app: AppHandle) -> Result<(), String> {
+    replay::stop(&app);
+    Ok(())
+}
+
+/// Change the replay speed (0 pauses)
+#[tauri::command]
+async fn set_replay_speed(speed: f64) -> Result<ReplayInfo, String> {
+    replay::set_speed(speed)
+}
+
+/// Jump to a point in the replayed transcript, in ms from its start
+#[tauri::command]
+async fn seek_replay(app: AppHandle, offset_ms: u64) -> Result<ReplayInfo, String> {
+    replay::seek(&app, offset_ms)
+}
+
+/// Get the state of the running replay, if any
+#[tauri::command]
+async fn get_replay_state() -> Result<Option<ReplayInfo>, String> {
+    Ok(replay::info())
+}
+
+/// Get the JSONL lines and content blocks the parser didn't understand
+#[tauri::command]
+async fn get_parse_diagnostics() -> Result<ParseDiagnostics, String> {
+    Ok(session::diagnostics::snapshot())
+}
+
 /// Show and focus the main application window
 #[tauri::command]
 async fn show_main_window(app: AppHandle) -> Result<(), String> {
@@ -154,12 +472,36 @@ pub fn run() {
             // Start the polling loop when the app starts
             start_polling(app.handle().clone());
 
-            // Create the tray icon with click handler
+            // Stream detached sessions' terminals to the frontend
+            let pty_handle = app.handle().clone();
+            pty::set_listener(move |event| {
+                let result = match event {
+                    pty::PtyEvent::Output(output) => pty_handle.emit("pty-output", &output),
+                    pty::PtyEvent::Exit(exit) => pty_handle.emit("pty-exit", &exit),
+                };
+                if let Err(e) = result {
+                    eprintln!("[pty] Failed to emit terminal event: {}", e);
+                }
+            });
+
+            // Create the tray icon with click handler; the menu opens on right click
             let app_handle = app.handle().clone();
+            let jump_to_next =
+                MenuItem::with_id(app, "jump-to-next", "Jump to Next Session", true, None::<&str>)?;
+            let tray_menu = Menu::with_items(app, &[&jump_to_next])?;
             TrayIconBuilder::new()
                 .icon(app.default_window_icon().unwrap().clone())
                 .icon_as_template(true)
                 .tooltip("c9watch")
+                .menu(&tray_menu)
+                .show_menu_on_left_click(false)
+                .on_menu_event(|app, event| {
+                    if event.id().as_ref() == "jump-to-next" {
+                        let app = app.clone();
+                        // Detection reads every transcript, so keep it off the event loop
+                        std::thread::spawn(move || jump_to_next_session(&app));
+                    }
+                })
                 .on_tray_icon_event(move |_tray, event| {
                     if let TrayIconEvent::Click {
                         button: MouseButton::Left,
@@ -182,9 +524,32 @@ pub fn run() {
             greet,
             get_sessions,
             get_conversation,
+            get_files_touched,
+            get_file_conflicts,
             stop_session,
+            launch_session,
+            resume_session,
+            interrupt_session,
+            get_child_processes,
+            signal_child_process,
+            pty_write,
+            pty_resize,
+            get_pty_scrollback,
+            get_attention_queue,
             open_session,
             rename_session,
+            update_session_metadata,
+            get_status_history,
+            get_notification_history,
+            get_config,
+            set_config,
+            start_replay,
+            stop_replay,
+            set_replay_speed,
+            seek_replay,
+            get_replay_state,
+            get_parse_diagnostics,
+            get_version_distribution,
             show_main_window
         ])
         .run(tauri::generate_context!())
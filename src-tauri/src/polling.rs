@@ -1,15 +1,23 @@
+use crate::config;
+use crate::session::context::crossed_threshold;
+use crate::session::files::retain_file_trackers;
+use crate::session::history::retain_histories;
 use crate::session::{
-    determine_status, get_pending_tool_name, parse_last_n_entries, parse_sessions_index,
-    SessionDetector, SessionStatus,
+    claude_dirs, context_usage, find_conflicts, git_info, latest_resources, parse_last_n_entries,
+    parse_sessions_index, permission_checker, sample_resources, scan_files_touched, scan_history,
+    ClaudeDirs, Clock, ContextUsage, FileConflict, FilesTouched, GitInfo, ModelSwitch,
+    PendingTool, ResourceBudget, ResourceUsage, SessionDetector,
+    SessionEntry, SessionStatus, StatusContext, SystemClock,
 };
+use crate::store::{SessionMeta, SessionRecord, Store};
 use chrono::{DateTime, Utc};
-use serde::Serialize;
+use serde::{Deserialize, Serialize};
 use std::collections::hash_map::DefaultHasher;
 use std::collections::{HashMap, HashSet};
 use std::fs::File;
 use std::hash::{Hash, Hasher};
 use std::io::{BufRead, BufReader};
-use std::path::Path;
+use std::path::{Path, PathBuf};
 use std::sync::{Arc, Mutex};
 use std::thread;
 use std::time::Duration;
@@ -17,7 +25,7 @@ use tauri::{AppHandle, Emitter};
 use tauri_plugin_notification::NotificationExt;
 
 /// Combined session information for the frontend
-#[derive(Debug, Clone, Serialize)]
+#[derive(Debug, Clone, PartialEq, Serialize)]
 #[serde(rename_all = "camelCase")]
 pub struct Session {
     pub id: String,
@@ -33,6 +41,117 @@ pub struct Session {
     pub status: SessionStatus,
     pub latest_message: String,
     pub pending_tool_name: Option<String>,
+    /// Every tool call waiting for permission, in the order Claude made them
+    pub pending_tools: Vec<PendingTool>,
+    pub tags: Vec<String>,
+    pub note: Option<String>,
+    pub pinned: bool,
+    pub priority: i32,
+    /// Context window fill as of the latest assistant message
+    pub context: Option<ContextUsage>,
+    /// Times the conversation was compacted
+    pub compaction_count: u32,
+    /// Model of the latest assistant message
+    pub model: Option<String>,
+    /// Models in the order they were used, starting with the initial one
+    pub model_switches: Vec<ModelSwitch>,
+    /// Claude Code version that wrote the latest entry
+    pub cli_version: Option<String>,
+    /// Files this session and others on the same working tree and branch both changed
+    pub conflicts: Vec<FileConflict>,
+    /// Repository, worktree and working state of the session's cwd
+    pub git: Option<GitInfo>,
+    /// CPU, memory and uptime of the claude process and its child processes
+    pub resources: Option<ResourceUsage>,
+    /// Runs on a terminal owned by c9watch that the app can show and type into
+    pub embedded_terminal: bool,
+}
+
+/// Payload of the `budget-exceeded` event
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct BudgetAlert {
+    pub session_id: String,
+    pub budget: ResourceBudget,
+    pub usage: ResourceUsage,
+}
+
+/// Optional criteria for narrowing down the session list
+///
+/// Every field that is set must match; an empty filter matches everything.
+#[derive(Debug, Clone, Default, Deserialize)]
+#[serde(rename_all = "camelCase", default)]
+pub struct SessionFilter {
+    /// Sessions must carry all of these tags
+    pub tags: Vec<String>,
+    pub pinned: Option<bool>,
+    /// Sessions must be in one of these statuses
+    pub statuses: Vec<SessionStatus>,
+    pub project_path: Option<String>,
+    pub min_priority: Option<i32>,
+}
+
+impl SessionFilter {
+    pub fn matches(&self, session: &Session) -> bool {
+        self.tags.iter().all(|tag| session.tags.contains(tag))
+            && self.pinned.is_none_or(|pinned| session.pinned == pinned)
+            && (self.statuses.is_empty() || self.statuses.contains(&session.status))
+            && self
+                .project_path
+                .as_ref()
+                .is_none_or(|path| &session.project_path == path)
+            && self.min_priority.is_none_or(|min| session.priority >= min)
+    }
+}
+
+/// Running sessions grouped by the Claude Code version they run on
+#[derive(Debug, Clone, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct VersionUsage {
+    /// CLI version, or None for sessions that haven't written a versioned entry
+    pub version: Option<String>,
+    pub session_ids: Vec<String>,
+    /// Older than the newest version among the running sessions
+    pub outdated: bool,
+}
+
+/// Version distribution across `sessions`, newest version first
+pub fn version_distribution(sessions: &[Session]) -> Vec<VersionUsage> {
+    let mut groups: Vec<VersionUsage> = Vec::new();
+    for session in sessions {
+        match groups.iter_mut().find(|g| g.version == session.cli_version) {
+            Some(group) => group.session_ids.push(session.id.clone()),
+            None => groups.push(VersionUsage {
+                version: session.cli_version.clone(),
+                session_ids: vec![session.id.clone()],
+                outdated: false,
+            }),
+        }
+    }
+
+    groups.sort_by(|a, b| {
+        let key = |g: &VersionUsage| g.version.as_deref().map(version_key);
+        key(b).cmp(&key(a))
+    });
+    let newest = groups
+        .first()
+        .and_then(|g| g.version.as_deref())
+        .map(version_key);
+    for group in &mut groups {
+        group.outdated = match (group.version.as_deref(), &newest) {
+            (Some(version), Some(newest)) => version_key(version) < *newest,
+            _ => false,
+        };
+    }
+    groups
+}
+
+/// Numeric parts of a version like "2.1.3" for ordering
+fn version_key(version: &str) -> Vec<u64> {
+    version
+        .split(['.', '-', '+'])
+        .map_while(|part| part.parse().ok())
+        .collect()
 }
 
 /// Start the background polling loop
@@ -45,19 +164,55 @@ pub struct Session {
 pub fn start_polling(app: AppHandle) {
     thread::spawn(move || {
         let app_handle = Arc::new(app);
-        let poll_interval = Duration::from_secs(2);
 
         // Track previous status for each session
         let previous_status: Arc<Mutex<HashMap<String, SessionStatus>>> =
             Arc::new(Mutex::new(HashMap::new()));
 
+        // Track the previous context fill (percent) for each session
+        let mut previous_context: HashMap<String, f64> = HashMap::new();
+
+        // Track the resource budgets each session is over
+        let mut previous_budgets: HashMap<String, Vec<ResourceBudget>> = HashMap::new();
+
+        // Track the file conflicts already announced
+        let mut previous_conflicts: HashSet<(String, Vec<String>)> = HashSet::new();
+
         // Track if this is the first poll cycle
         let mut is_first_cycle = true;
 
         loop {
+            // Pick up edits to the config file before each cycle
+            if let Some(config) = config::reload_if_changed() {
+                if let Err(e) = app_handle.emit("config-updated", &*config) {
+                    eprintln!("Failed to emit config-updated event: {}", e);
+                }
+            }
+            let config = config::current();
+
             // Detect and enrich sessions
             match detect_and_enrich_sessions() {
-                Ok(sessions) => {
+                Ok(mut sessions) => {
+                    let is_first_poll = is_first_cycle;
+                    attach_resources(&mut sessions, config.resources.sample_window);
+
+                    // Persist session snapshots and status transitions
+                    if let Ok(store) = crate::store::global() {
+                        for session in &sessions {
+                            let record = SessionRecord {
+                                id: &session.id,
+                                pid: session.pid,
+                                project_path: &session.project_path,
+                                git_branch: session.git_branch.as_deref(),
+                                first_prompt: &session.first_prompt,
+                                status: &session.status,
+                            };
+                            if let Err(e) = store.upsert_session(&record) {
+                                eprintln!("[store] Failed to record session {}: {}", session.id, e);
+                            }
+                        }
+                    }
+
                     // Track current session IDs to clean up stale entries
                     let current_session_ids: HashSet<String> =
                         sessions.iter().map(|s| s.id.clone()).collect();
@@ -76,23 +231,12 @@ pub fn start_polling(app: AppHandle) {
                                 for session in &sessions {
                                     if let Some(prev_status) = prev_status_map.get(&session.id) {
                                         // Check for notification-worthy transitions
-                                        let should_notify = match (prev_status, &session.status) {
-                                            (SessionStatus::Working, SessionStatus::NeedsPermission) => true,
-                                            (SessionStatus::Working, SessionStatus::WaitingForInput) => true,
-                                            _ => false,
-                                        };
+                                        let should_notify = config
+                                            .notifications
+                                            .should_notify(prev_status, &session.status);
 
                                         if should_notify {
-                                            fire_notification(
-                                                &app_handle,
-                                                &session.id,
-                                                &session.first_prompt,
-                                                &session.session_name,
-                                                &session.status,
-                                                session.pending_tool_name.as_deref(),
-                                                session.pid,
-                                                &session.project_path,
-                                            );
+                                            fire_notification(&app_handle, session);
                                         }
                                     }
 
@@ -117,10 +261,61 @@ pub fn start_polling(app: AppHandle) {
                         }
                     }
 
-                    // Emit event to frontend
-                    if let Err(e) = app_handle.emit("sessions-updated", &sessions) {
-                        eprintln!("Failed to emit sessions-updated event: {}", e);
+                    // Notify when a session's context fills past the threshold
+                    for session in &sessions {
+                        let Some(context) = &session.context else {
+                            continue;
+                        };
+                        let previous = previous_context.insert(session.id.clone(), context.percent);
+                        if crossed_threshold(previous, context.percent, config.context.notify_at_percent) {
+                            fire_context_notification(&app_handle, session, context);
+                        }
+                    }
+                    previous_context.retain(|id, _| current_session_ids.contains(id));
+
+                    // Notify when a session's process tree goes over a resource budget
+                    for session in &sessions {
+                        let Some(usage) = &session.resources else {
+                            continue;
+                        };
+                        let exceeded = usage.exceeded_budgets(&config.resources);
+                        // A session already over budget when first seen alerts right away
+                        let previous = previous_budgets
+                            .insert(session.id.clone(), exceeded.clone())
+                            .unwrap_or_default();
+                        for budget in exceeded.into_iter().filter(|b| !previous.contains(b)) {
+                            let alert = BudgetAlert {
+                                session_id: session.id.clone(),
+                                budget,
+                                usage: usage.clone(),
+                            };
+                            if let Err(e) = app_handle.emit("budget-exceeded", &alert) {
+                                eprintln!("Failed to emit budget-exceeded event: {}", e);
+                            }
+                            fire_budget_notification(&app_handle, session, &alert);
+                        }
+                    }
+                    previous_budgets.retain(|id, _| current_session_ids.contains(id));
+
+                    // Announce file conflicts that appeared since the last cycle
+                    let conflicts = current_conflicts(&sessions);
+                    for conflict in &conflicts {
+                        let key = (conflict.path.clone(), conflict.session_ids.clone());
+                        if previous_conflicts.contains(&key) || is_first_poll {
+                            continue;
+                        }
+                        if let Err(e) = app_handle.emit("conflict-detected", conflict) {
+                            eprintln!("Failed to emit conflict-detected event: {}", e);
+                        }
+                        fire_conflict_notification(&app_handle, &sessions, conflict);
                     }
+                    previous_conflicts = conflicts
+                        .into_iter()
+                        .map(|c| (c.path, c.session_ids))
+                        .collect();
+
+                    // Emit event to frontend, together with the replayed session if any
+                    emit_sessions(&app_handle, sessions);
                 }
                 Err(e) => {
                     eprintln!("Error detecting sessions: {}", e);
@@ -128,24 +323,109 @@ pub fn start_polling(app: AppHandle) {
                 }
             }
 
-            thread::sleep(poll_interval);
+            thread::sleep(Duration::from_millis(config.polling.interval_ms));
         }
     });
 }
 
+/// Sessions from the last polling cycle, so other producers can re-emit the list
+static LATEST_SESSIONS: Mutex<Vec<Session>> = Mutex::new(Vec::new());
+
+/// Emit `sessions-updated` with the detected sessions plus the replayed one
+pub fn emit_sessions(app_handle: &AppHandle, detected: Vec<Session>) {
+    let mut sessions = detected.clone();
+    match LATEST_SESSIONS.lock() {
+        Ok(mut latest) => *latest = detected,
+        Err(poisoned) => *poisoned.into_inner() = detected,
+    }
+
+    if let Some(replayed) = crate::replay::current_session() {
+        sessions.push(replayed);
+    }
+
+    if let Err(e) = app_handle.emit("sessions-updated", &sessions) {
+        eprintln!("Failed to emit sessions-updated event: {}", e);
+    }
+}
+
+/// Re-emit the last detected sessions, e.g. after the replayed session changed
+pub fn reemit_sessions(app_handle: &AppHandle) {
+    let detected = LATEST_SESSIONS
+        .lock()
+        .map(|latest| latest.clone())
+        .unwrap_or_default();
+    emit_sessions(app_handle, detected);
+}
+
+/// Poll until `session_id` reaches `status`, returning the session as last seen
+///
+/// Returns None if the session disappeared. The session is returned in
+/// whatever status it had when `timeout` ran out.
+pub fn wait_for_status(
+    session_id: &str,
+    status: SessionStatus,
+    timeout: Duration,
+) -> Result<Option<Session>, String> {
+    let deadline = std::time::Instant::now() + timeout;
+    loop {
+        let session = detect_and_enrich_sessions()?
+            .into_iter()
+            .find(|s| s.id == session_id);
+        match &session {
+            Some(s) if s.status != status && std::time::Instant::now() < deadline => {
+                thread::sleep(Duration::from_millis(250));
+            }
+            _ => return Ok(session),
+        }
+    }
+}
+
+/// Everything a detection pass reads besides the process table
+///
+/// The poller uses the process-wide instances; tests point it at a
+/// simulated projects directory, an in-memory store and a fixed clock.
+pub struct DetectionContext<'a> {
+    pub dirs: &'a ClaudeDirs,
+    pub store: Option<&'a Store>,
+    pub clock: &'a dyn Clock,
+}
+
 /// Detect sessions and enrich them with status and conversation data
 pub fn detect_and_enrich_sessions() -> Result<Vec<Session>, String> {
-    let mut detector = SessionDetector::new()
+    let store = crate::store::global()
+        .inspect_err(|e| eprintln!("[polling] Session store unavailable: {}", e))
+        .ok();
+
+    detect_and_enrich_sessions_with(&DetectionContext {
+        dirs: claude_dirs(),
+        store,
+        clock: &SystemClock,
+    })
+}
+
+/// [`detect_and_enrich_sessions`] with explicit inputs
+pub fn detect_and_enrich_sessions_with(ctx: &DetectionContext) -> Result<Vec<Session>, String> {
+    let mut detector = SessionDetector::new(ctx.dirs)
         .map_err(|e| format!("Failed to create session detector: {}", e))?;
 
     let detected_sessions = detector
         .detect_sessions()
         .map_err(|e| format!("Failed to detect sessions: {}", e))?;
 
-    let custom_names = crate::session::CustomNames::load();
-    let custom_titles = crate::session::CustomTitles::load();
+    let config = config::current();
+    let store = ctx.store;
+    let all_meta = match store {
+        Some(store) => store.all_session_meta().unwrap_or_else(|e| {
+            eprintln!("[polling] Failed to load session metadata: {}", e);
+            HashMap::new()
+        }),
+        None => HashMap::new(),
+    };
     let mut sessions = Vec::new();
     let mut seen_ids: HashSet<String> = HashSet::new();
+    let mut transcripts = Vec::new();
+    let mut touched = HashMap::new();
+    let mut repos: HashMap<PathBuf, Option<GitInfo>> = HashMap::new();
 
     for detected in detected_sessions {
         // Get session ID - if not found, skip this session
@@ -184,7 +464,11 @@ pub fn detect_and_enrich_sessions() -> Result<Vec<Session>, String> {
                 let session_file_path = detected.project_path.join(format!("{}.jsonl", session_id));
 
                 // Try to get first prompt from JSONL file
-                let first_prompt = get_first_prompt_from_jsonl(&session_file_path)
+                let first_prompt = get_first_prompt_from_jsonl(
+                    &session_file_path,
+                    config.display.first_prompt_max_chars,
+                )
+                    .or_else(|| crate::launch::initial_prompt(&session_id))
                     .unwrap_or_else(|| "(Active session)".to_string());
 
                 // Count messages in the file
@@ -206,7 +490,7 @@ pub fn detect_and_enrich_sessions() -> Result<Vec<Session>, String> {
 
         // Parse the session JSONL file to determine status and get latest message
         let session_file_path = detected.project_path.join(format!("{}.jsonl", session_id));
-        let entries = match parse_last_n_entries(&session_file_path, 20) {
+        let entries = match parse_last_n_entries(&session_file_path, config.polling.tail_entries) {
             Ok(entries) => entries,
             Err(e) => {
                 eprintln!(
@@ -217,29 +501,80 @@ pub fn detect_and_enrich_sessions() -> Result<Vec<Session>, String> {
             }
         };
 
+        // Sessions missing from the index still record their branch on each entry
+        let git_branch = git_branch.or_else(|| {
+            entries
+                .iter()
+                .rev()
+                .find_map(|entry| entry.base()?.git_branch.clone())
+                .filter(|branch| !branch.is_empty())
+        });
+
+        // Sessions sharing a cwd share its git state
+        let git = repos
+            .entry(detected.cwd.clone())
+            .or_insert_with(|| git_info(&detected.cwd))
+            .clone();
+        let git_branch = git
+            .as_ref()
+            .and_then(|git| git.head.branch.clone())
+            .or(git_branch);
+
+        // Each profile's own allow rules decide what needs permission
+        let permissions = ctx
+            .dirs
+            .root_of(&detected.project_path)
+            .map(permission_checker)
+            .unwrap_or_default();
+        let status_ctx = StatusContext {
+            clock: ctx.clock,
+            thresholds: &config.status,
+            permissions: &permissions,
+        };
         let status = if entries.is_empty() {
             SessionStatus::Connecting
         } else {
-            determine_status(&entries)
+            status_ctx.determine(&entries)
         };
 
-        let latest_message = get_latest_message_from_entries(&entries);
-        let pending_tool_name = get_pending_tool_name(&entries);
+        if let Some(store) = store {
+            record_usage(store, &session_id, &entries);
+        }
+
+        let context = context_usage(&entries, &config.context);
+        let history = scan_history(&session_file_path);
+        touched.insert(session_id.clone(), scan_files_touched(&session_file_path));
+        transcripts.push(session_file_path.clone());
+
+        let latest_message =
+            get_latest_message_from_entries(&entries, config.display.latest_message_max_chars);
+        let pending_tools: Vec<PendingTool> = status_ctx
+            .pending_tool_uses(&entries)
+            .iter()
+            .map(|tool| PendingTool::new(tool, &config.risk.rules))
+            .collect();
+        let pending_tool_name = pending_tools.first().map(|tool| tool.name.clone());
 
         // Skip empty sessions (0 messages) - these are likely sessions where user
-        // immediately used /resume to switch to a different session
-        if message_count == 0 {
+        // immediately used /resume to switch to a different session. Sessions
+        // started with an explicit ID (e.g. launched from here) stay visible
+        // as Connecting until their first entry is written.
+        if message_count == 0 && !detected.id_from_args {
             continue;
         }
 
-        // Use custom name if available, otherwise use detected project name
-        let session_name = custom_names
-            .get(&session_id)
-            .cloned()
-            .unwrap_or(detected.project_name);
+        let meta = all_meta.get(&session_id).cloned().unwrap_or_default();
+        let SessionMeta {
+            custom_name,
+            custom_title,
+            tags,
+            note,
+            pinned,
+            priority,
+        } = meta;
 
-        // Get custom title if available
-        let custom_title = custom_titles.get(&session_id).cloned();
+        // Use custom name if available, otherwise use detected project name
+        let session_name = custom_name.unwrap_or(detected.project_name);
 
         sessions.push(Session {
             id: session_id,
@@ -249,20 +584,114 @@ pub fn detect_and_enrich_sessions() -> Result<Vec<Session>, String> {
             project_path: detected.cwd.to_string_lossy().to_string(),
             git_branch,
             first_prompt,
-            summary,
+            // The index lags behind; the transcript's own summary entries don't
+            summary: summary.or(history.latest_summary.clone()),
             message_count,
             modified,
             status,
             latest_message,
             pending_tool_name,
+            pending_tools,
+            tags,
+            note,
+            pinned,
+            priority,
+            context,
+            compaction_count: history.compactions,
+            model: history.current_model().map(str::to_string),
+            model_switches: history.models,
+            cli_version: history.version,
+            conflicts: vec![],
+            git,
+            resources: latest_resources(detected.pid),
+            embedded_terminal: crate::pty::is_detached(detected.pid),
         });
     }
 
+    attach_conflicts(&mut sessions, &touched);
+
+    // Forget the history of transcripts whose session has ended
+    retain_histories(&transcripts);
+    retain_file_trackers(&transcripts);
+
     Ok(sessions)
 }
 
+/// Sample each session's process tree
+///
+/// Only the polling loop calls this, once per cycle, so CPU averages span
+/// `window` cycles; other detection passes reuse the last sample.
+fn attach_resources(sessions: &mut [Session], window: usize) {
+    let pids: Vec<u32> = sessions.iter().map(|s| s.pid).collect();
+    let mut usage = sample_resources(&pids, window);
+    for session in sessions {
+        session.resources = usage.remove(&session.pid);
+    }
+}
+
+/// Working tree and branch a session edits; sessions sharing both step on each other
+fn working_tree(session: &Session) -> (PathBuf, Option<&str>) {
+    let root = match &session.git {
+        Some(git) => PathBuf::from(&git.worktree),
+        None => PathBuf::from(&session.project_path),
+    };
+    (root, session.git_branch.as_deref())
+}
+
+/// Record on each session the files it changed alongside another session
+/// working on the same tree and branch
+fn attach_conflicts(sessions: &mut [Session], touched: &HashMap<String, FilesTouched>) {
+    let mut trees: HashMap<(PathBuf, Option<&str>), Vec<&str>> = HashMap::new();
+    for session in sessions.iter() {
+        trees
+            .entry(working_tree(session))
+            .or_default()
+            .push(&session.id);
+    }
+
+    let mut by_session: HashMap<String, Vec<FileConflict>> = HashMap::new();
+    for ids in trees.values().filter(|ids| ids.len() > 1) {
+        let members = ids
+            .iter()
+            .filter_map(|id| touched.get(*id).map(|files| (*id, files)));
+        for conflict in find_conflicts(members) {
+            for id in &conflict.session_ids {
+                by_session.entry(id.clone()).or_default().push(conflict.clone());
+            }
+        }
+    }
+
+    for session in sessions.iter_mut() {
+        session.conflicts = by_session.remove(&session.id).unwrap_or_default();
+    }
+}
+
+/// Every conflict across `sessions`, once each
+pub(crate) fn current_conflicts(sessions: &[Session]) -> Vec<FileConflict> {
+    let mut conflicts: Vec<FileConflict> = Vec::new();
+    for conflict in sessions.iter().flat_map(|s| &s.conflicts) {
+        if !conflicts.contains(conflict) {
+            conflicts.push(conflict.clone());
+        }
+    }
+    conflicts
+}
+
+/// Persist token usage of the assistant messages in the parsed tail
+fn record_usage(store: &Store, session_id: &str, entries: &[SessionEntry]) {
+    for entry in entries {
+        if let SessionEntry::Assistant { message, .. } = entry {
+            if let Some(usage) = &message.usage {
+                if let Err(e) = store.record_usage(session_id, &message.id, &message.model, usage) {
+                    eprintln!("[store] Failed to record usage for {}: {}", session_id, e);
+                }
+            }
+        }
+    }
+}
+
 /// Extract the first user prompt from a session JSONL file
-fn get_first_prompt_from_jsonl(path: &Path) -> Option<String> {
+fn get_first_prompt_from_jsonl(path: &Path, max_chars: usize) -> Option<String> {
     let file = File::open(path).ok()?;
     let reader = BufReader::new(file);
 
@@ -276,13 +705,13 @@ fn get_first_prompt_from_jsonl(path: &Path) -> Option<String> {
                         if let Some(content) = message.get("content") {
                             // Content can be a string or array
                             if let Some(text) = content.as_str() {
-                                return Some(truncate_string(text, 100));
+                                return Some(truncate_string(text, max_chars));
                             } else if let Some(arr) = content.as_array() {
                                 // Find the first text block
                                 for item in arr {
                                     if item.get("type").and_then(|t| t.as_str()) == Some("text") {
                                         if let Some(text) = item.get("text").and_then(|t| t.as_str()) {
-                                            return Some(truncate_string(text, 100));
+                                            return Some(truncate_string(text, max_chars));
                                         }
                                     }
                                 }
@@ -298,7 +727,7 @@ fn get_first_prompt_from_jsonl(path: &Path) -> Option<String> {
 }
 
 /// Truncate a string to a maximum length (character-safe for UTF-8)
-fn truncate_string(s: &str, max_chars: usize) -> String {
+pub(crate) fn truncate_string(s: &str, max_chars: usize) -> String {
     let char_count = s.chars().count();
     if char_count <= max_chars {
         s.to_string()
@@ -309,7 +738,10 @@ fn truncate_string(s: &str, max_chars: usize) -> String {
 }
 
 /// Extract the latest message content from session entries
-fn get_latest_message_from_entries(entries: &[crate::session::parser::SessionEntry]) -> String {
+pub(crate) fn get_latest_message_from_entries(
+    entries: &[crate::session::parser::SessionEntry],
+    max_chars: usize,
+) -> String {
     if entries.is_empty() {
         return String::new();
     }
@@ -322,17 +754,17 @@ fn get_latest_message_from_entries(entries: &[crate::session::parser::SessionEnt
                 if message.is_tool_result {
                     continue;
                 }
-                return truncate_string(&message.content, 200);
+                return truncate_string(&message.content, max_chars);
             }
             crate::session::parser::SessionEntry::Assistant { message, .. } => {
                 // For assistant, try to find the last text block
                 for content in message.content.iter().rev() {
                     match content {
                         crate::session::parser::MessageContent::Text { text } => {
-                            return truncate_string(text, 200);
+                            return truncate_string(text, max_chars);
                         }
                         crate::session::parser::MessageContent::Thinking { thinking, .. } => {
-                            return truncate_string(thinking, 200);
+                            return truncate_string(thinking, max_chars);
                         }
                         crate::session::parser::MessageContent::ToolUse { name, .. } => {
                             return format!("Executing {}...", name);
@@ -382,31 +814,155 @@ struct NotificationMetadata {
 }
 
 /// Fire a notification for a status transition
-fn fire_notification(
+fn fire_notification(app_handle: &AppHandle, session: &Session) {
+    // Truncate title to the configured length
+    let display = &config::current().display;
+    let title = truncate_string(&session.first_prompt, display.notification_title_max_chars);
+    let body = notification_body(session, display.latest_message_max_chars);
+
+    deliver_notification(
+        app_handle,
+        &session.id,
+        session.pid,
+        &session.project_path,
+        &session.status,
+        &title,
+        &body,
+    );
+}
+
+/// Body of a status notification; a permission request shows each pending
+/// tool's risk and what it would act on, cut to `command_max_chars`
+fn notification_body(session: &Session, command_max_chars: usize) -> String {
+    let name = &session.session_name;
+    let describe = |tool: &PendingTool| {
+        format!(
+            "{} ({}): {}",
+            tool.name,
+            tool.risk.level.label(),
+            truncate_string(&tool.target, command_max_chars)
+        )
+    };
+    match session.status {
+        SessionStatus::NeedsPermission => match session.pending_tools.as_slice() {
+            [] => {
+                let tool_name = session.pending_tool_name.as_deref().unwrap_or("unknown tool");
+                format!("{}: Needs permission for {}", name, tool_name)
+            }
+            [tool] => {
+                let mut body = format!("{}: Needs permission for {}", name, describe(tool));
+                if let Some(description) = &tool.description {
+                    body.push('\n');
+                    body.push_str(&truncate_string(description, command_max_chars));
+                }
+                body
+            }
+            tools => {
+                let mut body = format!("{}: Needs permission for {} tools", name, tools.len());
+                for tool in tools {
+                    body.push('\n');
+                    body.push_str(&describe(tool));
+                }
+                body
+            }
+        },
+        SessionStatus::WaitingForInput => format!("{}: Finished working", name),
+        SessionStatus::Working => format!("{}: Started working", name),
+        SessionStatus::Connecting => format!("{}: Connecting", name),
+    }
+}
+
+/// Fire a notification when a session's context window is filling up
+fn fire_context_notification(app_handle: &AppHandle, session: &Session, context: &ContextUsage) {
+    let title_max_chars = config::current().display.notification_title_max_chars;
+    let title = truncate_string(&session.first_prompt, title_max_chars);
+    let body = format!(
+        "{}: Context {:.0}% full ({}k of {}k tokens)",
+        session.session_name,
+        context.percent,
+        context.tokens / 1000,
+        context.window_tokens / 1000
+    );
+
+    deliver_notification(
+        app_handle,
+        &session.id,
+        session.pid,
+        &session.project_path,
+        &session.status,
+        &title,
+        &body,
+    );
+}
+
+/// Fire a notification when live sessions start changing the same file
+fn fire_conflict_notification(app_handle: &AppHandle, sessions: &[Session], conflict: &FileConflict) {
+    let Some(session) = sessions.iter().find(|s| conflict.session_ids.contains(&s.id)) else {
+        return;
+    };
+    let title_max_chars = config::current().display.notification_title_max_chars;
+    let title = truncate_string(&session.first_prompt, title_max_chars);
+    let file_name = Path::new(&conflict.path)
+        .file_name()
+        .map(|name| name.to_string_lossy().to_string())
+        .unwrap_or_else(|| conflict.path.clone());
+    let body = format!(
+        "{}: {} sessions editing {}",
+        session.session_name,
+        conflict.session_ids.len(),
+        file_name
+    );
+
+    deliver_notification(
+        app_handle,
+        &session.id,
+        session.pid,
+        &session.project_path,
+        &session.status,
+        &title,
+        &body,
+    );
+}
+
+/// Fire a notification when a session's process tree goes over a budget
+fn fire_budget_notification(app_handle: &AppHandle, session: &Session, alert: &BudgetAlert) {
+    let title_max_chars = config::current().display.notification_title_max_chars;
+    let title = truncate_string(&session.first_prompt, title_max_chars);
+    let usage = &alert.usage;
+    let body = match alert.budget {
+        ResourceBudget::Memory => format!(
+            "{}: Using {} MB of memory across {} processes",
+            session.session_name,
+            usage.rss_bytes / (1024 * 1024),
+            usage.process_count
+        ),
+        ResourceBudget::Cpu => format!(
+            "{}: Averaging {:.0}% CPU across {} processes",
+            session.session_name, usage.avg_cpu_percent, usage.process_count
+        ),
+    };
+
+    deliver_notification(
+        app_handle,
+        &session.id,
+        session.pid,
+        &session.project_path,
+        &session.status,
+        &title,
+        &body,
+    );
+}
+
+/// Show a native notification, announce it to the frontend and record it
+fn deliver_notification(
     app_handle: &AppHandle,
     session_id: &str,
-    first_prompt: &str,
-    session_name: &str,
-    status: &SessionStatus,
-    pending_tool_name: Option<&str>,
     pid: u32,
     project_path: &str,
+    status: &SessionStatus,
+    title: &str,
+    body: &str,
 ) {
-    // Truncate title to 60 characters
-    let title = truncate_string(first_prompt, 60);
-
-    // Build the body based on the status
-    let body = match status {
-        SessionStatus::NeedsPermission => {
-            let tool_name = pending_tool_name.unwrap_or("unknown tool");
-            format!("{}: Needs permission for {}", session_name, tool_name)
-        }
-        SessionStatus::WaitingForInput => {
-            format!("{}: Finished working", session_name)
-        }
-        _ => return, // Should not happen based on the caller's logic
-    };
-
     // Generate a stable i32 ID from the session_id string using hash
     let mut hasher = DefaultHasher::new();
     session_id.hash(&mut hasher);
@@ -417,8 +973,8 @@ fn fire_notification(
     if let Err(e) = app_handle
         .notification()
         .builder()
-        .title(&title)
-        .body(&body)
+        .title(title)
+        .body(body)
         .show()
     {
         eprintln!("[notification] Failed to show notification: {}", e);
@@ -430,34 +986,358 @@ fn fire_notification(
         session_id: session_id.to_string(),
         pid,
         project_path: project_path.to_string(),
-        title: title.clone(),
+        title: title.to_string(),
     };
 
     if let Err(e) = app_handle.emit("notification-fired", &metadata) {
         eprintln!("Failed to emit notification-fired event: {}", e);
     }
+
+    if let Ok(store) = crate::store::global() {
+        if let Err(e) = store.record_notification(session_id, status, title, body) {
+            eprintln!("[store] Failed to record notification: {}", e);
+        }
+    }
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
 
+    fn sample_session() -> Session {
+        Session {
+            id: "s1".to_string(),
+            pid: 1234,
+            session_name: "api".to_string(),
+            custom_title: None,
+            project_path: "/work/api".to_string(),
+            git_branch: Some("main".to_string()),
+            first_prompt: "Fix the flaky test".to_string(),
+            summary: None,
+            message_count: 4,
+            modified: String::new(),
+            status: SessionStatus::NeedsPermission,
+            latest_message: String::new(),
+            pending_tool_name: Some("Bash".to_string()),
+            pending_tools: vec![],
+            tags: vec!["experiment".to_string(), "release-blocker".to_string()],
+            note: None,
+            pinned: true,
+            priority: 1,
+            context: None,
+            compaction_count: 0,
+            model: Some("claude-opus-4-5".to_string()),
+            model_switches: vec![],
+            cli_version: Some("2.1.3".to_string()),
+            conflicts: vec![],
+            git: None,
+            resources: None,
+            embedded_terminal: false,
+        }
+    }
+
     #[test]
-    fn test_detect_and_enrich_sessions() {
-        // This test will only work if there are active Claude sessions
-        match detect_and_enrich_sessions() {
-            Ok(sessions) => {
-                println!("Detected {} sessions", sessions.len());
-                for session in sessions {
-                    println!(
-                        "Session: {} - {} (PID: {}, Status: {:?})",
-                        session.id, session.session_name, session.pid, session.status
-                    );
+    fn test_permission_notification_shows_risk_and_command() {
+        use crate::session::{PendingToolUse, RiskRule};
+
+        let pending = |id: &str, name: &str, input: serde_json::Value| {
+            let tool = PendingToolUse { id, name, input: &input };
+            PendingTool::new(&tool, &RiskRule::defaults())
+        };
+        let push = pending(
+            "toolu_1",
+            "Bash",
+            serde_json::json!({
+                "command": "git push --force origin main",
+                "description": "Publish the rebased branch"
+            }),
+        );
+        let session = Session {
+            pending_tools: vec![push.clone()],
+            ..sample_session()
+        };
+
+        assert_eq!(
+            notification_body(&session, 200),
+            "api: Needs permission for Bash (destructive): git push --force origin main\n\
+             Publish the rebased branch"
+        );
+        assert_eq!(
+            notification_body(&session, 8),
+            "api: Needs permission for Bash (destructive): git push...\nPublish ..."
+        );
+
+        // Tools requested together in one message are listed one per line
+        let edit = pending(
+            "toolu_2",
+            "Edit",
+            serde_json::json!({ "file_path": "/work/api/src/lib.rs", "old_string": "a", "new_string": "b" }),
+        );
+        let both = Session {
+            pending_tools: vec![edit, push],
+            ..sample_session()
+        };
+        assert_eq!(
+            notification_body(&both, 200),
+            "api: Needs permission for 2 tools\n\
+             Edit (local write): /work/api/src/lib.rs\n\
+             Bash (destructive): git push --force origin main"
+        );
+
+        let finished = Session {
+            status: SessionStatus::WaitingForInput,
+            ..session
+        };
+        assert_eq!(notification_body(&finished, 200), "api: Finished working");
+    }
+
+    #[test]
+    fn test_conflicts_need_same_tree_and_branch() {
+        use crate::session::files::{FileChange, FileTouch};
+
+        let session = |id: &str, path: &str, branch: &str| Session {
+            id: id.to_string(),
+            project_path: path.to_string(),
+            git_branch: Some(branch.to_string()),
+            ..sample_session()
+        };
+        let edits = |paths: &[&str]| FilesTouched {
+            files: paths
+                .iter()
+                .map(|path| FileTouch {
+                    path: path.to_string(),
+                    change: FileChange::Edited,
+                    touches: 1,
+                    last_touched: String::new(),
+                })
+                .collect(),
+            created: 0,
+            edited: paths.len() as u32,
+            deleted: 0,
+        };
+
+        let mut sessions = vec![
+            session("a", "/work/api", "main"),
+            session("b", "/work/api", "main"),
+            session("c", "/work/api", "feature"),
+            session("d", "/work/web", "main"),
+        ];
+        let touched = HashMap::from([
+            ("a".to_string(), edits(&["/work/api/src/lib.rs", "/work/api/README.md"])),
+            ("b".to_string(), edits(&["/work/api/src/lib.rs"])),
+            ("c".to_string(), edits(&["/work/api/src/lib.rs"])),
+            ("d".to_string(), edits(&["/work/api/src/lib.rs"])),
+        ]);
+
+        attach_conflicts(&mut sessions, &touched);
+
+        let expected = FileConflict {
+            path: "/work/api/src/lib.rs".to_string(),
+            session_ids: vec!["a".to_string(), "b".to_string()],
+        };
+        assert_eq!(sessions[0].conflicts, vec![expected.clone()]);
+        assert_eq!(sessions[1].conflicts, vec![expected.clone()]);
+        // Another branch or another checkout doesn't conflict
+        assert!(sessions[2].conflicts.is_empty());
+        assert!(sessions[3].conflicts.is_empty());
+        assert_eq!(current_conflicts(&sessions), vec![expected]);
+    }
+
+    #[test]
+    fn test_version_distribution() {
+        let with_version = |id: &str, version: Option<&str>| Session {
+            id: id.to_string(),
+            cli_version: version.map(str::to_string),
+            ..sample_session()
+        };
+        let sessions = vec![
+            with_version("a", Some("2.1.3")),
+            with_version("b", Some("2.0.10")),
+            with_version("c", Some("2.1.3")),
+            with_version("d", None),
+            with_version("e", Some("2.0.9")),
+        ];
+
+        let distribution = version_distribution(&sessions);
+        let versions: Vec<Option<&str>> =
+            distribution.iter().map(|g| g.version.as_deref()).collect();
+        assert_eq!(versions, vec![Some("2.1.3"), Some("2.0.10"), Some("2.0.9"), None]);
+        assert_eq!(distribution[0].session_ids, vec!["a", "c"]);
+        assert!(!distribution[0].outdated);
+        assert!(distribution[1].outdated && distribution[2].outdated);
+        assert!(!distribution[3].outdated);
+    }
+
+    #[test]
+    fn test_empty_filter_matches_everything() {
+        assert!(SessionFilter::default().matches(&sample_session()));
+    }
+
+    #[test]
+    fn test_filter_by_tags_pinned_and_status() {
+        let session = sample_session();
+
+        let filter = SessionFilter {
+            tags: vec!["release-blocker".to_string()],
+            pinned: Some(true),
+            statuses: vec![SessionStatus::NeedsPermission, SessionStatus::WaitingForInput],
+            ..Default::default()
+        };
+        assert!(filter.matches(&session));
+
+        let missing_tag = SessionFilter {
+            tags: vec!["release-blocker".to_string(), "docs".to_string()],
+            ..Default::default()
+        };
+        assert!(!missing_tag.matches(&session));
+
+        let unpinned = SessionFilter {
+            pinned: Some(false),
+            ..Default::default()
+        };
+        assert!(!unpinned.matches(&session));
+
+        let high_priority = SessionFilter {
+            min_priority: Some(2),
+            ..Default::default()
+        };
+        assert!(!high_priority.matches(&session));
+    }
+
+    #[cfg(target_os = "linux")]
+    mod end_to_end {
+        use super::*;
+        use crate::session::FixedClock;
+        use crate::test_support::{claude_processes_lock, FakeClaude, SimulatedClaudeHome};
+        use serde_json::json;
+        use std::sync::MutexGuard;
+
+        /// Detection against the simulated home, with a hermetic store and rules
+        struct Harness {
+            home: SimulatedClaudeHome,
+            dirs: ClaudeDirs,
+            store: Store,
+            _processes: MutexGuard<'static, ()>,
+        }
+
+        impl Harness {
+            fn new(name: &str) -> Self {
+                let processes = claude_processes_lock();
+                let home = SimulatedClaudeHome::new(name);
+                Self {
+                    dirs: home.dirs(),
+                    home,
+                    store: Store::open_in_memory().unwrap(),
+                    _processes: processes,
                 }
             }
-            Err(e) => {
-                println!("Error detecting sessions: {}", e);
+
+            fn detect_at(&self, clock: &dyn Clock) -> Vec<Session> {
+                detect_and_enrich_sessions_with(&DetectionContext {
+                    dirs: &self.dirs,
+                    store: Some(&self.store),
+                    clock,
+                })
+                .unwrap()
             }
+
+            fn detect(&self) -> Vec<Session> {
+                self.detect_at(&SystemClock)
+            }
+
+            fn only_session(&self) -> Session {
+                let sessions = self.detect();
+                assert_eq!(sessions.len(), 1, "expected one session, got {:?}", sessions);
+                sessions.into_iter().next().unwrap()
+            }
+        }
+
+        #[test]
+        fn test_session_lifecycle() {
+            let harness = Harness::new("lifecycle");
+            let cwd = harness.home.workspace("login_service");
+
+            // Started, but nothing written yet
+            let mut claude = FakeClaude::spawn(
+                &harness.home,
+                &cwd,
+                "7d0c3a52-0000-4000-8000-000000000001",
+            )
+            .unwrap();
+            assert!(harness.detect().is_empty());
+
+            claude.prompt("Fix the login redirect bug");
+            let session = harness.only_session();
+            assert_eq!(session.id, claude.session_id());
+            assert_eq!(session.pid, claude.pid());
+            assert_eq!(session.project_path, cwd.to_string_lossy());
+            assert_eq!(session.first_prompt, "Fix the login redirect bug");
+            assert_eq!(session.status, SessionStatus::Working);
+
+            // Auto-approved tool call
+            let read = claude.call_tool("Read", json!({ "file_path": "src/login.rs" }));
+            assert_eq!(harness.only_session().status, SessionStatus::Working);
+            claude.tool_result(&read, "fn redirect() {}");
+            assert_eq!(harness.only_session().status, SessionStatus::Working);
+
+            // Tool call waiting for permission
+            let bash = claude.call_tool("Bash", json!({ "command": "rm -rf target" }));
+            let session = harness.only_session();
+            assert_eq!(session.status, SessionStatus::NeedsPermission);
+            assert_eq!(session.pending_tool_name.as_deref(), Some("Bash"));
+
+            // Approved, then finished
+            claude.tool_result(&bash, "");
+            claude.reply("Fixed the redirect and cleaned the build.");
+            let session = harness.only_session();
+            assert_eq!(session.status, SessionStatus::Working);
+            assert_eq!(session.latest_message, "Fixed the redirect and cleaned the build.");
+            assert_eq!(session.message_count, 6);
+            assert_eq!(session.model.as_deref(), Some("claude-sonnet-4-5"));
+            assert_eq!(session.cli_version.as_deref(), Some("2.1.3"));
+            assert!(session.context.is_some());
+            let mut sessions = vec![session];
+            attach_resources(&mut sessions, 3);
+            assert!(sessions[0].resources.as_ref().is_some_and(|r| r.process_count >= 1));
+
+            // A minute later the session is idle
+            let later = FixedClock(Utc::now() + chrono::Duration::seconds(60));
+            let sessions = harness.detect_at(&later);
+            assert_eq!(sessions[0].status, SessionStatus::WaitingForInput);
+
+            // Token usage of every assistant message reached the store
+            let usage = harness.store.usage_totals(claude.session_id()).unwrap();
+            assert_eq!(usage.output_tokens, 60);
+
+            // The process exits; its transcript stays but the session is gone
+            claude.exit();
+            assert!(harness.detect().is_empty());
+        }
+
+        #[test]
+        fn test_concurrent_sessions_are_matched_by_cwd() {
+            let harness = Harness::new("concurrent");
+            let api = harness.home.workspace("api");
+            let web = harness.home.workspace("web");
+
+            let mut api_claude =
+                FakeClaude::spawn(&harness.home, &api, "7d0c3a52-0000-4000-8000-00000000000a")
+                    .unwrap();
+            let mut web_claude =
+                FakeClaude::spawn(&harness.home, &web, "7d0c3a52-0000-4000-8000-00000000000b")
+                    .unwrap();
+            api_claude.prompt("Add pagination to /users");
+            web_claude.prompt("Render the user list");
+            web_claude.call_tool("Write", json!({ "file_path": "src/users.tsx", "content": "" }));
+
+            let mut sessions = harness.detect();
+            sessions.sort_by(|a, b| a.project_path.cmp(&b.project_path));
+            assert_eq!(sessions.len(), 2);
+
+            assert_eq!(sessions[0].pid, api_claude.pid());
+            assert_eq!(sessions[0].status, SessionStatus::Working);
+            assert_eq!(sessions[1].pid, web_claude.pid());
+            assert_eq!(sessions[1].status, SessionStatus::NeedsPermission);
         }
     }
 }
@@ -0,0 +1,370 @@
+//! Sessions running on a pseudo-terminal owned by c9watch
+//!
+//! Claude Code is interactive and needs a terminal. A detached launch gives
+//! it one here instead of in a terminal emulator; the master side stays
+//! open for as long as the process runs. Output is kept as scrollback and
+//! passed to the listener, and keystrokes are written back to the master,
+//! so the app can act as the terminal itself.
+
+use serde::Serialize;
+use std::collections::{HashMap, VecDeque};
+use std::fs::File;
+use std::io::{Read, Write};
+use std::os::fd::{AsRawFd, FromRawFd, OwnedFd};
+use std::os::unix::process::CommandExt;
+use std::path::Path;
+use std::process::{Command, Stdio};
+use std::sync::{Mutex, OnceLock};
+use std::thread;
+use thiserror::Error;
+
+#[derive(Error, Debug)]
+pub enum PtyError {
+    #[error("Failed to open a pseudo-terminal: {0}")]
+    Open(std::io::Error),
+
+    #[error("Failed to start {program}: {source}")]
+    Spawn {
+        program: String,
+        source: std::io::Error,
+    },
+
+    #[error("No terminal for process {0}")]
+    NotFound(u32),
+
+    #[error("Invalid terminal size {cols}x{rows}")]
+    InvalidSize { cols: u16, rows: u16 },
+
+    #[error("Terminal I/O failed: {0}")]
+    Io(std::io::Error),
+}
+
+/// Initial size of a detached session's terminal
+const COLS: u16 = 120;
+const ROWS: u16 = 40;
+
+/// Output kept per terminal for a pane opened after the session started
+const SCROLLBACK_BYTES: usize = 1024 * 1024;
+
+/// Terminal output, decoded as UTF-8
+#[derive(Debug, Clone, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PtyOutput {
+    pub pid: u32,
+    pub data: String,
+}
+
+/// A process on a c9watch terminal exited
+#[derive(Debug, Clone, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PtyExit {
+    pub pid: u32,
+    /// None when the process was killed by a signal
+    pub exit_code: Option<i32>,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum PtyEvent {
+    Output(PtyOutput),
+    Exit(PtyExit),
+}
+
+type Listener = Box<dyn Fn(PtyEvent) + Send + Sync>;
+
+static LISTENER: OnceLock<Listener> = OnceLock::new();
+
+/// Receive output and exits of every terminal; only the first listener is kept
+pub fn set_listener(listener: impl Fn(PtyEvent) + Send + Sync + 'static) {
+    let _ = LISTENER.set(Box::new(listener));
+}
+
+fn notify(event: PtyEvent) {
+    if let Some(listener) = LISTENER.get() {
+        listener(event);
+    }
+}
+
+/// Master side and recent output of a running detached session
+struct Terminal {
+    master: File,
+    scrollback: VecDeque<u8>,
+}
+
+impl Terminal {
+    fn record(&mut self, output: &[u8]) {
+        self.scrollback.extend(output);
+        let excess = self.scrollback.len().saturating_sub(SCROLLBACK_BYTES);
+        self.scrollback.drain(..excess);
+    }
+}
+
+/// Terminal of each running detached session, by pid
+static TERMINALS: Mutex<Option<HashMap<u32, Terminal>>> = Mutex::new(None);
+
+/// Start `program` on a new pseudo-terminal in `cwd`, returning its pid
+///
+/// The process leads a new session with the terminal as its controlling
+/// tty, so it survives c9watch's own terminal (if any) going away.
+pub fn spawn(program: &str, args: &[String], cwd: &Path) -> Result<u32, PtyError> {
+    let (master, slave) = open_pty()?;
+    let stdio = |fd: &OwnedFd| -> Result<Stdio, PtyError> {
+        Ok(Stdio::from(fd.try_clone().map_err(PtyError::Open)?))
+    };
+
+    let mut command = Command::new(program);
+    command
+        .args(args)
+        .current_dir(cwd)
+        .env("TERM", "xterm-256color")
+        .stdin(stdio(&slave)?)
+        .stdout(stdio(&slave)?)
+        .stderr(stdio(&slave)?);
+    // SAFETY: setsid() and ioctl() are async-signal-safe
+    unsafe {
+        command.pre_exec(|| {
+            if libc::setsid() == -1 || libc::ioctl(0, libc::TIOCSCTTY as _, 0) == -1 {
+                return Err(std::io::Error::last_os_error());
+            }
+            Ok(())
+        });
+    }
+
+    let mut child = command.spawn().map_err(|source| PtyError::Spawn {
+        program: program.to_string(),
+        source,
+    })?;
+    drop(slave);
+    let pid = child.id();
+
+    let master = File::from(master);
+    let mut reader = master.try_clone().map_err(PtyError::Open)?;
+    lock().get_or_insert_with(HashMap::new).insert(
+        pid,
+        Terminal {
+            master,
+            scrollback: VecDeque::new(),
+        },
+    );
+
+    // Claude blocks once the terminal's buffer fills, so keep draining it
+    // even when nobody is watching
+    thread::spawn(move || {
+        let mut buf = [0u8; 8192];
+        let mut pending = Vec::new();
+        while let Ok(n @ 1..) = reader.read(&mut buf) {
+            if let Some(terminal) = lock().as_mut().and_then(|t| t.get_mut(&pid)) {
+                terminal.record(&buf[..n]);
+            }
+            pending.extend_from_slice(&buf[..n]);
+            let data = take_utf8(&mut pending);
+            if !data.is_empty() {
+                notify(PtyEvent::Output(PtyOutput { pid, data }));
+            }
+        }
+    });
+
+    // Reap the process and close the terminal when it exits
+    thread::spawn(move || {
+        let status = child.wait();
+        if let Some(terminals) = lock().as_mut() {
+            terminals.remove(&pid);
+        }
+        notify(PtyEvent::Exit(PtyExit {
+            pid,
+            exit_code: status.ok().and_then(|status| status.code()),
+        }));
+    });
+
+    Ok(pid)
+}
+
+/// Whether `pid` runs on a pseudo-terminal started by [`spawn`]
+pub fn is_detached(pid: u32) -> bool {
+    lock().as_ref().is_some_and(|terminals| terminals.contains_key(&pid))
+}
+
+/// Type `data` into the terminal of `pid`, as keystrokes
+pub fn write_input(pid: u32, data: &[u8]) -> Result<(), PtyError> {
+    // Write through a clone so a process that stops reading input can't
+    // block the output thread on the lock
+    let mut master = with_terminal(pid, |terminal| {
+        terminal.master.try_clone().map_err(PtyError::Io)
+    })??;
+    master.write_all(data).map_err(PtyError::Io)
+}
+
+/// Resize the terminal of `pid`; the process gets SIGWINCH
+pub fn resize(pid: u32, cols: u16, rows: u16) -> Result<(), PtyError> {
+    if cols == 0 || rows == 0 {
+        return Err(PtyError::InvalidSize { cols, rows });
+    }
+    let size = libc::winsize {
+        ws_row: rows,
+        ws_col: cols,
+        ws_xpixel: 0,
+        ws_ypixel: 0,
+    };
+    with_terminal(pid, |terminal| {
+        // SAFETY: TIOCSWINSZ reads a winsize that lives for the call
+        let result =
+            unsafe { libc::ioctl(terminal.master.as_raw_fd(), libc::TIOCSWINSZ as _, &size) };
+        if result == -1 {
+            return Err(PtyError::Io(std::io::Error::last_os_error()));
+        }
+        Ok(())
+    })?
+}
+
+/// Recent output of the terminal of `pid`, up to the scrollback limit
+pub fn scrollback(pid: u32) -> Result<String, PtyError> {
+    with_terminal(pid, |terminal| {
+        let (front, back) = terminal.scrollback.as_slices();
+        String::from_utf8_lossy(&[front, back].concat()).into_owned()
+    })
+}
+
+fn with_terminal<T>(pid: u32, f: impl FnOnce(&mut Terminal) -> T) -> Result<T, PtyError> {
+    lock()
+        .as_mut()
+        .and_then(|terminals| terminals.get_mut(&pid))
+        .map(f)
+        .ok_or(PtyError::NotFound(pid))
+}
+
+/// Decode the complete characters of `pending`, leaving a trailing partial one
+/// for the next read
+fn take_utf8(pending: &mut Vec<u8>) -> String {
+    let complete = match std::str::from_utf8(pending) {
+        Ok(_) => pending.len(),
+        // error_len() is None when the input just ends mid-character
+        Err(e) if e.error_len().is_none() => e.valid_up_to(),
+        Err(_) => pending.len(),
+    };
+    let rest = pending.split_off(complete);
+    let data = String::from_utf8_lossy(pending).into_owned();
+    *pending = rest;
+    data
+}
+
+fn open_pty() -> Result<(OwnedFd, OwnedFd), PtyError> {
+    let mut master = -1;
+    let mut slave = -1;
+    let size = libc::winsize {
+        ws_row: ROWS,
+        ws_col: COLS,
+        ws_xpixel: 0,
+        ws_ypixel: 0,
+    };
+    // SAFETY: the out-pointers are valid for the call and the name buffer is unused
+    let result = unsafe {
+        libc::openpty(
+            &mut master,
+            &mut slave,
+            std::ptr::null_mut(),
+            std::ptr::null(),
+            &size as *const libc::winsize as *mut libc::winsize,
+        )
+    };
+    if result != 0 {
+        return Err(PtyError::Open(std::io::Error::last_os_error()));
+    }
+
+    // SAFETY: openpty() succeeded, so both descriptors are open and ours
+    let (master, slave) = unsafe { (OwnedFd::from_raw_fd(master), OwnedFd::from_raw_fd(slave)) };
+    // The child gets the slave through stdio only
+    // SAFETY: fcntl() on a descriptor we own
+    unsafe {
+        libc::fcntl(master.as_raw_fd(), libc::F_SETFD, libc::FD_CLOEXEC);
+        libc::fcntl(slave.as_raw_fd(), libc::F_SETFD, libc::FD_CLOEXEC);
+    }
+    Ok((master, slave))
+}
+
+fn lock() -> std::sync::MutexGuard<'static, Option<HashMap<u32, Terminal>>> {
+    TERMINALS.lock().unwrap_or_else(|poisoned| poisoned.into_inner())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::time::{Duration, Instant};
+
+    #[test]
+    fn test_spawn_runs_on_a_terminal() {
+        let dir = std::env::temp_dir();
+        let marker = dir.join(format!("c9watch-pty-{}", std::process::id()));
+        let script = format!("[ -t 0 ] && [ -t 1 ] && touch {}", marker.display());
+
+        let pid = spawn("sh", &["-c".to_string(), script], &dir).unwrap();
+        assert!(pid > 0);
+
+        let deadline = Instant::now() + Duration::from_secs(5);
+        while is_detached(pid) && Instant::now() < deadline {
+            thread::sleep(Duration::from_millis(20));
+        }
+        assert!(!is_detached(pid));
+        assert!(marker.exists());
+        let _ = std::fs::remove_file(&marker);
+    }
+
+    fn wait_for(what: impl Fn() -> bool) -> bool {
+        let deadline = Instant::now() + Duration::from_secs(5);
+        while !what() {
+            if Instant::now() > deadline {
+                return false;
+            }
+            thread::sleep(Duration::from_millis(20));
+        }
+        true
+    }
+
+    #[test]
+    fn test_input_resize_and_scrollback() {
+        let dir = std::env::temp_dir();
+        let pid = spawn("sh", &[], &dir).unwrap();
+        let output_contains = |text: &str| scrollback(pid).is_ok_and(|output| output.contains(text));
+
+        write_input(pid, b"echo typed-$((20 + 22))\n").unwrap();
+        assert!(wait_for(|| output_contains("typed-42")));
+
+        resize(pid, 100, 30).unwrap();
+        write_input(pid, b"stty size\n").unwrap();
+        assert!(wait_for(|| output_contains("30 100")));
+        assert!(matches!(resize(pid, 0, 30), Err(PtyError::InvalidSize { .. })));
+
+        write_input(pid, b"exit\n").unwrap();
+        assert!(wait_for(|| !is_detached(pid)));
+        assert!(matches!(scrollback(pid), Err(PtyError::NotFound(_))));
+        assert!(matches!(write_input(pid, b"x"), Err(PtyError::NotFound(_))));
+    }
+
+    #[test]
+    fn test_scrollback_keeps_the_latest_output() {
+        let mut terminal = Terminal {
+            master: File::open("/dev/null").unwrap(),
+            scrollback: VecDeque::new(),
+        };
+        terminal.record(&vec![b'a'; SCROLLBACK_BYTES]);
+        terminal.record(b"tail");
+
+        assert_eq!(terminal.scrollback.len(), SCROLLBACK_BYTES);
+        assert!(terminal.scrollback.iter().rev().take(4).eq(b"liat".iter()));
+    }
+
+    #[test]
+    fn test_take_utf8_holds_back_split_characters() {
+        let bytes = "é→".as_bytes();
+        let mut pending = bytes[..3].to_vec();
+
+        assert_eq!(take_utf8(&mut pending), "é");
+        assert_eq!(pending, bytes[2..3]);
+
+        pending.extend_from_slice(&bytes[3..]);
+        assert_eq!(take_utf8(&mut pending), "→");
+        assert!(pending.is_empty());
+
+        // Invalid bytes are replaced rather than held forever
+        let mut invalid = vec![0xff, b'x'];
+        assert_eq!(take_utf8(&mut invalid), "\u{fffd}x");
+    }
+}
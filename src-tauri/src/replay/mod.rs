@@ -0,0 +1,431 @@
+use crate::config;
+use crate::polling::{self, Session};
+use crate::session::{
+    claude_dirs, context_usage, permission_checker, FixedClock, PendingTool, PermissionChecker,
+    SessionEntry, StatusContext, Transcript, TranscriptHistory,
+};
+use chrono::{DateTime, Duration as ChronoDuration, Utc};
+use serde::Serialize;
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::{Arc, Condvar, Mutex, MutexGuard};
+use std::thread;
+use std::time::{Duration, Instant};
+use tauri::{AppHandle, Emitter};
+
+/// Transcript replayed when no file is given (the demo session)
+const DEMO_TRANSCRIPT: &str = include_str!("demo.jsonl");
+
+/// How often the replay clock advances
+const TICK: Duration = Duration::from_millis(100);
+
+/// Keep replaying this long past the last entry so idle transitions show up
+const IDLE_TAIL_SECS: i64 = 60;
+
+/// Public view of the running replay, emitted as `replay-updated`
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ReplayInfo {
+    /// Id of the synthetic session in `sessions-updated`
+    pub session_id: String,
+    /// Transcript being replayed, or None for the built-in demo
+    pub path: Option<PathBuf>,
+    pub start: String,
+    pub end: String,
+    /// Transcript time the replay has reached
+    pub position: String,
+    /// Transcript seconds per wall-clock second (0 means paused)
+    pub speed: f64,
+    pub looping: bool,
+    pub finished: bool,
+}
+
+struct Replay {
+    /// Bumped for every new replay so a superseded thread knows to stop
+    generation: u64,
+    transcript: Transcript,
+    path: Option<PathBuf>,
+    name: String,
+    start: DateTime<Utc>,
+    end: DateTime<Utc>,
+    position: DateTime<Utc>,
+    speed: f64,
+    looping: bool,
+    session: Option<Session>,
+}
+
+static REPLAY: Mutex<Option<Replay>> = Mutex::new(None);
+static GENERATION: AtomicU64 = AtomicU64::new(0);
+/// Wakes a paused or finished replay thread when the replay changes
+static CHANGED: Condvar = Condvar::new();
+
+impl Replay {
+    fn session_id(&self) -> String {
+        format!("replay-{}", self.name)
+    }
+
+    fn finished(&self) -> bool {
+        self.position >= self.end
+    }
+
+    /// Paused, or at the end with nothing to loop back to
+    fn idle(&self) -> bool {
+        self.speed == 0.0 || (self.finished() && !self.looping)
+    }
+
+    fn info(&self) -> ReplayInfo {
+        ReplayInfo {
+            session_id: self.session_id(),
+            path: self.path.clone(),
+            start: self.start.to_rfc3339(),
+            end: self.end.to_rfc3339(),
+            position: self.position.to_rfc3339(),
+            speed: self.speed,
+            looping: self.looping,
+            finished: self.finished(),
+        }
+    }
+
+    /// Move the replay clock forward by `elapsed` wall-clock time
+    fn advance(&mut self, elapsed: Duration) {
+        let step_ms = elapsed.as_secs_f64() * self.speed * 1000.0;
+        let step = ChronoDuration::milliseconds(step_ms as i64);
+        self.position = (self.position + step).min(self.end);
+
+        if self.finished() && self.looping {
+            self.position = self.start;
+        }
+    }
+
+    /// Allow rules of the root the replayed transcript is kept under, else the primary one
+    fn permissions(&self) -> Arc<PermissionChecker> {
+        let dirs = claude_dirs();
+        self.path
+            .as_deref()
+            .and_then(|path| dirs.root_of(path))
+            .or(dirs.primary())
+            .map(permission_checker)
+            .unwrap_or_default()
+    }
+
+    /// The synthetic session as the poller would have reported it at `position`
+    fn build_session(&self, permissions: &PermissionChecker) -> Session {
+        let config = config::current();
+        let clock = FixedClock(self.position);
+        let status_ctx = StatusContext {
+            clock: &clock,
+            thresholds: &config.status,
+            permissions,
+        };
+        let tail = self
+            .transcript
+            .entries_at(self.position, config.polling.tail_entries);
+        let all = self.transcript.entries_at(self.position, usize::MAX);
+        let history = TranscriptHistory::from_lines(&self.transcript.lines_at(self.position));
+
+        let status = status_ctx.determine(&tail);
+        let (cwd, git_branch) = all
+            .iter()
+            .find_map(|entry| match entry {
+                SessionEntry::User { base, .. } | SessionEntry::Assistant { base, .. } => {
+                    Some((base.cwd.clone(), base.git_branch.clone()))
+                }
+                _ => None,
+            })
+            .unwrap_or_default();
+        let first_prompt = all
+            .iter()
+            .find_map(|entry| match entry {
+                SessionEntry::User { message, .. } if !message.is_tool_result => {
+                    Some(polling::truncate_string(
+                        &message.content,
+                        config.display.first_prompt_max_chars,
+                    ))
+                }
+                _ => None,
+            })
+            .unwrap_or_else(|| "(Replay)".to_string());
+        let message_count = all
+            .iter()
+            .filter(|entry| {
+                matches!(entry, SessionEntry::User { .. } | SessionEntry::Assistant { .. })
+            })
+            .count() as u32;
+        let pending_tools: Vec<PendingTool> = status_ctx
+            .pending_tool_uses(&tail)
+            .iter()
+            .map(|tool| PendingTool::new(tool, &config.risk.rules))
+            .collect();
+
+        Session {
+            id: self.session_id(),
+            pid: 0,
+            session_name: format!("Replay: {}", self.name),
+            custom_title: None,
+            project_path: cwd
+                .map(|cwd| cwd.to_string_lossy().to_string())
+                .unwrap_or_default(),
+            git_branch,
+            first_prompt,
+            summary: history.latest_summary.clone(),
+            message_count,
+            modified: self.position.to_rfc3339(),
+            status,
+            latest_message: polling::get_latest_message_from_entries(
+                &tail,
+                config.display.latest_message_max_chars,
+            ),
+            pending_tool_name: pending_tools.first().map(|tool| tool.name.clone()),
+            pending_tools,
+            tags: vec!["replay".to_string()],
+            note: None,
+            pinned: false,
+            priority: 0,
+            context: context_usage(&tail, &config.context),
+            compaction_count: history.compactions,
+            model: history.current_model().map(str::to_string),
+            model_switches: history.models,
+            cli_version: history.version,
+            conflicts: vec![],
+            git: None,
+            resources: None,
+            embedded_terminal: false,
+        }
+    }
+}
+
+fn lock() -> MutexGuard<'static, Option<Replay>> {
+    REPLAY.lock().unwrap_or_else(|poisoned| poisoned.into_inner())
+}
+
+/// The replayed session, if a replay is running
+pub fn current_session() -> Option<Session> {
+    lock().as_ref().and_then(|replay| replay.session.clone())
+}
+
+/// Entries of the replayed session written so far, for the conversation view
+pub fn entries_for(session_id: &str) -> Option<Vec<SessionEntry>> {
+    let guard = lock();
+    let replay = guard.as_ref().filter(|r| r.session_id() == session_id)?;
+    Some(replay.transcript.entries_at(replay.position, usize::MAX))
+}
+
+/// State of the running replay
+pub fn info() -> Option<ReplayInfo> {
+    lock().as_ref().map(Replay::info)
+}
+
+/// Start replaying `path` (or the built-in demo), replacing any running replay
+pub fn start(
+    app: AppHandle,
+    path: Option<&Path>,
+    speed: f64,
+    looping: bool,
+) -> Result<ReplayInfo, String> {
+    validate_speed(speed)?;
+
+    let (transcript, name) = match path {
+        Some(path) => (
+            Transcript::from_file(path)?,
+            path.file_stem()
+                .map(|stem| stem.to_string_lossy().to_string())
+                .unwrap_or_else(|| "transcript".to_string()),
+        ),
+        None => (
+            Transcript::from_lines(DEMO_TRANSCRIPT.lines().map(str::to_string)),
+            "demo".to_string(),
+        ),
+    };
+    let (start, last) = transcript
+        .start()
+        .zip(transcript.end())
+        .ok_or("Transcript has no timestamped entries")?;
+
+    let generation = GENERATION.fetch_add(1, Ordering::SeqCst) + 1;
+
+    let mut replay = Replay {
+        generation,
+        transcript,
+        path: path.map(Path::to_path_buf),
+        name,
+        start,
+        end: last + ChronoDuration::seconds(IDLE_TAIL_SECS),
+        position: start,
+        speed,
+        looping,
+        session: None,
+    };
+    replay.session = Some(replay.build_session(&replay.permissions()));
+    let info = replay.info();
+    *lock() = Some(replay);
+    CHANGED.notify_all();
+
+    polling::reemit_sessions(&app);
+    let _ = app.emit("replay-updated", &info);
+
+    thread::spawn(move || run(app, generation));
+    Ok(info)
+}
+
+/// Stop the running replay and drop its session from the list
+pub fn stop(app: &AppHandle) {
+    if lock().take().is_some() {
+        CHANGED.notify_all();
+        polling::reemit_sessions(app);
+    }
+}
+
+/// Change the playback speed (0 pauses)
+pub fn set_speed(speed: f64) -> Result<ReplayInfo, String> {
+    validate_speed(speed)?;
+    let mut guard = lock();
+    let replay = guard.as_mut().ok_or("No replay is running")?;
+    replay.speed = speed;
+    CHANGED.notify_all();
+    Ok(replay.info())
+}
+
+/// Jump to `offset_ms` after the start of the transcript
+pub fn seek(app: &AppHandle, offset_ms: u64) -> Result<ReplayInfo, String> {
+    let info = {
+        let mut guard = lock();
+        let replay = guard.as_mut().ok_or("No replay is running")?;
+        replay.position =
+            (replay.start + ChronoDuration::milliseconds(offset_ms as i64)).min(replay.end);
+        replay.session = Some(replay.build_session(&replay.permissions()));
+        replay.info()
+    };
+    CHANGED.notify_all();
+
+    polling::reemit_sessions(app);
+    let _ = app.emit("replay-updated", &info);
+    Ok(info)
+}
+
+fn validate_speed(speed: f64) -> Result<(), String> {
+    if speed.is_finite() && (0.0..=1000.0).contains(&speed) {
+        Ok(())
+    } else {
+        Err("Replay speed must be between 0 and 1000".to_string())
+    }
+}
+
+/// Replay loop: advance the clock and re-emit whenever the session changes
+///
+/// While the replay is paused or finished the thread sleeps on [`CHANGED`]
+/// until it is resumed, seeked, stopped or replaced.
+fn run(app: AppHandle, generation: u64) {
+    let mut last_tick = Instant::now();
+
+    loop {
+        thread::sleep(TICK);
+
+        let update = {
+            let mut guard = lock();
+            while guard
+                .as_ref()
+                .is_some_and(|replay| replay.generation == generation && replay.idle())
+            {
+                guard = CHANGED.wait(guard).unwrap_or_else(|poisoned| poisoned.into_inner());
+                // Time spent paused doesn't move the replay
+                last_tick = Instant::now();
+            }
+            let replay = match guard.as_mut() {
+                Some(replay) if replay.generation == generation => replay,
+                // Stopped or replaced by a newer replay
+                _ => return,
+            };
+
+            replay.advance(last_tick.elapsed());
+            last_tick = Instant::now();
+            let session = replay.build_session(&replay.permissions());
+            if replay.session.as_ref() == Some(&session) {
+                None
+            } else {
+                replay.session = Some(session);
+                Some(replay.info())
+            }
+        };
+
+        if let Some(info) = update {
+            polling::reemit_sessions(&app);
+            let _ = app.emit("replay-updated", &info);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::session::SessionStatus;
+
+    fn demo_replay() -> Replay {
+        let transcript = Transcript::from_lines(DEMO_TRANSCRIPT.lines().map(str::to_string));
+        let start = transcript.start().unwrap();
+        let end = transcript.end().unwrap() + ChronoDuration::seconds(IDLE_TAIL_SECS);
+        Replay {
+            generation: 1,
+            transcript,
+            path: None,
+            name: "demo".to_string(),
+            start,
+            end,
+            position: start,
+            speed: 1.0,
+            looping: false,
+            session: None,
+        }
+    }
+
+    #[test]
+    fn test_demo_session_walks_through_statuses() {
+        let mut replay = demo_replay();
+        let mut seen = Vec::new();
+
+        while !replay.finished() {
+            replay.advance(Duration::from_secs(1));
+            let status = replay.build_session(&PermissionChecker::default()).status;
+            if seen.last() != Some(&status) {
+                seen.push(status);
+            }
+        }
+
+        assert!(seen.contains(&SessionStatus::Working));
+        assert!(seen.contains(&SessionStatus::NeedsPermission));
+        assert_eq!(seen.last(), Some(&SessionStatus::WaitingForInput));
+    }
+
+    #[test]
+    fn test_synthetic_session_fields() {
+        let mut replay = demo_replay();
+        replay.advance(Duration::from_secs(15));
+        // No allow rules, so the result doesn't depend on the machine's settings
+        let session = replay.build_session(&PermissionChecker::default());
+
+        assert_eq!(session.id, "replay-demo");
+        assert_eq!(session.project_path, "/work/acme-api");
+        assert_eq!(session.git_branch.as_deref(), Some("feature/oauth"));
+        assert_eq!(session.status, SessionStatus::NeedsPermission);
+        assert_eq!(session.pending_tool_name.as_deref(), Some("Edit"));
+        assert!(session.first_prompt.starts_with("Add Google"));
+        assert!(session.model.is_some());
+        assert_eq!(session.model_switches.len(), 1);
+    }
+
+    #[test]
+    fn test_speed_and_looping() {
+        let mut replay = demo_replay();
+        replay.speed = 10.0;
+        replay.advance(Duration::from_secs(3));
+        assert_eq!(replay.position, replay.start + ChronoDuration::seconds(30));
+
+        replay.looping = true;
+        replay.advance(Duration::from_secs(60));
+        assert_eq!(replay.position, replay.start);
+        assert!(!replay.idle());
+        replay.speed = 0.0;
+        assert!(replay.idle());
+        assert!(validate_speed(-1.0).is_err());
+        assert!(validate_speed(f64::NAN).is_err());
+    }
+}
@@ -0,0 +1,164 @@
+use std::ffi::OsString;
+use std::path::{Path, PathBuf};
+use std::sync::OnceLock;
+
+/// Environment variable Claude Code uses to relocate its data directory
+pub const CLAUDE_CONFIG_DIR_ENV: &str = "CLAUDE_CONFIG_DIR";
+
+/// Locations of the Claude Code data directories c9watch watches
+///
+/// Normally this is just `~/.claude`. `CLAUDE_CONFIG_DIR` may point somewhere
+/// else, or list several directories separated like `PATH` for people who keep
+/// multiple profiles. Sessions that were started without the variable still
+/// write to `~/.claude`, so the default root is always watched as well and
+/// comes first.
+#[derive(Debug, Clone, PartialEq)]
+pub struct ClaudeDirs {
+    roots: Vec<PathBuf>,
+}
+
+static CLAUDE_DIRS: OnceLock<ClaudeDirs> = OnceLock::new();
+
+/// Process-wide Claude directories, resolved from the environment on first use
+pub fn claude_dirs() -> &'static ClaudeDirs {
+    CLAUDE_DIRS.get_or_init(ClaudeDirs::from_env)
+}
+
+impl ClaudeDirs {
+    /// Use exactly these roots (duplicates are dropped, order is kept)
+    pub fn new<I: IntoIterator<Item = PathBuf>>(roots: I) -> Self {
+        let mut unique: Vec<PathBuf> = Vec::new();
+        for root in roots {
+            if !unique.contains(&root) {
+                unique.push(root);
+            }
+        }
+        Self { roots: unique }
+    }
+
+    /// Resolve the roots from `$HOME` and `CLAUDE_CONFIG_DIR`
+    pub fn from_env() -> Self {
+        Self::resolve(dirs::home_dir(), std::env::var_os(CLAUDE_CONFIG_DIR_ENV))
+    }
+
+    fn resolve(home: Option<PathBuf>, config_dir: Option<OsString>) -> Self {
+        let default_root = home.map(|h| h.join(".claude"));
+        let configured = config_dir
+            .map(|value| {
+                std::env::split_paths(&value)
+                    .filter(|p| !p.as_os_str().is_empty())
+                    .collect::<Vec<_>>()
+            })
+            .unwrap_or_default();
+
+        Self::new(default_root.into_iter().chain(configured))
+    }
+
+    /// All watched roots, default first
+    pub fn roots(&self) -> &[PathBuf] {
+        &self.roots
+    }
+
+    /// Root holding c9watch's own files (the default `~/.claude` when available)
+    pub fn primary(&self) -> Option<&Path> {
+        self.roots.first().map(PathBuf::as_path)
+    }
+
+    /// `projects/` directory of every root
+    pub fn projects_dirs(&self) -> Vec<PathBuf> {
+        self.roots.iter().map(|root| root.join("projects")).collect()
+    }
+
+    /// Root whose `projects/` directory contains `path`
+    pub fn root_of(&self, path: &Path) -> Option<&Path> {
+        self.roots
+            .iter()
+            .find(|root| path.starts_with(root.join("projects")))
+            .map(PathBuf::as_path)
+    }
+
+    /// Locate a session transcript (`<session_id>.jsonl`) in any project of any root
+    pub fn find_session_file(&self, session_id: &str) -> Option<PathBuf> {
+        let session_filename = format!("{}.jsonl", session_id);
+
+        for projects_dir in self.projects_dirs() {
+            let entries = match std::fs::read_dir(&projects_dir) {
+                Ok(entries) => entries,
+                Err(_) => continue,
+            };
+
+            for entry in entries.flatten() {
+                let session_file = entry.path().join(&session_filename);
+                if session_file.is_file() {
+                    return Some(session_file);
+                }
+            }
+        }
+
+        None
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_default_root_only() {
+        let dirs = ClaudeDirs::resolve(Some(PathBuf::from("/home/me")), None);
+        assert_eq!(dirs.roots(), &[PathBuf::from("/home/me/.claude")]);
+        assert_eq!(
+            dirs.projects_dirs(),
+            vec![PathBuf::from("/home/me/.claude/projects")]
+        );
+    }
+
+    #[test]
+    fn test_config_dir_adds_roots_after_default() {
+        let value = std::env::join_paths(["/data/claude-work", "/data/claude-personal"]).unwrap();
+        let dirs = ClaudeDirs::resolve(Some(PathBuf::from("/home/me")), Some(value));
+        assert_eq!(
+            dirs.roots(),
+            &[
+                PathBuf::from("/home/me/.claude"),
+                PathBuf::from("/data/claude-work"),
+                PathBuf::from("/data/claude-personal"),
+            ]
+        );
+        assert_eq!(dirs.primary(), Some(Path::new("/home/me/.claude")));
+        assert_eq!(
+            dirs.root_of(Path::new("/data/claude-work/projects/-src-api")),
+            Some(Path::new("/data/claude-work"))
+        );
+        assert_eq!(dirs.root_of(Path::new("/data/claude-work/todos")), None);
+    }
+
+    #[test]
+    fn test_config_dir_pointing_at_default_is_not_duplicated() {
+        let dirs = ClaudeDirs::resolve(
+            Some(PathBuf::from("/home/me")),
+            Some(OsString::from("/home/me/.claude")),
+        );
+        assert_eq!(dirs.roots().len(), 1);
+    }
+
+    #[test]
+    fn test_no_home_uses_config_dir() {
+        let dirs = ClaudeDirs::resolve(None, Some(OsString::from("/srv/claude")));
+        assert_eq!(dirs.primary(), Some(Path::new("/srv/claude")));
+    }
+
+    #[test]
+    fn test_find_session_file_across_roots() {
+        let base = std::env::temp_dir().join(format!("c9watch-dirs-test-{}", std::process::id()));
+        let project = base.join("second").join("projects").join("-work-api");
+        std::fs::create_dir_all(&project).unwrap();
+        std::fs::write(project.join("abc.jsonl"), "").unwrap();
+
+        let dirs = ClaudeDirs::new([base.join("first"), base.join("second")]);
+        assert_eq!(dirs.find_session_file("abc"), Some(project.join("abc.jsonl")));
+        assert_eq!(dirs.find_session_file("missing"), None);
+
+        let _ = std::fs::remove_dir_all(&base);
+    }
+}
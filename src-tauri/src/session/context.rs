@@ -0,0 +1,124 @@
+use super::history::SYNTHETIC_MODEL;
+use super::parser::{SessionEntry, SystemEvent};
+use crate::config::ContextConfig;
+use serde::Serialize;
+
+/// How full a session's context window is, as of its latest assistant message
+#[derive(Debug, Clone, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ContextUsage {
+    pub model: String,
+    /// Input plus cache creation and cache read tokens of the latest request
+    pub tokens: u64,
+    pub window_tokens: u64,
+    /// 0-100, rounded to one decimal
+    pub percent: f64,
+}
+
+/// Compute the context fill from the latest usage in `entries`
+///
+/// Returns None when no assistant message carries usage, or when the
+/// conversation was compacted after it (the old figure no longer applies).
+pub fn context_usage(entries: &[SessionEntry], config: &ContextConfig) -> Option<ContextUsage> {
+    for entry in entries.iter().rev() {
+        match entry {
+            SessionEntry::System {
+                event: SystemEvent::CompactBoundary { .. },
+                ..
+            } => return None,
+            SessionEntry::Assistant { base, message } => {
+                // Sub-agents have a context of their own
+                if base.is_sidechain == Some(true) || message.model == SYNTHETIC_MODEL {
+                    continue;
+                }
+                let Some(usage) = &message.usage else {
+                    continue;
+                };
+
+                let tokens = [
+                    usage.input_tokens,
+                    usage.cache_creation_input_tokens,
+                    usage.cache_read_input_tokens,
+                ]
+                .iter()
+                .map(|t| t.unwrap_or(0) as u64)
+                .sum::<u64>();
+
+                // More tokens than the window allows means the session runs
+                // with the 1M context beta
+                let mut window_tokens = config.window_for(&message.model);
+                if tokens > window_tokens {
+                    window_tokens = window_tokens.max(1_000_000);
+                }
+                let percent = (tokens as f64 / window_tokens as f64 * 1000.0).round() / 10.0;
+
+                return Some(ContextUsage {
+                    model: message.model.clone(),
+                    tokens,
+                    window_tokens,
+                    percent: percent.min(100.0),
+                });
+            }
+            _ => {}
+        }
+    }
+
+    None
+}
+
+/// Whether the fill level went from below `threshold` to at or above it
+pub fn crossed_threshold(previous: Option<f64>, current: f64, threshold: u8) -> bool {
+    threshold > 0
+        && current >= threshold as f64
+        && previous.is_some_and(|previous| previous < threshold as f64)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::session::parser::parse_jsonl_entries;
+
+    fn assistant(model: &str, input: u32, cache_read: u32) -> String {
+        format!(
+            r#"{{"type":"assistant","uuid":"a","timestamp":"2026-03-01T12:00:00Z","message":{{"model":"{}","id":"msg","role":"assistant","content":[],"stop_reason":null,"stop_sequence":null,"usage":{{"input_tokens":{},"output_tokens":50,"cache_creation_input_tokens":1000,"cache_read_input_tokens":{}}}}}}}"#,
+            model, input, cache_read
+        )
+    }
+
+    const BOUNDARY: &str = r#"{"type":"system","subtype":"compact_boundary","content":"Conversation compacted","level":"info","uuid":"s","timestamp":"2026-03-01T12:00:01Z","compactMetadata":{"trigger":"auto","preTokens":170000}}"#;
+
+    #[test]
+    fn test_context_usage_from_latest_message() {
+        let config = ContextConfig::default();
+        let entries = parse_jsonl_entries(vec![
+            assistant("claude-opus-4-5", 100, 50_000),
+            assistant("claude-opus-4-5", 10, 149_990),
+            assistant(SYNTHETIC_MODEL, 0, 0),
+        ]);
+
+        let usage = context_usage(&entries, &config).unwrap();
+        assert_eq!(usage.tokens, 151_000);
+        assert_eq!(usage.window_tokens, 200_000);
+        assert_eq!(usage.percent, 75.5);
+
+        // Past the default window means the 1M beta is on
+        let entries = parse_jsonl_entries(vec![assistant("claude-sonnet-4-5", 10, 300_000)]);
+        assert_eq!(context_usage(&entries, &config).unwrap().window_tokens, 1_000_000);
+
+        // After a compaction the old figure is stale
+        let entries = parse_jsonl_entries(vec![
+            assistant("claude-opus-4-5", 10, 170_000),
+            BOUNDARY.to_string(),
+        ]);
+        assert_eq!(context_usage(&entries, &config), None);
+    }
+
+    #[test]
+    fn test_crossed_threshold() {
+        assert!(crossed_threshold(Some(79.9), 80.0, 80));
+        assert!(!crossed_threshold(Some(81.0), 85.0, 80));
+        // First sighting of a session doesn't notify
+        assert!(!crossed_threshold(None, 90.0, 80));
+        assert!(!crossed_threshold(Some(10.0), 90.0, 0));
+    }
+}
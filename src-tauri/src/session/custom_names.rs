@@ -1,40 +1,30 @@
+use super::claude_dirs::ClaudeDirs;
+use serde::de::DeserializeOwned;
 use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
 use std::fs;
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
 
+/// Names from the JSON file c9watch used before the store, read once to import them
 #[derive(Debug, Serialize, Deserialize, Default)]
 pub struct CustomNames {
     pub names: HashMap<String, String>,
 }
 
+/// Titles from the JSON file c9watch used before the store, read once to import them
 #[derive(Debug, Serialize, Deserialize, Default)]
 pub struct CustomTitles {
     pub titles: HashMap<String, String>,
 }
 
 impl CustomNames {
-    pub fn load() -> Self {
-        let path = Self::get_path();
-        if let Ok(content) = fs::read_to_string(path) {
-            serde_json::from_str(&content).unwrap_or_default()
-        } else {
-            Self::default()
-        }
-    }
-
-    pub fn save(&self) -> Result<(), String> {
-        let path = Self::get_path();
-        if let Some(parent) = path.parent() {
-            fs::create_dir_all(parent).map_err(|e| e.to_string())?;
-        }
-        let content = serde_json::to_string_pretty(self).map_err(|e| e.to_string())?;
-        fs::write(path, content).map_err(|e| e.to_string())
+    /// Load custom names, failing loudly if the file exists but can't be read
+    pub fn load(dirs: &ClaudeDirs) -> Result<Self, String> {
+        load_json(&Self::get_path(dirs)?)
     }
 
-    fn get_path() -> PathBuf {
-        let home = dirs::home_dir().expect("Failed to get home directory");
-        home.join(".claude").join("session-monitor-names.json")
+    fn get_path(dirs: &ClaudeDirs) -> Result<PathBuf, String> {
+        legacy_file_path(dirs, "session-monitor-names.json")
     }
 
     pub fn get(&self, session_id: &str) -> Option<&String> {
@@ -47,27 +37,13 @@ impl CustomNames {
 }
 
 impl CustomTitles {
-    pub fn load() -> Self {
-        let path = Self::get_path();
-        if let Ok(content) = fs::read_to_string(path) {
-            serde_json::from_str(&content).unwrap_or_default()
-        } else {
-            Self::default()
-        }
+    /// Load custom titles, failing loudly if the file exists but can't be read
+    pub fn load(dirs: &ClaudeDirs) -> Result<Self, String> {
+        load_json(&Self::get_path(dirs)?)
     }
 
-    pub fn save(&self) -> Result<(), String> {
-        let path = Self::get_path();
-        if let Some(parent) = path.parent() {
-            fs::create_dir_all(parent).map_err(|e| e.to_string())?;
-        }
-        let content = serde_json::to_string_pretty(self).map_err(|e| e.to_string())?;
-        fs::write(path, content).map_err(|e| e.to_string())
-    }
-
-    fn get_path() -> PathBuf {
-        let home = dirs::home_dir().expect("Failed to get home directory");
-        home.join(".claude").join("session-monitor-titles.json")
+    fn get_path(dirs: &ClaudeDirs) -> Result<PathBuf, String> {
+        legacy_file_path(dirs, "session-monitor-titles.json")
     }
 
     pub fn get(&self, session_id: &str) -> Option<&String> {
@@ -78,3 +54,72 @@ impl CustomTitles {
         self.titles.insert(session_id, title);
     }
 }
+
+/// The name/title files live in the primary Claude root, where c9watch always kept them
+fn legacy_file_path(dirs: &ClaudeDirs, file_name: &str) -> Result<PathBuf, String> {
+    dirs.primary()
+        .map(|root| root.join(file_name))
+        .ok_or_else(|| "No Claude data directory configured".to_string())
+}
+
+/// Copy each legacy file that exists to `<file>.bak` beside it
+pub fn backup_legacy_files(dirs: &ClaudeDirs) -> Result<(), String> {
+    for path in [CustomNames::get_path(dirs)?, CustomTitles::get_path(dirs)?] {
+        if !path.is_file() {
+            continue;
+        }
+        let backup = path.with_extension("json.bak");
+        fs::copy(&path, &backup)
+            .map_err(|e| format!("Failed to back up {}: {}", path.display(), e))?;
+    }
+    Ok(())
+}
+
+/// Read a JSON file, treating a missing file as empty
+///
+/// An unreadable or corrupt file is an error rather than an empty default,
+/// so the import never records names it failed to read as imported.
+fn load_json<T: DeserializeOwned + Default>(path: &Path) -> Result<T, String> {
+    let content = match fs::read_to_string(path) {
+        Ok(content) => content,
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => return Ok(T::default()),
+        Err(e) => return Err(format!("Failed to read {}: {}", path.display(), e)),
+    };
+
+    serde_json::from_str(&content)
+        .map_err(|e| format!("{} is not valid JSON ({})", path.display(), e))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn temp_path(name: &str) -> PathBuf {
+        let dir = std::env::temp_dir().join(format!(
+            "c9watch-names-test-{}-{}",
+            name,
+            std::process::id()
+        ));
+        let _ = fs::remove_dir_all(&dir);
+        fs::create_dir_all(&dir).unwrap();
+        dir.join("session-monitor-titles.json")
+    }
+
+    #[test]
+    fn test_missing_file_loads_as_empty() {
+        let path = temp_path("missing");
+        let titles: CustomTitles = load_json(&path).unwrap();
+        assert!(titles.titles.is_empty());
+    }
+
+    #[test]
+    fn test_corrupt_file_is_an_error() {
+        let path = temp_path("corrupt");
+        fs::write(&path, "{\"titles\": {\"s1\": ").unwrap();
+        assert!(load_json::<CustomTitles>(&path).is_err());
+
+        fs::write(&path, "{\"titles\": {\"s1\": \"one\"}}").unwrap();
+        let titles: CustomTitles = load_json(&path).unwrap();
+        assert_eq!(titles.get("s1"), Some(&"one".to_string()));
+    }
+}
@@ -1,3 +1,4 @@
+use super::claude_dirs::{claude_dirs, ClaudeDirs};
 use serde::{Deserialize, Serialize};
 use std::fs;
 use std::path::{Path, PathBuf};
@@ -33,25 +34,30 @@ pub struct DetectedSession {
 
     /// Project name (derived from cwd)
     pub project_name: String,
+
+    /// The session ID came from the process's command line (`--session-id` or
+    /// `--resume`) rather than from matching session files, so it is known
+    /// before the session file exists
+    #[serde(default)]
+    pub id_from_args: bool,
 }
 
 /// Session detector that finds running Claude processes and matches them to session files
 pub struct SessionDetector {
     system: System,
-    claude_projects_dir: PathBuf,
+    claude_projects_dirs: Vec<PathBuf>,
 }
 
 impl SessionDetector {
-    /// Creates a new SessionDetector
-    pub fn new() -> Result<Self, SessionDetectorError> {
-        let home_dir = dirs::home_dir()
-            .ok_or(SessionDetectorError::HomeDirectoryNotFound)?;
-
-        let claude_projects_dir = home_dir.join(".claude").join("projects");
+    /// Creates a new SessionDetector watching the `projects/` dir of every Claude root
+    pub fn new(dirs: &ClaudeDirs) -> Result<Self, SessionDetectorError> {
+        if dirs.roots().is_empty() {
+            return Err(SessionDetectorError::HomeDirectoryNotFound);
+        }
 
         Ok(Self {
             system: System::new_all(),
-            claude_projects_dir,
+            claude_projects_dirs: dirs.projects_dirs(),
         })
     }
 
@@ -161,7 +167,52 @@ impl SessionDetector {
         let mut sorted_processes: Vec<&ClaudeProcess> = processes.iter().collect();
         sorted_processes.sort_by(|a, b| b.start_time.cmp(&a.start_time));
 
+        // Processes started with an explicit session ID own that session. Before
+        // its file exists that is only trusted for sessions c9watch launched:
+        // others may belong to a Claude root this detector doesn't watch.
+        for proc in &sorted_processes {
+            let (Some(proc_cwd), Some(arg)) = (&proc.cwd, &proc.session_id_arg) else {
+                continue;
+            };
+            let session_id = arg.id();
+
+            let existing = session_files.iter().find(|(_, path, ..)| {
+                path.file_stem().and_then(|s| s.to_str()) == Some(session_id)
+            });
+            let (project_path, project_name) = match (existing, arg) {
+                (Some((_, _, project_dir, _, project_name, _)), _) => {
+                    (project_dir.clone(), project_name.clone())
+                }
+                (None, SessionIdArg::New(_)) if crate::launch::was_launched(session_id) => {
+                    let Some(projects_dir) = self.claude_projects_dirs.first() else {
+                        continue;
+                    };
+                    let name = proc_cwd
+                        .file_name()
+                        .map(|n| n.to_string_lossy().to_string())
+                        .unwrap_or_else(|| "unknown".to_string());
+                    (projects_dir.join(encode_project_dir(proc_cwd)), name)
+                }
+                // Resuming a session kept under another Claude root, or started
+                // elsewhere and not written yet
+                (None, _) => continue,
+            };
+
+            used_session_ids.insert(session_id.to_string());
+            sessions.push(DetectedSession {
+                pid: proc.pid,
+                cwd: proc_cwd.clone(),
+                project_path,
+                session_id: Some(session_id.to_string()),
+                project_name,
+                id_from_args: true,
+            });
+        }
+
         for proc in sorted_processes {
+            if proc.session_id_arg.is_some() {
+                continue;
+            }
             let proc_cwd = match &proc.cwd {
                 Some(cwd) => cwd,
                 None => continue, // Skip processes without cwd
@@ -235,6 +286,7 @@ impl SessionDetector {
                         project_path: project_dir.clone(),
                         session_id: Some(session_id),
                         project_name: project_name.clone(),
+                        id_from_args: false,
                     });
                 }
             }
@@ -299,11 +351,17 @@ impl SessionDetector {
                 // Get the current working directory of the process
                 let cwd = process.cwd().map(|p| p.to_path_buf());
                 let start_time = process.start_time();
+                let args: Vec<String> = process
+                    .cmd()
+                    .iter()
+                    .map(|arg| arg.to_string_lossy().to_string())
+                    .collect();
 
                 processes.push(ClaudeProcess {
                     pid: pid.as_u32(),
                     cwd,
                     start_time,
+                    session_id_arg: session_id_from_args(&args),
                 });
             }
         }
@@ -311,25 +369,27 @@ impl SessionDetector {
         processes
     }
 
-    /// Enumerates all project directories in ~/.claude/projects/
+    /// Enumerates all project directories in every `<claude root>/projects/`
     fn enumerate_project_directories(&self) -> Result<Vec<PathBuf>, SessionDetectorError> {
         let mut project_dirs = Vec::new();
 
-        // Check if the claude projects directory exists
-        if !self.claude_projects_dir.exists() {
-            return Ok(project_dirs);
-        }
+        for claude_projects_dir in &self.claude_projects_dirs {
+            // Skip roots that don't have a projects directory (yet)
+            if !claude_projects_dir.exists() {
+                continue;
+            }
 
-        // Read all entries in the projects directory
-        let entries = fs::read_dir(&self.claude_projects_dir)?;
+            // Read all entries in the projects directory
+            let entries = fs::read_dir(claude_projects_dir)?;
 
-        for entry in entries {
-            let entry = entry?;
-            let path = entry.path();
+            for entry in entries {
+                let entry = entry?;
+                let path = entry.path();
 
-            // Only include directories
-            if path.is_dir() {
-                project_dirs.push(path);
+                // Only include directories
+                if path.is_dir() {
+                    project_dirs.push(path);
+                }
             }
         }
 
@@ -340,7 +400,7 @@ impl SessionDetector {
 
 impl Default for SessionDetector {
     fn default() -> Self {
-        Self::new().expect("Failed to create SessionDetector")
+        Self::new(claude_dirs()).expect("Failed to create SessionDetector")
     }
 }
 
@@ -350,6 +410,57 @@ struct ClaudeProcess {
     pid: u32,
     cwd: Option<PathBuf>,
     start_time: u64, // Process start time (seconds since epoch)
+    session_id_arg: Option<SessionIdArg>, // Session ID given on the command line
+}
+
+/// Session ID named on a claude command line
+#[derive(Debug, Clone, PartialEq)]
+enum SessionIdArg {
+    /// `--session-id`: a new session; its file appears with the first entry
+    New(String),
+    /// `--resume`: an existing session, whose file must already exist
+    Resume(String),
+}
+
+impl SessionIdArg {
+    fn id(&self) -> &str {
+        match self {
+            SessionIdArg::New(id) | SessionIdArg::Resume(id) => id,
+        }
+    }
+}
+
+/// Session ID a claude command line names with `--session-id` or `--resume`
+///
+/// `--resume` without an ID opens a picker, so it doesn't count.
+fn session_id_from_args(args: &[String]) -> Option<SessionIdArg> {
+    let looks_like_id = |value: &str| value.len() == 36 && value.chars().all(|c| c.is_ascii_hexdigit() || c == '-');
+
+    args.iter().enumerate().find_map(|(i, arg)| {
+        let (flag, value) = match arg.split_once('=') {
+            Some((flag @ ("--session-id" | "--resume"), value)) => (flag, value),
+            _ if matches!(arg.as_str(), "--session-id" | "--resume" | "-r") => {
+                (arg.as_str(), args.get(i + 1)?.as_str())
+            }
+            _ => return None,
+        };
+        if !looks_like_id(value) {
+            return None;
+        }
+        Some(match flag {
+            "--session-id" => SessionIdArg::New(value.to_string()),
+            _ => SessionIdArg::Resume(value.to_string()),
+        })
+    })
+}
+
+/// Directory name Claude Code stores a project's sessions under: the cwd
+/// with every character other than ASCII letters and digits replaced by `-`
+pub fn encode_project_dir(cwd: &Path) -> String {
+    cwd.to_string_lossy()
+        .chars()
+        .map(|c| if c.is_ascii_alphanumeric() { c } else { '-' })
+        .collect()
 }
 
 /// Structure of sessions-index.json
@@ -384,28 +495,109 @@ struct SessionEntry {
 mod tests {
     use super::*;
 
+    #[test]
+    fn test_session_id_from_args() {
+        let id = "0b7e3f52-3c55-4d0e-9f36-2f1e6d5c4a10";
+        let args = |line: &str| line.split(' ').map(str::to_string).collect::<Vec<_>>();
+
+        assert_eq!(
+            session_id_from_args(&args(&format!("claude --session-id {} fix it", id))),
+            Some(SessionIdArg::New(id.to_string()))
+        );
+        assert_eq!(
+            session_id_from_args(&args(&format!("claude --resume={}", id))),
+            Some(SessionIdArg::Resume(id.to_string()))
+        );
+        assert_eq!(
+            session_id_from_args(&args(&format!("claude -r {}", id))),
+            Some(SessionIdArg::Resume(id.to_string()))
+        );
+        // The picker, and prompts that merely mention the flag
+        assert_eq!(session_id_from_args(&args("claude --resume")), None);
+        assert_eq!(session_id_from_args(&args("claude --resume --model opus")), None);
+        assert_eq!(
+            encode_project_dir(Path::new("/home/dev/my_app.v2")),
+            "-home-dev-my-app-v2"
+        );
+    }
+
     #[test]
     fn test_detector_creation() {
-        let result = SessionDetector::new();
+        let result = SessionDetector::new(claude_dirs());
         assert!(result.is_ok());
     }
 
+    #[test]
+    fn test_detector_requires_a_root() {
+        let result = SessionDetector::new(&ClaudeDirs::new([]));
+        assert!(matches!(result, Err(SessionDetectorError::HomeDirectoryNotFound)));
+    }
+
+    #[cfg(target_os = "linux")]
     #[test]
     fn test_find_claude_processes() {
-        let detector = SessionDetector::new().unwrap();
+        use crate::test_support::{FakeClaude, SimulatedClaudeHome};
+
+        let home = SimulatedClaudeHome::new("detector-procs");
+        let cwd = home.workspace("repo");
+        let claude = FakeClaude::spawn(&home, &cwd, "session").unwrap();
+
+        let detector = SessionDetector::new(&home.dirs()).unwrap();
         let processes = detector.find_claude_processes();
-        // This test will vary based on whether claude is running
-        println!("Found {} claude processes", processes.len());
+        let found = processes
+            .iter()
+            .find(|p| p.pid == claude.pid())
+            .expect("fake claude process should be found");
+        assert_eq!(found.cwd.as_deref(), Some(cwd.as_path()));
+    }
+
+    #[cfg(target_os = "linux")]
+    #[test]
+    fn test_unwritten_session_id_is_only_claimed_when_launched_here() {
+        use crate::test_support::{claude_processes_lock, SimulatedClaudeHome};
+        use std::process::{Command, Stdio};
+
+        let _processes = claude_processes_lock();
+        let home = SimulatedClaudeHome::new("detector-foreign");
+        let mut child = Command::new(home.fake_cli().unwrap())
+            .args(["--session-id", "1b9e0c2a-0000-4000-8000-000000000001"])
+            .current_dir(home.workspace("repo"))
+            .stdin(Stdio::null())
+            .spawn()
+            .unwrap();
+
+        // Seen as a claude process, but not claimed as a session
+        let mut detector = SessionDetector::new(&home.dirs()).unwrap();
+        let mut seen = false;
+        for _ in 0..50 {
+            let detected = detector.detect_sessions().unwrap();
+            assert!(detected.iter().all(|s| s.pid != child.id()));
+            seen = detector.find_claude_processes().iter().any(|p| p.pid == child.id());
+            if seen {
+                break;
+            }
+            std::thread::sleep(std::time::Duration::from_millis(20));
+        }
+        assert!(seen);
+
+        let _ = child.kill();
+        let _ = child.wait();
     }
 
+    #[cfg(target_os = "linux")]
     #[test]
     fn test_enumerate_project_directories() {
-        let detector = SessionDetector::new().unwrap();
-        let result = detector.enumerate_project_directories();
-        assert!(result.is_ok());
+        use crate::test_support::{FakeClaude, SimulatedClaudeHome};
 
-        if let Ok(dirs) = result {
-            println!("Found {} project directories", dirs.len());
-        }
+        let home = SimulatedClaudeHome::new("detector-dirs");
+        let detector = SessionDetector::new(&home.dirs()).unwrap();
+        assert!(detector.enumerate_project_directories().unwrap().is_empty());
+
+        let mut claude = FakeClaude::spawn(&home, &home.workspace("repo"), "session").unwrap();
+        claude.prompt("hello");
+
+        let dirs = detector.enumerate_project_directories().unwrap();
+        assert_eq!(dirs.len(), 1);
+        assert!(dirs[0].join("session.jsonl").is_file());
     }
 }
@@ -0,0 +1,174 @@
+use chrono::{SecondsFormat, Utc};
+use serde::Serialize;
+use std::collections::hash_map::DefaultHasher;
+use std::collections::{HashMap, HashSet};
+use std::hash::{Hash, Hasher};
+use std::sync::{Mutex, MutexGuard};
+
+/// Longest sample line kept per shape
+const SAMPLE_MAX_CHARS: usize = 500;
+
+/// Distinct lines remembered for de-duplication before the memory is reset
+const MAX_SEEN_LINES: usize = 50_000;
+
+/// A kind of JSONL line the parser couldn't fully understand
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Serialize)]
+#[serde(tag = "kind", rename_all = "camelCase")]
+pub enum Shape {
+    /// The line is not valid JSON or doesn't match the entry it claims to be
+    #[serde(rename_all = "camelCase")]
+    ParseFailure { entry_type: Option<String> },
+
+    /// An entry `type` the parser has no variant for
+    #[serde(rename_all = "camelCase")]
+    UnknownEntryType { entry_type: String },
+
+    /// A content block `type` the parser has no variant for
+    #[serde(rename_all = "camelCase")]
+    UnknownContentBlock {
+        entry_type: String,
+        block_type: String,
+    },
+}
+
+/// What has been seen of one shape
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ShapeReport {
+    #[serde(flatten)]
+    pub shape: Shape,
+    /// Distinct lines with this shape
+    pub count: u64,
+    /// RFC 3339 with fixed precision, so the strings sort chronologically
+    pub first_seen: String,
+    pub last_seen: String,
+    /// Deserialization error of the first failing line, for parse failures
+    pub error: Option<String>,
+    /// First line seen with this shape, truncated
+    pub sample: String,
+}
+
+/// Snapshot returned by the diagnostics command
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ParseDiagnostics {
+    /// Shapes ordered by most recently seen
+    pub shapes: Vec<ShapeReport>,
+}
+
+#[derive(Default)]
+struct Registry {
+    shapes: HashMap<Shape, ShapeReport>,
+    seen_lines: HashSet<(u64, Shape)>,
+}
+
+static REGISTRY: Mutex<Option<Registry>> = Mutex::new(None);
+
+fn registry() -> MutexGuard<'static, Option<Registry>> {
+    REGISTRY.lock().unwrap_or_else(|poisoned| poisoned.into_inner())
+}
+
+fn line_hash(line: &str) -> u64 {
+    let mut hasher = DefaultHasher::new();
+    line.hash(&mut hasher);
+    hasher.finish()
+}
+
+/// Record a line with an unexpected shape
+///
+/// The poller re-reads the same tail every cycle, so each distinct line is
+/// counted once. The first time a shape appears it is logged.
+pub fn record(shape: Shape, line: &str, error: Option<String>) {
+    let mut guard = registry();
+    let registry = guard.get_or_insert_with(Registry::default);
+
+    if registry.seen_lines.len() >= MAX_SEEN_LINES {
+        registry.seen_lines.clear();
+    }
+    if !registry.seen_lines.insert((line_hash(line), shape.clone())) {
+        return;
+    }
+
+    let now = Utc::now().to_rfc3339_opts(SecondsFormat::Millis, true);
+    match registry.shapes.get_mut(&shape) {
+        Some(report) => {
+            report.count += 1;
+            report.last_seen = now;
+        }
+        None => {
+            eprintln!(
+                "[parser] New JSONL shape {:?}{}: {}",
+                shape,
+                error.as_deref().map(|e| format!(" ({})", e)).unwrap_or_default(),
+                truncate(line)
+            );
+            registry.shapes.insert(
+                shape.clone(),
+                ShapeReport {
+                    shape,
+                    count: 1,
+                    first_seen: now.clone(),
+                    last_seen: now,
+                    error,
+                    sample: truncate(line),
+                },
+            );
+        }
+    }
+}
+
+/// Everything recorded since startup
+pub fn snapshot() -> ParseDiagnostics {
+    let mut shapes: Vec<ShapeReport> = registry()
+        .as_ref()
+        .map(|registry| registry.shapes.values().cloned().collect())
+        .unwrap_or_default();
+    shapes.sort_by(|a, b| b.last_seen.cmp(&a.last_seen));
+    ParseDiagnostics { shapes }
+}
+
+fn truncate(line: &str) -> String {
+    if line.chars().count() <= SAMPLE_MAX_CHARS {
+        line.to_string()
+    } else {
+        let truncated: String = line.chars().take(SAMPLE_MAX_CHARS).collect();
+        format!("{}...", truncated)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn report_for(shape: &Shape) -> Option<ShapeReport> {
+        snapshot().shapes.into_iter().find(|r| &r.shape == shape)
+    }
+
+    #[test]
+    fn test_distinct_lines_are_counted_once() {
+        let shape = Shape::UnknownEntryType {
+            entry_type: "diagnostics-test-dedupe".to_string(),
+        };
+
+        record(shape.clone(), r#"{"type":"diagnostics-test-dedupe","n":1}"#, None);
+        record(shape.clone(), r#"{"type":"diagnostics-test-dedupe","n":1}"#, None);
+        record(shape.clone(), r#"{"type":"diagnostics-test-dedupe","n":2}"#, None);
+
+        let report = report_for(&shape).unwrap();
+        assert_eq!(report.count, 2);
+        assert_eq!(report.sample, r#"{"type":"diagnostics-test-dedupe","n":1}"#);
+    }
+
+    #[test]
+    fn test_samples_are_truncated() {
+        let shape = Shape::ParseFailure {
+            entry_type: Some("diagnostics-test-long".to_string()),
+        };
+        let line = "x".repeat(SAMPLE_MAX_CHARS * 2);
+        record(shape.clone(), &line, Some("expected value".to_string()));
+
+        let report = report_for(&shape).unwrap();
+        assert_eq!(report.sample.chars().count(), SAMPLE_MAX_CHARS + 3);
+        assert_eq!(report.error.as_deref(), Some("expected value"));
+    }
+}
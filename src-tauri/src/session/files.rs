@@ -0,0 +1,392 @@
+use super::parser::{IncrementalScan, MessageContent, SessionEntry};
+use serde::Serialize;
+use std::collections::{BTreeMap, HashMap, HashSet};
+use std::path::{Component, Path, PathBuf};
+use std::sync::Mutex;
+
+/// What a session did to a file, as of its latest successful change
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "PascalCase")]
+pub enum FileChange {
+    /// The file didn't exist before the session wrote it
+    Created,
+    Edited,
+    /// Removed with `rm` (best effort: only plain paths are recognised)
+    Deleted,
+}
+
+/// One file a session changed
+#[derive(Debug, Clone, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct FileTouch {
+    pub path: String,
+    pub change: FileChange,
+    /// Successful tool calls that changed the file
+    pub touches: u32,
+    /// Timestamp of the latest of those calls' results
+    pub last_touched: String,
+}
+
+/// Files a session created, edited and deleted, most recently touched first
+#[derive(Debug, Clone, Default, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct FilesTouched {
+    pub files: Vec<FileTouch>,
+    pub created: u32,
+    pub edited: u32,
+    pub deleted: u32,
+}
+
+/// A file changed by more than one live session
+#[derive(Debug, Clone, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct FileConflict {
+    pub path: String,
+    pub session_ids: Vec<String>,
+}
+
+/// A file-changing tool call waiting for its result
+struct PendingChange {
+    paths: Vec<PathBuf>,
+    tool: ChangeTool,
+}
+
+#[derive(Clone, Copy)]
+enum ChangeTool {
+    Write,
+    Edit,
+    Remove,
+}
+
+/// Builds a [`FilesTouched`] from session entries, in transcript order
+///
+/// A tool call only counts once its result comes back without an error, so
+/// denied or failed edits are ignored.
+#[derive(Default)]
+pub struct FileTracker {
+    cwd: Option<PathBuf>,
+    pending: HashMap<String, PendingChange>,
+    files: HashMap<PathBuf, FileTouch>,
+    /// Files the file-history snapshots say didn't exist before being changed
+    new_files: HashSet<PathBuf>,
+    /// Files the file-history snapshots have a backup of
+    existing_files: HashSet<PathBuf>,
+}
+
+impl FileTracker {
+    pub fn apply(&mut self, entry: &SessionEntry) {
+        match entry {
+            SessionEntry::Assistant { base, message } => {
+                if let Some(cwd) = &base.cwd {
+                    self.cwd = Some(cwd.clone());
+                }
+                for content in &message.content {
+                    if let MessageContent::ToolUse { id, name, input } = content {
+                        if let Some(change) = self.pending_change(name, input) {
+                            self.pending.insert(id.clone(), change);
+                        }
+                    }
+                }
+            }
+            SessionEntry::User { base, message } => {
+                if let Some(cwd) = &base.cwd {
+                    self.cwd = Some(cwd.clone());
+                }
+                for result in &message.tool_results {
+                    let Some(change) = self.pending.remove(&result.tool_use_id) else {
+                        continue;
+                    };
+                    if !result.is_error {
+                        self.record(change, &base.timestamp);
+                    }
+                }
+            }
+            SessionEntry::FileHistorySnapshot { snapshot, .. } => {
+                let backups = snapshot
+                    .get("trackedFileBackups")
+                    .and_then(|b| b.as_object())
+                    .into_iter()
+                    .flatten();
+                for (path, backup) in backups {
+                    let path = self.resolve(path);
+                    if backup.get("backupFileName").is_some_and(|name| name.is_null()) {
+                        self.new_files.insert(path);
+                    } else {
+                        self.existing_files.insert(path);
+                    }
+                }
+            }
+            _ => {}
+        }
+    }
+
+    fn pending_change(&self, tool: &str, input: &serde_json::Value) -> Option<PendingChange> {
+        let path_field = |field: &str| {
+            input
+                .get(field)
+                .and_then(|p| p.as_str())
+                .map(|p| vec![self.resolve(p)])
+        };
+
+        let (paths, tool) = match tool {
+            "Write" => (path_field("file_path")?, ChangeTool::Write),
+            "Edit" | "MultiEdit" => (path_field("file_path")?, ChangeTool::Edit),
+            "NotebookEdit" => (path_field("notebook_path")?, ChangeTool::Edit),
+            "Bash" => {
+                let command = input.get("command")?.as_str()?;
+                let paths: Vec<PathBuf> = removed_paths(command)
+                    .iter()
+                    .map(|p| self.resolve(p))
+                    .collect();
+                if paths.is_empty() {
+                    return None;
+                }
+                (paths, ChangeTool::Remove)
+            }
+            _ => return None,
+        };
+        Some(PendingChange { paths, tool })
+    }
+
+    fn record(&mut self, change: PendingChange, timestamp: &str) {
+        for path in change.paths {
+            let previous = self.files.get(&path).map(|touch| touch.change);
+            let next = match (change.tool, previous) {
+                (ChangeTool::Remove, _) => FileChange::Deleted,
+                (ChangeTool::Write, None) => {
+                    // Without a snapshot, a file first seen through Write is new
+                    if self.new_files.contains(&path) || !self.existing_files.contains(&path) {
+                        FileChange::Created
+                    } else {
+                        FileChange::Edited
+                    }
+                }
+                (ChangeTool::Write, Some(FileChange::Deleted)) => FileChange::Created,
+                (ChangeTool::Edit, None | Some(FileChange::Deleted)) => FileChange::Edited,
+                (_, Some(previous)) => previous,
+            };
+
+            let touch = self.files.entry(path.clone()).or_insert_with(|| FileTouch {
+                path: path.to_string_lossy().to_string(),
+                change: next,
+                touches: 0,
+                last_touched: String::new(),
+            });
+            touch.change = next;
+            touch.touches += 1;
+            touch.last_touched = timestamp.to_string();
+        }
+    }
+
+    /// Absolute, lexically normalised form of a path from the transcript
+    fn resolve(&self, path: &str) -> PathBuf {
+        let path = Path::new(path);
+        let joined = match &self.cwd {
+            Some(cwd) if path.is_relative() => cwd.join(path),
+            _ => path.to_path_buf(),
+        };
+
+        let mut normalised = PathBuf::new();
+        for component in joined.components() {
+            match component {
+                Component::CurDir => {}
+                Component::ParentDir => {
+                    normalised.pop();
+                }
+                other => normalised.push(other),
+            }
+        }
+        normalised
+    }
+
+    pub fn summary(&self) -> FilesTouched {
+        let mut files: Vec<FileTouch> = self.files.values().cloned().collect();
+        files.sort_by(|a, b| b.last_touched.cmp(&a.last_touched).then(a.path.cmp(&b.path)));
+
+        let count = |change: FileChange| files.iter().filter(|f| f.change == change).count() as u32;
+        FilesTouched {
+            created: count(FileChange::Created),
+            edited: count(FileChange::Edited),
+            deleted: count(FileChange::Deleted),
+            files,
+        }
+    }
+}
+
+/// Files touched across `entries`
+pub fn files_touched(entries: &[SessionEntry]) -> FilesTouched {
+    let mut tracker = FileTracker::default();
+    for entry in entries {
+        tracker.apply(entry);
+    }
+    tracker.summary()
+}
+
+static TRACKERS: Mutex<Option<HashMap<PathBuf, IncrementalScan<FileTracker>>>> = Mutex::new(None);
+
+/// Files touched by a session so far, reading only what was appended since last time
+pub fn scan_files_touched(path: &Path) -> FilesTouched {
+    let mut guard = TRACKERS.lock().unwrap_or_else(|p| p.into_inner());
+    let scan = guard
+        .get_or_insert_with(HashMap::new)
+        .entry(path.to_path_buf())
+        .or_default();
+
+    scan.update(path, |tracker, line| {
+        if let Ok(entry) = serde_json::from_str::<SessionEntry>(line) {
+            tracker.apply(&entry);
+        }
+    })
+    .summary()
+}
+
+/// Drop the cached trackers of transcripts that are no longer watched
+pub fn retain_file_trackers(watched: &[PathBuf]) {
+    let mut guard = TRACKERS.lock().unwrap_or_else(|p| p.into_inner());
+    if let Some(trackers) = guard.as_mut() {
+        trackers.retain(|path, _| watched.contains(path));
+    }
+}
+
+/// Files changed by more than one of `sessions`, by path
+///
+/// Paths are absolute, so the same file in two worktrees of a repo is not a
+/// conflict, while two sessions in one checkout editing it is.
+pub fn find_conflicts<'a, I>(sessions: I) -> Vec<FileConflict>
+where
+    I: IntoIterator<Item = (&'a str, &'a FilesTouched)>,
+{
+    let mut by_path: BTreeMap<&str, Vec<String>> = BTreeMap::new();
+    for (session_id, touched) in sessions {
+        for file in &touched.files {
+            let ids = by_path.entry(file.path.as_str()).or_default();
+            if !ids.iter().any(|id| id == session_id) {
+                ids.push(session_id.to_string());
+            }
+        }
+    }
+
+    by_path
+        .into_iter()
+        .filter(|(_, ids)| ids.len() > 1)
+        .map(|(path, session_ids)| FileConflict {
+            path: path.to_string(),
+            session_ids,
+        })
+        .collect()
+}
+
+/// Plain paths removed by `rm` in a shell command
+///
+/// Globs, variables and anything else that needs a shell to expand is skipped.
+fn removed_paths(command: &str) -> Vec<String> {
+    let mut paths = Vec::new();
+    for segment in command.split(['\n', ';', '|', '&']) {
+        let mut words = segment.split_whitespace();
+        let is_rm = match words.next() {
+            Some("rm") => true,
+            Some("git") => words.next() == Some("rm"),
+            _ => false,
+        };
+        if !is_rm {
+            continue;
+        }
+
+        for word in words {
+            let word = word.trim_matches(|c| c == '"' || c == '\'');
+            if word.is_empty() || word.starts_with('-') || word.contains(['*', '?', '$', '`', '>', '<', '~']) {
+                continue;
+            }
+            paths.push(word.to_string());
+        }
+    }
+    paths
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::session::parser::parse_jsonl_entries;
+
+    fn tool_use(id: &str, name: &str, input: &str, ts: &str) -> String {
+        format!(
+            r#"{{"type":"assistant","uuid":"a-{id}","timestamp":"{ts}","cwd":"/work/api","message":{{"model":"m","id":"msg-{id}","role":"assistant","content":[{{"type":"tool_use","id":"{id}","name":"{name}","input":{input}}}],"stop_reason":null,"stop_sequence":null,"usage":null}}}}"#
+        )
+    }
+
+    fn tool_result(id: &str, is_error: bool, ts: &str) -> String {
+        format!(
+            r#"{{"type":"user","uuid":"u-{id}","timestamp":"{ts}","cwd":"/work/api","message":{{"role":"user","content":[{{"type":"tool_result","tool_use_id":"{id}","content":"ok","is_error":{is_error}}}]}}}}"#
+        )
+    }
+
+    #[test]
+    fn test_files_touched_from_tool_calls() {
+        let entries = parse_jsonl_entries(vec![
+            r#"{"type":"file-history-snapshot","messageId":"m1","isSnapshotUpdate":false,"snapshot":{"trackedFileBackups":{"/work/api/src/lib.rs":{"backupFileName":"abc@v1","version":1}}}}"#.to_string(),
+            tool_use("t1", "Write", r#"{"file_path":"src/new.rs","content":"x"}"#, "2026-03-01T12:00:00Z"),
+            tool_result("t1", false, "2026-03-01T12:00:01Z"),
+            tool_use("t2", "Edit", r#"{"file_path":"/work/api/src/lib.rs","old_string":"a","new_string":"b"}"#, "2026-03-01T12:00:02Z"),
+            tool_result("t2", false, "2026-03-01T12:00:03Z"),
+            tool_use("t3", "Edit", r#"{"file_path":"src/new.rs","old_string":"x","new_string":"y"}"#, "2026-03-01T12:00:04Z"),
+            tool_result("t3", false, "2026-03-01T12:00:05Z"),
+            tool_use("t4", "Bash", r#"{"command":"rm -f old.txt build/*.o && cargo build"}"#, "2026-03-01T12:00:06Z"),
+            tool_result("t4", false, "2026-03-01T12:00:07Z"),
+            // Failed, and still pending
+            tool_use("t5", "Write", r#"{"file_path":"/etc/hosts","content":""}"#, "2026-03-01T12:00:08Z"),
+            tool_result("t5", true, "2026-03-01T12:00:09Z"),
+            tool_use("t6", "Edit", r#"{"file_path":"src/main.rs","old_string":"a","new_string":"b"}"#, "2026-03-01T12:00:10Z"),
+        ]);
+
+        let touched = files_touched(&entries);
+        assert_eq!((touched.created, touched.edited, touched.deleted), (1, 1, 1));
+
+        let paths: Vec<(&str, FileChange, u32)> = touched
+            .files
+            .iter()
+            .map(|f| (f.path.as_str(), f.change, f.touches))
+            .collect();
+        assert_eq!(
+            paths,
+            vec![
+                ("/work/api/old.txt", FileChange::Deleted, 1),
+                ("/work/api/src/new.rs", FileChange::Created, 2),
+                ("/work/api/src/lib.rs", FileChange::Edited, 1),
+            ]
+        );
+        assert_eq!(touched.files[0].last_touched, "2026-03-01T12:00:07Z");
+    }
+
+    #[test]
+    fn test_find_conflicts() {
+        let touch = |path: &str| FilesTouched {
+            files: vec![FileTouch {
+                path: path.to_string(),
+                change: FileChange::Edited,
+                touches: 1,
+                last_touched: String::new(),
+            }],
+            ..FilesTouched::default()
+        };
+        let a = touch("/work/api/src/lib.rs");
+        let b = touch("/work/api/src/lib.rs");
+        let worktree = touch("/work/api-wt/src/lib.rs");
+
+        let conflicts = find_conflicts([("a", &a), ("b", &b), ("c", &worktree)]);
+        assert_eq!(
+            conflicts,
+            vec![FileConflict {
+                path: "/work/api/src/lib.rs".to_string(),
+                session_ids: vec!["a".to_string(), "b".to_string()],
+            }]
+        );
+    }
+
+    #[test]
+    fn test_removed_paths() {
+        assert_eq!(removed_paths("rm -rf target"), vec!["target"]);
+        assert_eq!(removed_paths("git rm 'c.txt'; ls -la"), vec!["c.txt"]);
+        assert!(removed_paths("rm *.log $TMP/x").is_empty());
+        assert!(removed_paths("cargo rm serde").is_empty());
+    }
+}
@@ -0,0 +1,404 @@
+use serde::Serialize;
+use std::collections::HashMap;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::process::Command;
+use std::sync::Mutex;
+use std::time::{Duration, Instant, SystemTime};
+
+/// How long a cached `git status` is reused while `index` and `HEAD` are
+/// unchanged; editing a tracked file touches neither, so that shows up after this
+const STATUS_MAX_AGE: Duration = Duration::from_secs(30);
+
+/// Root of the git working tree containing `dir`
+///
+/// A linked worktree has a `.git` file instead of a directory, so each
+/// worktree of a repository is its own root.
+pub fn worktree_root(dir: &Path) -> Option<PathBuf> {
+    dir.ancestors()
+        .find(|ancestor| ancestor.join(".git").exists())
+        .map(Path::to_path_buf)
+}
+
+/// Where a working tree keeps its git data
+#[derive(Debug, Clone, PartialEq)]
+pub struct RepoLocation {
+    /// Root of the working tree
+    pub worktree: PathBuf,
+    /// Git directory of this worktree (`.git`, or `.git/worktrees/<name>` for a linked one)
+    pub git_dir: PathBuf,
+    /// Git directory shared by all worktrees of the repository
+    pub common_dir: PathBuf,
+}
+
+impl RepoLocation {
+    /// Resolve the working tree containing `dir`
+    pub fn find(dir: &Path) -> Option<Self> {
+        let worktree = worktree_root(dir)?;
+        let dot_git = worktree.join(".git");
+
+        let git_dir = if dot_git.is_dir() {
+            dot_git
+        } else {
+            // Linked worktrees point at their git directory: "gitdir: <path>"
+            let contents = fs::read_to_string(&dot_git).ok()?;
+            let target = contents.trim().strip_prefix("gitdir:")?.trim();
+            worktree.join(target)
+        };
+
+        let common_dir = match fs::read_to_string(git_dir.join("commondir")) {
+            Ok(common) => git_dir.join(common.trim()),
+            Err(_) => git_dir.clone(),
+        };
+
+        Some(Self {
+            worktree,
+            git_dir: normalize(&git_dir),
+            common_dir: normalize(&common_dir),
+        })
+    }
+
+    /// The main working tree, which names the repository
+    pub fn repo_root(&self) -> PathBuf {
+        if self.common_dir.file_name().is_some_and(|name| name == ".git") {
+            self.common_dir.parent().map(Path::to_path_buf).unwrap_or_default()
+        } else {
+            // Bare repository
+            self.common_dir.clone()
+        }
+    }
+
+    pub fn is_linked_worktree(&self) -> bool {
+        self.git_dir != self.common_dir
+    }
+
+    /// What HEAD points at, read from the git directory
+    pub fn head(&self) -> Head {
+        let Ok(head) = fs::read_to_string(self.git_dir.join("HEAD")) else {
+            return Head::default();
+        };
+        let head = head.trim();
+
+        match head.strip_prefix("ref:") {
+            Some(reference) => {
+                let reference = reference.trim();
+                Head {
+                    branch: reference.strip_prefix("refs/heads/").map(str::to_string),
+                    commit: self.resolve_ref(reference),
+                }
+            }
+            None => Head {
+                branch: None,
+                commit: Some(head.to_string()),
+            },
+        }
+    }
+
+    /// Commit a ref points at, from its loose file or `packed-refs`
+    fn resolve_ref(&self, reference: &str) -> Option<String> {
+        for dir in [&self.git_dir, &self.common_dir] {
+            if let Ok(commit) = fs::read_to_string(dir.join(reference)) {
+                return Some(commit.trim().to_string());
+            }
+        }
+
+        let packed = fs::read_to_string(self.common_dir.join("packed-refs")).ok()?;
+        packed.lines().find_map(|line| {
+            let (commit, name) = line.split_once(' ')?;
+            (name == reference).then(|| commit.to_string())
+        })
+    }
+}
+
+/// Collapse `..` left over from relative gitdir pointers
+fn normalize(path: &Path) -> PathBuf {
+    let mut normalized = PathBuf::new();
+    for component in path.components() {
+        match component {
+            std::path::Component::ParentDir => {
+                normalized.pop();
+            }
+            std::path::Component::CurDir => {}
+            other => normalized.push(other),
+        }
+    }
+    normalized
+}
+
+/// The commit a working tree has checked out
+#[derive(Debug, Clone, Default, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Head {
+    /// Checked-out branch, None when HEAD is detached
+    pub branch: Option<String>,
+    pub commit: Option<String>,
+}
+
+/// A path with changes that aren't committed yet
+#[derive(Debug, Clone, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct UncommittedFile {
+    /// Relative to the working tree root
+    pub path: String,
+    /// Two-letter porcelain status: staged then unstaged ("M.", ".M", "??", ...)
+    pub status: String,
+}
+
+/// Repository, worktree and working state of a session's cwd
+#[derive(Debug, Clone, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct GitInfo {
+    /// Main working tree of the repository; worktrees of one repo share it
+    pub repo_root: String,
+    /// Working tree the session runs in
+    pub worktree: String,
+    pub is_linked_worktree: bool,
+    pub head: Head,
+    pub dirty: bool,
+    /// Commits ahead of / behind the upstream, None without an upstream
+    pub ahead: Option<u32>,
+    pub behind: Option<u32>,
+    pub uncommitted: Vec<UncommittedFile>,
+}
+
+/// Resolve the repository of `dir` and read its working state
+///
+/// Returns None outside a git repository. When `git` itself can't run, the
+/// working state is left empty but HEAD is still read from `.git`.
+pub fn git_info(dir: &Path) -> Option<GitInfo> {
+    let location = RepoLocation::find(dir)?;
+    let status = cached_status(&location, working_status).unwrap_or_default();
+
+    Some(GitInfo {
+        repo_root: location.repo_root().to_string_lossy().to_string(),
+        worktree: location.worktree.to_string_lossy().to_string(),
+        is_linked_worktree: location.is_linked_worktree(),
+        head: location.head(),
+        dirty: !status.files.is_empty(),
+        ahead: status.ahead,
+        behind: status.behind,
+        uncommitted: status.files,
+    })
+}
+
+#[derive(Debug, Clone, Default, PartialEq)]
+struct WorkingStatus {
+    ahead: Option<u32>,
+    behind: Option<u32>,
+    files: Vec<UncommittedFile>,
+}
+
+struct CachedStatus {
+    /// Modification times of `index` and `HEAD` when the status was taken
+    stamp: [Option<SystemTime>; 2],
+    taken: Instant,
+    status: Option<WorkingStatus>,
+}
+
+/// Last `git status` of each working tree
+static STATUSES: Mutex<Option<HashMap<PathBuf, CachedStatus>>> = Mutex::new(None);
+
+/// Working status of `location`, running `run` only when the index or HEAD
+/// changed or the cached result is older than [`STATUS_MAX_AGE`]
+///
+/// Detection runs on every poll, and `git status` is slow on large repositories.
+fn cached_status<F>(location: &RepoLocation, run: F) -> Option<WorkingStatus>
+where
+    F: FnOnce(&Path) -> Option<WorkingStatus>,
+{
+    let stamp = ["index", "HEAD"].map(|name| {
+        fs::metadata(location.git_dir.join(name))
+            .and_then(|meta| meta.modified())
+            .ok()
+    });
+
+    let fresh = |cached: &CachedStatus| {
+        cached.stamp == stamp && cached.taken.elapsed() < STATUS_MAX_AGE
+    };
+    {
+        let guard = STATUSES.lock().unwrap_or_else(|p| p.into_inner());
+        if let Some(cached) = guard.as_ref().and_then(|c| c.get(&location.worktree)) {
+            if fresh(cached) {
+                return cached.status.clone();
+            }
+        }
+    }
+
+    // Run git without holding the lock so other worktrees aren't held up
+    let status = run(&location.worktree);
+    let mut guard = STATUSES.lock().unwrap_or_else(|p| p.into_inner());
+    let cache = guard.get_or_insert_with(HashMap::new);
+    // Entries this old would be refreshed anyway, so worktrees no session uses go away
+    cache.retain(|_, cached| cached.taken.elapsed() < STATUS_MAX_AGE);
+    cache.insert(
+        location.worktree.clone(),
+        CachedStatus {
+            stamp,
+            taken: Instant::now(),
+            status: status.clone(),
+        },
+    );
+    status
+}
+
+/// Run `git status` in the working tree; it only reads local state
+fn working_status(worktree: &Path) -> Option<WorkingStatus> {
+    let output = Command::new("git")
+        .arg("-C")
+        .arg(worktree)
+        .args(["--no-optional-locks", "status", "--porcelain=v2", "--branch"])
+        .output()
+        .ok()?;
+    if !output.status.success() {
+        return None;
+    }
+    Some(parse_porcelain(&String::from_utf8_lossy(&output.stdout)))
+}
+
+/// Parse `git status --porcelain=v2 --branch` output
+fn parse_porcelain(output: &str) -> WorkingStatus {
+    let mut status = WorkingStatus::default();
+
+    for line in output.lines() {
+        if let Some(counts) = line.strip_prefix("# branch.ab ") {
+            let mut counts = counts.split_whitespace();
+            status.ahead = counts.next().and_then(|a| a.trim_start_matches('+').parse().ok());
+            status.behind = counts.next().and_then(|b| b.trim_start_matches('-').parse().ok());
+            continue;
+        }
+
+        // Ordinary and unmerged entries have 8 and 10 fields before the path,
+        // renames have 9 and append the original path after a tab
+        let (code, path) = match line.split_once(' ') {
+            Some(("1", rest)) => (rest.get(..2), rest.splitn(8, ' ').nth(7)),
+            Some(("2", rest)) => (
+                rest.get(..2),
+                rest.splitn(9, ' ').nth(8).and_then(|p| p.split('\t').next()),
+            ),
+            Some(("u", rest)) => (rest.get(..2), rest.splitn(10, ' ').nth(9)),
+            Some(("?", path)) => (Some("??"), Some(path)),
+            _ => continue,
+        };
+        if let (Some(code), Some(path)) = (code, path) {
+            status.files.push(UncommittedFile {
+                path: path.to_string(),
+                status: code.to_string(),
+            });
+        }
+    }
+
+    status
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_worktree_root() {
+        let root = std::env::temp_dir().join(format!("c9watch-git-{}", std::process::id()));
+        let nested = root.join("repo").join("src").join("auth");
+        let worktree = root.join("repo-wt");
+        fs::create_dir_all(root.join("repo").join(".git")).unwrap();
+        fs::create_dir_all(&nested).unwrap();
+        fs::create_dir_all(&worktree).unwrap();
+        fs::write(worktree.join(".git"), "gitdir: ../repo/.git/worktrees/wt\n").unwrap();
+
+        assert_eq!(worktree_root(&nested), Some(root.join("repo")));
+        assert_eq!(worktree_root(&worktree), Some(worktree.clone()));
+
+        let _ = fs::remove_dir_all(&root);
+    }
+
+    #[test]
+    fn test_linked_worktree_resolves_to_its_repo() {
+        let root = std::env::temp_dir().join(format!("c9watch-repo-{}", std::process::id()));
+        let git_dir = root.join("repo").join(".git");
+        let linked_dir = git_dir.join("worktrees").join("wt");
+        let worktree = root.join("repo-wt");
+        fs::create_dir_all(git_dir.join("refs").join("heads")).unwrap();
+        fs::create_dir_all(&linked_dir).unwrap();
+        fs::create_dir_all(&worktree).unwrap();
+        fs::write(git_dir.join("HEAD"), "ref: refs/heads/main\n").unwrap();
+        fs::write(git_dir.join("refs/heads/main"), "aaaa\n").unwrap();
+        fs::write(git_dir.join("packed-refs"), "# pack-refs with: peeled\nbbbb refs/heads/feature\n").unwrap();
+        fs::write(linked_dir.join("HEAD"), "ref: refs/heads/feature\n").unwrap();
+        fs::write(linked_dir.join("commondir"), "../..\n").unwrap();
+        fs::write(worktree.join(".git"), "gitdir: ../repo/.git/worktrees/wt\n").unwrap();
+
+        let main = RepoLocation::find(&root.join("repo")).unwrap();
+        assert!(!main.is_linked_worktree());
+        assert_eq!(main.head().branch.as_deref(), Some("main"));
+        assert_eq!(main.head().commit.as_deref(), Some("aaaa"));
+
+        let linked = RepoLocation::find(&worktree).unwrap();
+        assert!(linked.is_linked_worktree());
+        assert_eq!(linked.repo_root(), main.repo_root());
+        assert_eq!(
+            linked.head(),
+            Head {
+                branch: Some("feature".to_string()),
+                commit: Some("bbbb".to_string()),
+            }
+        );
+
+        let _ = fs::remove_dir_all(&root);
+    }
+
+    #[test]
+    fn test_status_is_cached_until_the_index_changes() {
+        let root = std::env::temp_dir().join(format!("c9watch-status-{}", std::process::id()));
+        let git_dir = root.join(".git");
+        fs::create_dir_all(&git_dir).unwrap();
+        fs::write(git_dir.join("HEAD"), "ref: refs/heads/main\n").unwrap();
+        fs::write(git_dir.join("index"), "").unwrap();
+        let location = RepoLocation::find(&root).unwrap();
+
+        let runs = std::cell::Cell::new(0);
+        let run = |_: &Path| {
+            runs.set(runs.get() + 1);
+            Some(WorkingStatus { ahead: Some(runs.get()), ..WorkingStatus::default() })
+        };
+
+        assert_eq!(cached_status(&location, run).unwrap().ahead, Some(1));
+        assert_eq!(cached_status(&location, run).unwrap().ahead, Some(1));
+        assert_eq!(runs.get(), 1);
+
+        // Staging or committing rewrites the index
+        let index = fs::File::options().write(true).open(git_dir.join("index")).unwrap();
+        index.set_modified(SystemTime::now() + Duration::from_secs(5)).unwrap();
+        assert_eq!(cached_status(&location, run).unwrap().ahead, Some(2));
+
+        let _ = fs::remove_dir_all(&root);
+    }
+
+    #[test]
+    fn test_parse_porcelain() {
+        let output = "# branch.oid 1234\n\
+            # branch.head main\n\
+            # branch.upstream origin/main\n\
+            # branch.ab +2 -1\n\
+            1 .M N... 100644 100644 100644 abcd abcd src/lib.rs\n\
+            2 R. N... 100644 100644 100644 abcd abcd R100 src/new name.rs\tsrc/old.rs\n\
+            u UU N... 100644 100644 100644 100644 abcd abcd abcd Cargo.lock\n\
+            ? notes.txt\n";
+
+        let status = parse_porcelain(output);
+        assert_eq!(status.ahead, Some(2));
+        assert_eq!(status.behind, Some(1));
+        let files: Vec<(&str, &str)> = status
+            .files
+            .iter()
+            .map(|f| (f.status.as_str(), f.path.as_str()))
+            .collect();
+        assert_eq!(
+            files,
+            vec![
+                (".M", "src/lib.rs"),
+                ("R.", "src/new name.rs"),
+                ("UU", "Cargo.lock"),
+                ("??", "notes.txt"),
+            ]
+        );
+    }
+}
@@ -0,0 +1,191 @@
+use super::parser::IncrementalScan;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+
+/// Model name Claude Code uses for messages it writes itself (no real usage)
+pub(super) const SYNTHETIC_MODEL: &str = "<synthetic>";
+
+/// What happened over a session's whole lifetime, beyond the parsed tail
+#[derive(Debug, Clone, Default, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct TranscriptHistory {
+    /// Times the conversation was compacted (manually or automatically)
+    ///
+    /// Only `compact_boundary` entries count. `summary` lines are titles
+    /// Claude Code writes for conversation branches, often several per file,
+    /// and don't mark a compaction.
+    pub compactions: u32,
+    /// Text of the latest `summary` entry
+    pub latest_summary: Option<String>,
+    /// Models in the order they were used; the first one is the starting model
+    pub models: Vec<ModelSwitch>,
+    /// Claude Code version that wrote the latest entry
+    pub version: Option<String>,
+}
+
+impl TranscriptHistory {
+    /// History of an in-memory transcript
+    pub fn from_lines<S: AsRef<str>>(lines: &[S]) -> Self {
+        let mut history = Self::default();
+        for line in lines {
+            if let Ok(parsed) = serde_json::from_str::<HistoryLine>(line.as_ref()) {
+                history.apply(parsed);
+            }
+        }
+        history
+    }
+
+    /// Model of the latest assistant message
+    pub fn current_model(&self) -> Option<&str> {
+        self.models.last().map(|m| m.model.as_str())
+    }
+}
+
+/// A model taking over the conversation
+#[derive(Debug, Clone, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ModelSwitch {
+    pub model: String,
+    /// Timestamp of the first message written by this model
+    pub at: String,
+}
+
+/// The few fields the history needs from a JSONL line
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct HistoryLine {
+    #[serde(rename = "type")]
+    kind: Option<String>,
+    subtype: Option<String>,
+    summary: Option<String>,
+    timestamp: Option<String>,
+    version: Option<String>,
+    is_sidechain: Option<bool>,
+    message: Option<HistoryMessage>,
+}
+
+#[derive(Deserialize)]
+struct HistoryMessage {
+    model: Option<String>,
+}
+
+impl TranscriptHistory {
+    fn apply(&mut self, line: HistoryLine) {
+        if line.version.is_some() {
+            self.version = line.version;
+        }
+
+        match line.kind.as_deref() {
+            Some("system") if line.subtype.as_deref() == Some("compact_boundary") => {
+                self.compactions += 1;
+            }
+            Some("summary") if line.summary.is_some() => {
+                self.latest_summary = line.summary;
+            }
+            // Sub-agents may run on a different model without switching the session's
+            Some("assistant") if line.is_sidechain != Some(true) => {
+                let Some(model) = line.message.and_then(|m| m.model) else {
+                    return;
+                };
+                if model != SYNTHETIC_MODEL && self.current_model() != Some(model.as_str()) {
+                    self.models.push(ModelSwitch {
+                        model,
+                        at: line.timestamp.unwrap_or_default(),
+                    });
+                }
+            }
+            _ => {}
+        }
+    }
+}
+
+static HISTORIES: Mutex<Option<HashMap<PathBuf, IncrementalScan<TranscriptHistory>>>> =
+    Mutex::new(None);
+
+/// Compactions, model switches and CLI version of a session transcript
+pub fn scan_history(path: &Path) -> TranscriptHistory {
+    let mut guard = HISTORIES.lock().unwrap_or_else(|p| p.into_inner());
+    let scan = guard
+        .get_or_insert_with(HashMap::new)
+        .entry(path.to_path_buf())
+        .or_default();
+
+    scan.update(path, |history, line| {
+        if let Ok(parsed) = serde_json::from_str::<HistoryLine>(line) {
+            history.apply(parsed);
+        }
+    })
+    .clone()
+}
+
+/// Drop the cached history of transcripts that are no longer watched
+pub fn retain_histories(watched: &[PathBuf]) {
+    let mut guard = HISTORIES.lock().unwrap_or_else(|p| p.into_inner());
+    if let Some(states) = guard.as_mut() {
+        states.retain(|path, _| watched.contains(path));
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::fs::File;
+    use std::io::Write;
+
+    fn assistant(model: &str, ts: &str, version: &str) -> String {
+        format!(
+            r#"{{"type":"assistant","uuid":"a","timestamp":"{}","version":"{}","message":{{"model":"{}","id":"msg","role":"assistant","content":[],"stop_reason":null,"stop_sequence":null,"usage":null}}}}"#,
+            ts, version, model
+        )
+    }
+
+    const BOUNDARY: &str = r#"{"type":"system","subtype":"compact_boundary","content":"Conversation compacted","level":"info","uuid":"s","timestamp":"2026-03-01T12:00:01Z"}"#;
+
+    const SUMMARY: &str = r#"{"type":"summary","summary":"Fix the login flow","leafUuid":"a"}"#;
+
+    #[test]
+    fn test_scan_history_reads_appended_lines() {
+        let path = std::env::temp_dir().join(format!("c9watch-history-{}.jsonl", std::process::id()));
+        let mut file = File::create(&path).unwrap();
+        writeln!(file, "{}", assistant("claude-sonnet-4-5", "2026-03-01T12:00:00Z", "2.1.0")).unwrap();
+        writeln!(file, "{}", BOUNDARY).unwrap();
+        writeln!(file, "{}", assistant("claude-sonnet-4-5", "2026-03-01T12:01:00Z", "2.1.0")).unwrap();
+
+        writeln!(file, "{}", SUMMARY).unwrap();
+
+        let history = scan_history(&path);
+        assert_eq!(history.compactions, 1);
+        assert_eq!(history.latest_summary.as_deref(), Some("Fix the login flow"));
+        assert_eq!(history.models.len(), 1);
+        assert_eq!(history.version.as_deref(), Some("2.1.0"));
+
+        // A half-written line is picked up once it is complete
+        write!(file, "{}", assistant("claude-opus-4-5", "2026-03-01T12:02:00Z", "2.1.3")).unwrap();
+        assert_eq!(scan_history(&path).models.len(), 1);
+        writeln!(file).unwrap();
+        writeln!(file, "{}", assistant(SYNTHETIC_MODEL, "2026-03-01T12:03:00Z", "2.1.3")).unwrap();
+
+        let history = scan_history(&path);
+        assert_eq!(history.current_model(), Some("claude-opus-4-5"));
+        assert_eq!(
+            history.models[1],
+            ModelSwitch {
+                model: "claude-opus-4-5".to_string(),
+                at: "2026-03-01T12:02:00Z".to_string(),
+            }
+        );
+        assert_eq!(history.version.as_deref(), Some("2.1.3"));
+
+        // Truncated and rewritten
+        let mut file = File::create(&path).unwrap();
+        writeln!(file, "{}", assistant("claude-haiku-4-5", "2026-03-01T13:00:00Z", "2.1.4")).unwrap();
+        let history = scan_history(&path);
+        assert_eq!(history.compactions, 0);
+        assert_eq!(history.current_model(), Some("claude-haiku-4-5"));
+
+        retain_histories(&[]);
+        let _ = std::fs::remove_file(&path);
+    }
+}
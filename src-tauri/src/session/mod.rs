@@ -1,16 +1,42 @@
+pub mod claude_dirs;
+pub mod context;
 pub mod custom_names;
 pub mod detector;
+pub mod diagnostics;
+pub mod files;
+pub mod git;
+pub mod history;
 pub mod parser;
+pub mod pending;
 pub mod permissions;
+pub mod replay;
+pub mod resources;
+pub mod risk;
 pub mod status;
 
-pub use custom_names::{CustomNames, CustomTitles};
-pub use detector::{DetectedSession, SessionDetector};
+pub use claude_dirs::{claude_dirs, ClaudeDirs};
+pub use context::{context_usage, ContextUsage};
+pub use custom_names::{backup_legacy_files, CustomNames, CustomTitles};
+pub use detector::{encode_project_dir, DetectedSession, SessionDetector};
+pub use diagnostics::ParseDiagnostics;
+pub use files::{files_touched, find_conflicts, scan_files_touched, FileConflict, FilesTouched};
+pub use git::{git_info, GitInfo};
+pub use history::{scan_history, ModelSwitch, TranscriptHistory};
 pub use parser::{
     extract_messages, parse_all_entries, parse_last_n_entries, parse_sessions_index,
-    MessageContent, MessageType, SessionEntry, SessionIndexEntry, SessionsIndex,
+    CompactMetadata, MessageContent, MessageType, ProgressData, SessionEntry, SessionIndexEntry,
+    SessionsIndex, SystemEvent, Usage,
 };
 pub use permissions::PermissionChecker;
+pub use replay::Transcript;
+pub use pending::PendingTool;
+pub use resources::{
+    child_processes, latest_resources, sample_resources, ChildProcess, ResourceBudget,
+    ResourceUsage,
+};
+pub use risk::{classify_tool, tool_target, RiskLevel, RiskRule, ToolRisk};
 pub use status::{
-    determine_status, determine_status_with_context, get_pending_tool_name, SessionStatus,
+    determine_status, determine_status_with_context, get_pending_tool_name, get_pending_tool_uses,
+    permission_checker, Clock, FixedClock, PendingToolUse, SessionStatus, StatusContext,
+    SystemClock,
 };
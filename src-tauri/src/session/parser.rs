@@ -1,3 +1,4 @@
+use super::diagnostics::{self, Shape};
 use serde::{Deserialize, Serialize};
 use std::fs::File;
 use std::io::{BufRead, BufReader, Seek, SeekFrom};
@@ -55,10 +56,45 @@ pub enum SessionEntry {
         #[serde(rename = "leafUuid")]
         leaf_uuid: String,
     },
+    /// Live update from a running tool, hook or sub-agent
+    Progress {
+        #[serde(flatten)]
+        base: SessionEntryBase,
+        /// Id of the progress stream (for sub-agents, the agent's message id)
+        #[serde(rename = "toolUseID")]
+        tool_use_id: Option<String>,
+        /// Id of the tool_use block the progress belongs to
+        #[serde(rename = "parentToolUseID")]
+        parent_tool_use_id: Option<String>,
+        data: ProgressData,
+    },
+    /// Notice written by Claude Code itself rather than the model
+    System {
+        #[serde(flatten)]
+        base: SessionEntryBase,
+        /// "info", "warning", "error", "suggestion", ...
+        level: Option<String>,
+        content: Option<String>,
+        #[serde(flatten)]
+        event: SystemEvent,
+    },
     #[serde(other)]
     Unknown,
 }
 
+impl SessionEntry {
+    /// Base fields of entries that carry them
+    pub fn base(&self) -> Option<&SessionEntryBase> {
+        match self {
+            SessionEntry::User { base, .. }
+            | SessionEntry::Assistant { base, .. }
+            | SessionEntry::Progress { base, .. }
+            | SessionEntry::System { base, .. } => Some(base),
+            _ => None,
+        }
+    }
+}
+
 /// Common fields shared across session entries
 #[derive(Debug, Clone, Serialize, Deserialize)]
 #[serde(rename_all = "camelCase")]
@@ -85,6 +121,15 @@ pub struct UserMessage {
     pub content: String,
     /// Whether this user entry is a tool result rather than an actual user prompt
     pub is_tool_result: bool,
+    /// The tool calls this entry answers, in order
+    pub tool_results: Vec<ToolResultRef>,
+}
+
+/// Which tool call a tool result answers and whether the call failed
+#[derive(Debug, Clone, Serialize, PartialEq)]
+pub struct ToolResultRef {
+    pub tool_use_id: String,
+    pub is_error: bool,
 }
 
 impl<'de> Deserialize<'de> for UserMessage {
@@ -102,6 +147,7 @@ impl<'de> Deserialize<'de> for UserMessage {
 
         let content_value = value.get("content");
 
+        let mut tool_results = Vec::new();
         let (content, is_tool_result) = match content_value {
             Some(Value::String(s)) => (s.clone(), false),
             Some(Value::Array(arr)) => {
@@ -109,6 +155,15 @@ impl<'de> Deserialize<'de> for UserMessage {
                 for item in arr {
                     match item.get("type").and_then(|t| t.as_str()) {
                         Some("tool_result") => {
+                            if let Some(id) = item.get("tool_use_id").and_then(|i| i.as_str()) {
+                                tool_results.push(ToolResultRef {
+                                    tool_use_id: id.to_string(),
+                                    is_error: item
+                                        .get("is_error")
+                                        .and_then(|e| e.as_bool())
+                                        .unwrap_or(false),
+                                });
+                            }
                             if let Some(content) = item.get("content") {
                                 match content {
                                     Value::String(s) => parts.push(s.clone()),
@@ -145,6 +200,7 @@ impl<'de> Deserialize<'de> for UserMessage {
             role,
             content,
             is_tool_result,
+            tool_results,
         })
     }
 }
@@ -186,6 +242,113 @@ pub enum MessageContent {
     Unknown,
 }
 
+/// Payload of a `progress` entry, tagged by its `type` (e.g. `bash_progress`)
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum ProgressData {
+    /// Output of a Bash command that is still running
+    #[serde(rename_all = "camelCase")]
+    BashProgress {
+        /// Most recent output lines
+        output: Option<String>,
+        /// Everything the command has printed so far
+        full_output: Option<String>,
+        elapsed_time_seconds: Option<f64>,
+        total_lines: Option<u64>,
+    },
+    /// A hook is running for the tool
+    #[serde(rename_all = "camelCase")]
+    HookProgress {
+        /// "PreToolUse", "PostToolUse", ...
+        hook_event: Option<String>,
+        hook_name: Option<String>,
+        command: Option<String>,
+    },
+    /// A sub-agent (Task tool) wrote a message
+    AgentProgress,
+    /// Any other kind, kept with its name
+    #[serde(untagged)]
+    Other {
+        #[serde(rename = "type")]
+        kind: Option<String>,
+    },
+}
+
+impl ProgressData {
+    /// The progress `type` as written in the JSONL
+    pub fn kind(&self) -> &str {
+        match self {
+            ProgressData::BashProgress { .. } => "bash_progress",
+            ProgressData::HookProgress { .. } => "hook_progress",
+            ProgressData::AgentProgress => "agent_progress",
+            ProgressData::Other { kind } => kind.as_deref().unwrap_or("unknown"),
+        }
+    }
+}
+
+/// What a `system` entry reports, tagged by its `subtype`
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(tag = "subtype", rename_all = "snake_case")]
+pub enum SystemEvent {
+    /// The conversation was compacted; earlier entries are summarised
+    #[serde(rename_all = "camelCase")]
+    CompactBoundary {
+        compact_metadata: Option<CompactMetadata>,
+    },
+    /// Stop hooks ran at the end of a turn
+    #[serde(rename_all = "camelCase")]
+    StopHookSummary {
+        #[serde(default)]
+        hook_count: u32,
+        #[serde(default)]
+        hook_errors: Vec<serde_json::Value>,
+        /// A hook blocked Claude from stopping
+        #[serde(default)]
+        prevented_continuation: bool,
+        stop_reason: Option<String>,
+    },
+    /// Written when a turn ends
+    #[serde(rename_all = "camelCase")]
+    TurnDuration {
+        duration_ms: Option<u64>,
+    },
+    /// An API request failed and may be retried
+    #[serde(rename_all = "camelCase")]
+    ApiError {
+        retry_in_ms: Option<f64>,
+        retry_attempt: Option<u32>,
+        max_retries: Option<u32>,
+    },
+    /// Any other subtype, kept with its name
+    #[serde(untagged)]
+    Other {
+        subtype: Option<String>,
+    },
+}
+
+impl SystemEvent {
+    /// The `subtype` as written in the JSONL
+    pub fn subtype(&self) -> &str {
+        match self {
+            SystemEvent::CompactBoundary { .. } => "compact_boundary",
+            SystemEvent::StopHookSummary { .. } => "stop_hook_summary",
+            SystemEvent::TurnDuration { .. } => "turn_duration",
+            SystemEvent::ApiError { .. } => "api_error",
+            SystemEvent::Other { subtype } => subtype.as_deref().unwrap_or("unknown"),
+        }
+    }
+}
+
+/// Details of a compaction
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CompactMetadata {
+    /// "auto" or "manual"
+    pub trigger: Option<String>,
+    /// Context size in tokens before compacting
+    pub pre_tokens: Option<u64>,
+}
+
 /// Token usage information
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct Usage {
@@ -225,11 +388,7 @@ pub fn read_last_n_lines<P: AsRef<Path>>(path: P, n: usize) -> Result<Vec<String
 
     // For small files, just read everything
     if file_size < 10_000 {
-        let reader = BufReader::new(file);
-        let lines: Vec<String> = reader.lines()
-            .filter_map(|line| line.ok())
-            .filter(|line| !line.trim().is_empty())
-            .collect();
+        let lines = read_lines(BufReader::new(file));
 
         let start = if lines.len() > n { lines.len() - n } else { 0 };
         return Ok(lines[start..].to_vec());
@@ -243,27 +402,152 @@ pub fn read_last_n_lines<P: AsRef<Path>>(path: P, n: usize) -> Result<Vec<String
     file.seek(SeekFrom::End(-(chunk_size as i64)))
         .map_err(|e| format!("Failed to seek in file: {}", e))?;
 
-    let reader = BufReader::new(file);
-    let lines: Vec<String> = reader.lines()
-        .filter_map(|line| line.ok())
-        .filter(|line| !line.trim().is_empty())
-        .collect();
+    let mut reader = BufReader::new(file);
+    // The seek lands inside a line (maybe inside a character), so drop it
+    if chunk_size < file_size as usize {
+        reader
+            .read_until(b'\n', &mut Vec::new())
+            .map_err(|e| format!("Failed to read JSONL file: {}", e))?;
+    }
+    let lines = read_lines(reader);
 
     let start = if lines.len() > n { lines.len() - n } else { 0 };
     Ok(lines[start..].to_vec())
 }
 
+/// Non-empty lines of `reader`, skipping any that aren't valid UTF-8 rather
+/// than stopping there
+fn read_lines(reader: impl BufRead) -> Vec<String> {
+    reader
+        .split(b'\n')
+        .map_while(Result::ok)
+        .filter_map(|line| String::from_utf8(line).ok())
+        .map(|line| line.trim_end_matches('\r').to_string())
+        .filter(|line| !line.trim().is_empty())
+        .collect()
+}
+
+/// State derived from a growing JSONL file, updated from the appended lines only
+///
+/// Transcripts only grow, so each [`update`](Self::update) reads just the
+/// complete lines written since the previous one. A file that shrank was
+/// rewritten, so the state is reset and the file is read from the start.
+#[derive(Default)]
+pub struct IncrementalScan<T> {
+    offset: u64,
+    state: T,
+}
+
+impl<T: Default> IncrementalScan<T> {
+    pub fn update<F: FnMut(&mut T, &str)>(&mut self, path: &Path, mut apply: F) -> &T {
+        let Ok(mut file) = File::open(path) else {
+            return &self.state;
+        };
+        let len = file.metadata().map(|m| m.len()).unwrap_or(0);
+        if len < self.offset {
+            *self = Self::default();
+        }
+        if len == self.offset || file.seek(SeekFrom::Start(self.offset)).is_err() {
+            return &self.state;
+        }
+
+        let mut reader = BufReader::new(file);
+        let mut line = Vec::new();
+        loop {
+            line.clear();
+            match reader.read_until(b'\n', &mut line) {
+                // A line without its newline is still being written
+                Ok(read) if read > 0 && line.ends_with(b"\n") => {
+                    self.offset += read as u64;
+                    // Read past a line that isn't UTF-8 instead of stalling on it
+                    apply(&mut self.state, String::from_utf8_lossy(&line).trim_end());
+                }
+                _ => break,
+            }
+        }
+
+        &self.state
+    }
+}
+
 /// Parse JSONL lines into SessionEntry structs
+///
+/// Lines that fail to parse are skipped, and lines or content blocks of a
+/// type the parser doesn't know are kept as `Unknown`. Both are recorded in
+/// the parse diagnostics so format changes don't go unnoticed.
 pub fn parse_jsonl_entries(lines: Vec<String>) -> Vec<SessionEntry> {
     lines
         .iter()
-        .filter_map(|line| {
-            serde_json::from_str::<SessionEntry>(line)
-                .ok()
-        })
+        .filter_map(|line| parse_jsonl_line(line))
         .collect()
 }
 
+/// Parse one JSONL line, recording anything unexpected about it
+fn parse_jsonl_line(line: &str) -> Option<SessionEntry> {
+    match serde_json::from_str::<SessionEntry>(line) {
+        Ok(entry) => {
+            match &entry {
+                SessionEntry::Unknown => record_unknown_entry(line),
+                SessionEntry::Assistant { message, .. }
+                    if message.content.iter().any(|c| matches!(c, MessageContent::Unknown)) =>
+                {
+                    record_unknown_blocks(line)
+                }
+                _ => {}
+            }
+            Some(entry)
+        }
+        Err(e) => {
+            let entry_type = serde_json::from_str::<serde_json::Value>(line)
+                .ok()
+                .and_then(|value| value.get("type")?.as_str().map(str::to_string));
+            diagnostics::record(Shape::ParseFailure { entry_type }, line, Some(e.to_string()));
+            None
+        }
+    }
+}
+
+fn record_unknown_entry(line: &str) {
+    let entry_type = serde_json::from_str::<serde_json::Value>(line)
+        .ok()
+        .and_then(|value| value.get("type")?.as_str().map(str::to_string))
+        .unwrap_or_else(|| "(missing)".to_string());
+    diagnostics::record(Shape::UnknownEntryType { entry_type }, line, None);
+}
+
+fn record_unknown_blocks(line: &str) {
+    let Ok(value) = serde_json::from_str::<serde_json::Value>(line) else {
+        return;
+    };
+    let entry_type = value
+        .get("type")
+        .and_then(|t| t.as_str())
+        .unwrap_or("assistant")
+        .to_string();
+    let blocks = value
+        .pointer("/message/content")
+        .and_then(|c| c.as_array())
+        .into_iter()
+        .flatten();
+
+    for block in blocks {
+        let block_type = block.get("type").and_then(|t| t.as_str()).unwrap_or("(missing)");
+        if !KNOWN_CONTENT_BLOCKS.contains(&block_type) {
+            diagnostics::record(
+                Shape::UnknownContentBlock {
+                    entry_type: entry_type.clone(),
+                    block_type: block_type.to_string(),
+                },
+                line,
+                None,
+            );
+        }
+    }
+}
+
+/// Content block types with a `MessageContent` variant
+const KNOWN_CONTENT_BLOCKS: &[&str] = &["text", "thinking", "tool_use", "tool_result"];
+
 /// Parse the last N entries from a session JSONL file
 pub fn parse_last_n_entries<P: AsRef<Path>>(
     path: P,
@@ -278,13 +562,7 @@ pub fn parse_all_entries<P: AsRef<Path>>(path: P) -> Result<Vec<SessionEntry>, S
     let file = File::open(path.as_ref())
         .map_err(|e| format!("Failed to open JSONL file: {}", e))?;
 
-    let reader = BufReader::new(file);
-    let lines: Vec<String> = reader
-        .lines()
-        .filter_map(|line| line.ok())
-        .filter(|line| !line.trim().is_empty())
-        .collect();
-
+    let lines = read_lines(BufReader::new(file));
     Ok(parse_jsonl_entries(lines))
 }
 
@@ -357,13 +635,82 @@ pub fn extract_messages(entries: &[SessionEntry]) -> Vec<(String, MessageType, S
                     }
                 }
             }
+            SessionEntry::System { base, content, event, .. } => {
+                if let Some(text) = describe_system_event(event, content.as_deref()) {
+                    messages.push((base.timestamp.clone(), MessageType::System, text));
+                }
+            }
             _ => {}
         }
     }
 
+    // Live output of tools that are still running (no message after it yet)
+    let last_message_idx = entries
+        .iter()
+        .rposition(|entry| matches!(entry, SessionEntry::User { .. } | SessionEntry::Assistant { .. }))
+        .map_or(0, |idx| idx + 1);
+    let mut running: Vec<(&str, &SessionEntryBase, &ProgressData)> = Vec::new();
+    for entry in &entries[last_message_idx..] {
+        if let SessionEntry::Progress {
+            base,
+            parent_tool_use_id: Some(tool_id),
+            data: data @ ProgressData::BashProgress { .. },
+            ..
+        } = entry
+        {
+            // Later updates replace earlier ones for the same tool
+            running.retain(|(id, _, _)| id != tool_id);
+            running.push((tool_id, base, data));
+        }
+    }
+    for (tool_id, base, data) in running {
+        if let ProgressData::BashProgress { output, full_output, elapsed_time_seconds, .. } = data {
+            let output = full_output.as_ref().or(output.as_ref()).map_or("", |o| o.trim_end());
+            let elapsed = elapsed_time_seconds.map(|s| format!(" ({:.0}s)", s)).unwrap_or_default();
+            messages.push((
+                base.timestamp.clone(),
+                MessageType::ToolProgress,
+                format!("[Running] {}{}: {}", tool_id, elapsed, output),
+            ));
+        }
+    }
+
     messages
 }
 
+/// Text shown in the conversation for a system entry, if it's worth showing
+fn describe_system_event(event: &SystemEvent, content: Option<&str>) -> Option<String> {
+    match event {
+        SystemEvent::CompactBoundary { compact_metadata } => {
+            let details = compact_metadata
+                .as_ref()
+                .map(|m| match (m.trigger.as_deref(), m.pre_tokens) {
+                    (Some(trigger), Some(tokens)) => format!(" ({}, {} tokens before)", trigger, tokens),
+                    (Some(trigger), None) => format!(" ({})", trigger),
+                    (None, Some(tokens)) => format!(" ({} tokens before)", tokens),
+                    (None, None) => String::new(),
+                })
+                .unwrap_or_default();
+            Some(format!("Conversation compacted{}", details))
+        }
+        SystemEvent::StopHookSummary { hook_errors, .. } if !hook_errors.is_empty() => {
+            let errors: Vec<String> = hook_errors
+                .iter()
+                .map(|e| e.as_str().map(str::to_string).unwrap_or_else(|| e.to_string()))
+                .collect();
+            Some(format!("Stop hook failed: {}", errors.join("\n")))
+        }
+        SystemEvent::ApiError { retry_attempt, max_retries, .. } => {
+            let retry = match (retry_attempt, max_retries) {
+                (Some(attempt), Some(max)) => format!(", retrying ({}/{})", attempt, max),
+                _ => String::new(),
+            };
+            Some(format!("{}{}", content.unwrap_or("API error"), retry))
+        }
+        _ => None,
+    }
+}
+
 /// Message type enumeration
 #[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
 pub enum MessageType {
@@ -372,12 +719,53 @@ pub enum MessageType {
     Thinking,
     ToolUse,
     ToolResult,
+    /// Output of a tool that is still running
+    ToolProgress,
+    /// Notice from Claude Code itself, e.g. a compaction
+    System,
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
 
+    #[test]
+    fn test_incremental_scan_reads_past_invalid_utf8() {
+        let path = std::env::temp_dir().join(format!("c9watch-scan-{}.jsonl", std::process::id()));
+        std::fs::write(&path, b"one\n\xff\xfe\ntwo\n").unwrap();
+
+        let mut scan: IncrementalScan<Vec<String>> = IncrementalScan::default();
+        let count = |lines: &mut Vec<String>, line: &str| lines.push(line.to_string());
+        assert_eq!(scan.update(&path, count).len(), 3);
+
+        let mut file = std::fs::OpenOptions::new().append(true).open(&path).unwrap();
+        std::io::Write::write_all(&mut file, b"three\n").unwrap();
+        assert_eq!(scan.update(&path, count).last().map(String::as_str), Some("three"));
+
+        std::fs::remove_file(&path).unwrap();
+    }
+
+    #[test]
+    fn test_read_last_n_lines_survives_a_seek_into_a_character() {
+        let path = std::env::temp_dir().join(format!("c9watch-utf8-{}.jsonl", std::process::id()));
+        // 12001 bytes of two-byte characters, so seeking 4096 bytes back from
+        // the end lands on the second byte of one
+        let wide = "é".repeat(6000);
+        let tail = ["a".repeat(999), "b".repeat(999)];
+        let mut content = format!("{}\n{}\n{}\n", wide, tail[0], tail[1]).into_bytes();
+        std::fs::write(&path, &content).unwrap();
+
+        assert_eq!(read_last_n_lines(&path, 2).unwrap(), tail);
+
+        // A line that isn't UTF-8 is skipped without losing the ones after it
+        content.splice(0..0, b"\xff\xfe\n".iter().copied());
+        std::fs::write(&path, &content).unwrap();
+        let lines = read_lines(BufReader::new(File::open(&path).unwrap()));
+        assert_eq!(lines, vec![wide, tail[0].clone(), tail[1].clone()]);
+
+        std::fs::remove_file(&path).unwrap();
+    }
+
     #[test]
     fn test_parse_user_message() {
         let json = r#"{
@@ -499,6 +887,13 @@ mod tests {
 
         if let Ok(SessionEntry::User { message, .. }) = entry {
             assert!(message.content.contains("command output here"));
+            assert_eq!(
+                message.tool_results,
+                vec![ToolResultRef {
+                    tool_use_id: "toolu_123".to_string(),
+                    is_error: false,
+                }]
+            );
         } else {
             panic!("Expected User entry");
         }
@@ -538,17 +933,130 @@ mod tests {
 
     #[test]
     fn test_parse_progress_entry() {
-        // Progress entries should parse as Unknown (not cause errors)
         let json = r#"{
             "type": "progress",
             "uuid": "test-uuid",
             "timestamp": "2026-01-08T15:23:03.096Z",
-            "data": {"type": "bash_progress"},
-            "toolUseID": "toolu_123"
+            "data": {"type": "bash_progress", "output": "Compiling c9watch", "elapsedTimeSeconds": 3},
+            "toolUseID": "bash-progress-0",
+            "parentToolUseID": "toolu_123"
         }"#;
 
-        let entry: Result<SessionEntry, _> = serde_json::from_str(json);
-        assert!(entry.is_ok(), "Progress entries should parse as Unknown");
-        assert!(matches!(entry.unwrap(), SessionEntry::Unknown));
+        let entry: SessionEntry = serde_json::from_str(json).unwrap();
+        match entry {
+            SessionEntry::Progress { parent_tool_use_id, data, .. } => {
+                assert_eq!(parent_tool_use_id.as_deref(), Some("toolu_123"));
+                assert_eq!(data.kind(), "bash_progress");
+                match data {
+                    ProgressData::BashProgress { output, elapsed_time_seconds, .. } => {
+                        assert_eq!(output.as_deref(), Some("Compiling c9watch"));
+                        assert_eq!(elapsed_time_seconds, Some(3.0));
+                    }
+                    other => panic!("Expected bash progress, got {:?}", other),
+                }
+            }
+            other => panic!("Expected Progress entry, got {:?}", other),
+        }
+
+        // Kinds without a variant keep their name
+        let json = r#"{"type":"progress","uuid":"u","timestamp":"2026-01-08T15:23:03.096Z","data":{"type":"mcp_progress","status":"started"}}"#;
+        let entry: SessionEntry = serde_json::from_str(json).unwrap();
+        assert!(matches!(
+            entry,
+            SessionEntry::Progress { data: ProgressData::Other { kind: Some(ref kind) }, .. } if kind == "mcp_progress"
+        ));
+    }
+
+    #[test]
+    fn test_parse_system_entries() {
+        let json = r#"{"type":"system","subtype":"compact_boundary","content":"Conversation compacted","level":"info","uuid":"u1","timestamp":"2026-01-08T15:23:03.096Z","compactMetadata":{"trigger":"auto","preTokens":155000}}"#;
+        let entry: SessionEntry = serde_json::from_str(json).unwrap();
+        match entry {
+            SessionEntry::System { level, event: SystemEvent::CompactBoundary { compact_metadata }, .. } => {
+                assert_eq!(level.as_deref(), Some("info"));
+                let metadata = compact_metadata.unwrap();
+                assert_eq!(metadata.trigger.as_deref(), Some("auto"));
+                assert_eq!(metadata.pre_tokens, Some(155000));
+            }
+            other => panic!("Expected compact boundary, got {:?}", other),
+        }
+
+        let json = r#"{"type":"system","subtype":"stop_hook_summary","hookCount":1,"hookInfos":[{"command":"./lint.sh"}],"hookErrors":["lint failed"],"preventedContinuation":true,"stopReason":"","level":"suggestion","uuid":"u2","timestamp":"2026-01-08T15:23:04.096Z"}"#;
+        let entry: SessionEntry = serde_json::from_str(json).unwrap();
+        assert!(matches!(
+            entry,
+            SessionEntry::System { event: SystemEvent::StopHookSummary { hook_count: 1, prevented_continuation: true, .. }, .. }
+        ));
+
+        // Unfamiliar or missing subtypes still parse
+        let json = r#"{"type":"system","subtype":"informational","content":"Hi","level":"info","uuid":"u3","timestamp":"2026-01-08T15:23:05.096Z"}"#;
+        let entry: SessionEntry = serde_json::from_str(json).unwrap();
+        match entry {
+            SessionEntry::System { event, content, .. } => {
+                assert_eq!(event.subtype(), "informational");
+                assert_eq!(content.as_deref(), Some("Hi"));
+            }
+            other => panic!("Expected System entry, got {:?}", other),
+        }
+
+        let json = r#"{"type":"system","content":"PreToolUse:Bash completed","level":"info","uuid":"u4","timestamp":"2026-01-08T15:23:06.096Z"}"#;
+        let entry: SessionEntry = serde_json::from_str(json).unwrap();
+        assert!(matches!(entry, SessionEntry::System { event: SystemEvent::Other { subtype: None }, .. }));
+    }
+
+    #[test]
+    fn test_parse_jsonl_entries_records_drift() {
+        let lines = vec![
+            r#"{"type":"parser-test-new-kind","uuid":"u1"}"#.to_string(),
+            r#"{"type":"user","uuid":"u2","message":{"role":"user","content":"missing timestamp"}}"#.to_string(),
+            r#"{"type":"assistant","uuid":"u3","timestamp":"2026-01-08T15:23:03.096Z","message":{"model":"m","id":"msg_1","role":"assistant","content":[{"type":"parser_test_block"}],"stop_reason":null,"stop_sequence":null,"usage":null}}"#.to_string(),
+            "not json".to_string(),
+        ];
+
+        let entries = parse_jsonl_entries(lines);
+        assert_eq!(entries.len(), 2);
+
+        let shapes: Vec<Shape> = diagnostics::snapshot()
+            .shapes
+            .into_iter()
+            .map(|report| report.shape)
+            .collect();
+        assert!(shapes.contains(&Shape::UnknownEntryType {
+            entry_type: "parser-test-new-kind".to_string()
+        }));
+        assert!(shapes.contains(&Shape::ParseFailure {
+            entry_type: Some("user".to_string())
+        }));
+        assert!(shapes.contains(&Shape::UnknownContentBlock {
+            entry_type: "assistant".to_string(),
+            block_type: "parser_test_block".to_string()
+        }));
+        assert!(shapes.contains(&Shape::ParseFailure { entry_type: None }));
+    }
+
+    #[test]
+    fn test_extract_messages_shows_running_tool_output() {
+        let lines = vec![
+            r#"{"type":"assistant","uuid":"a1","timestamp":"2026-01-08T15:23:00.000Z","message":{"model":"m","id":"msg_1","role":"assistant","content":[{"type":"tool_use","id":"toolu_1","name":"Bash","input":{"command":"cargo build"}}],"stop_reason":null,"stop_sequence":null,"usage":null}}"#.to_string(),
+            r#"{"type":"progress","uuid":"p1","timestamp":"2026-01-08T15:23:01.000Z","toolUseID":"bash-progress-0","parentToolUseID":"toolu_1","data":{"type":"bash_progress","output":"Compiling a","fullOutput":"Compiling a","elapsedTimeSeconds":1}}"#.to_string(),
+            r#"{"type":"progress","uuid":"p2","timestamp":"2026-01-08T15:23:02.000Z","toolUseID":"bash-progress-1","parentToolUseID":"toolu_1","data":{"type":"bash_progress","output":"Compiling b","fullOutput":"Compiling a\nCompiling b","elapsedTimeSeconds":2}}"#.to_string(),
+            r#"{"type":"system","subtype":"compact_boundary","content":"Conversation compacted","level":"info","uuid":"s1","timestamp":"2026-01-08T15:23:03.000Z","compactMetadata":{"trigger":"manual","preTokens":90000}}"#.to_string(),
+        ];
+        let entries = parse_jsonl_entries(lines);
+
+        let messages = extract_messages(&entries);
+        let kinds: Vec<MessageType> = messages.iter().map(|(_, kind, _)| kind.clone()).collect();
+        assert_eq!(kinds, vec![MessageType::ToolUse, MessageType::System, MessageType::ToolProgress]);
+        assert_eq!(messages[1].2, "Conversation compacted (manual, 90000 tokens before)");
+        assert_eq!(messages[2].2, "[Running] toolu_1 (2s): Compiling a\nCompiling b");
+
+        // Once the result comes back the live output is gone
+        let mut finished = entries.clone();
+        finished.extend(parse_jsonl_entries(vec![
+            r#"{"type":"user","uuid":"u1","timestamp":"2026-01-08T15:23:04.000Z","message":{"role":"user","content":[{"type":"tool_result","tool_use_id":"toolu_1","content":"ok"}]}}"#.to_string(),
+        ]));
+        assert!(extract_messages(&finished)
+            .iter()
+            .all(|(_, kind, _)| *kind != MessageType::ToolProgress));
     }
 }
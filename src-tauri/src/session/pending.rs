@@ -0,0 +1,125 @@
+use super::risk::{classify_tool, tool_target, RiskRule, ToolRisk};
+use super::status::PendingToolUse;
+use serde::Serialize;
+use serde_json::Value;
+
+/// A tool call waiting for the user's permission, with what it would do
+#[derive(Debug, Clone, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PendingTool {
+    /// `tool_use` block ID
+    pub id: String,
+    pub name: String,
+    /// Input exactly as Claude sent it
+    pub input: Value,
+    /// What the call acts on in full: the command, file path or URL
+    pub target: String,
+    /// Claude's own explanation of the call, when the tool takes one
+    pub description: Option<String>,
+    /// Lines removed and added by an Edit or MultiEdit, prefixed with `-` and `+`
+    pub diff: Option<String>,
+    pub risk: ToolRisk,
+}
+
+impl PendingTool {
+    pub fn new(tool: &PendingToolUse, rules: &[RiskRule]) -> Self {
+        Self {
+            id: tool.id.to_string(),
+            name: tool.name.to_string(),
+            input: tool.input.clone(),
+            target: tool_target(tool.name, tool.input),
+            description: description(tool.name, tool.input),
+            diff: edit_diff(tool.name, tool.input),
+            risk: classify_tool(tool.name, tool.input, rules),
+        }
+    }
+}
+
+fn description(tool: &str, input: &Value) -> Option<String> {
+    // WebFetch says what it will look for on the page instead
+    let field = if tool == "WebFetch" { "prompt" } else { "description" };
+    input
+        .get(field)
+        .and_then(Value::as_str)
+        .filter(|text| !text.trim().is_empty())
+        .map(str::to_string)
+}
+
+fn edit_diff(tool: &str, input: &Value) -> Option<String> {
+    let edits: Vec<&Value> = match tool {
+        "Edit" => vec![input],
+        "MultiEdit" => input.get("edits")?.as_array()?.iter().collect(),
+        _ => return None,
+    };
+
+    let hunks: Vec<String> = edits
+        .iter()
+        .filter_map(|edit| {
+            let old = edit.get("old_string")?.as_str()?;
+            let new = edit.get("new_string")?.as_str()?;
+            let removed = old.lines().map(|line| format!("-{}", line));
+            let added = new.lines().map(|line| format!("+{}", line));
+            Some(removed.chain(added).collect::<Vec<_>>().join("\n"))
+        })
+        .collect();
+    (!hunks.is_empty()).then(|| hunks.join("\n@@\n"))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::session::RiskLevel;
+    use serde_json::json;
+
+    fn pending(name: &str, input: Value) -> PendingTool {
+        let tool = PendingToolUse {
+            id: "toolu_1",
+            name,
+            input: &input,
+        };
+        PendingTool::new(&tool, &RiskRule::defaults())
+    }
+
+    #[test]
+    fn test_bash_call_keeps_command_and_description() {
+        let tool = pending(
+            "Bash",
+            json!({ "command": "rm -rf dist && npm run build", "description": "Clean rebuild" }),
+        );
+
+        assert_eq!(tool.id, "toolu_1");
+        assert_eq!(tool.target, "rm -rf dist && npm run build");
+        assert_eq!(tool.description.as_deref(), Some("Clean rebuild"));
+        assert_eq!(tool.diff, None);
+        assert_eq!(tool.risk.level, RiskLevel::Destructive);
+    }
+
+    #[test]
+    fn test_edits_become_a_diff() {
+        let edit = pending(
+            "Edit",
+            json!({ "file_path": "/work/src/lib.rs", "old_string": "a\nb", "new_string": "a\nc" }),
+        );
+        assert_eq!(edit.target, "/work/src/lib.rs");
+        assert_eq!(edit.diff.as_deref(), Some("-a\n-b\n+a\n+c"));
+
+        let multi = pending(
+            "MultiEdit",
+            json!({
+                "file_path": "/work/src/lib.rs",
+                "edits": [
+                    { "old_string": "x", "new_string": "y" },
+                    { "old_string": "fn old()", "new_string": "" }
+                ]
+            }),
+        );
+        assert_eq!(multi.diff.as_deref(), Some("-x\n+y\n@@\n-fn old()"));
+
+        let fetch = pending(
+            "WebFetch",
+            json!({ "url": "https://docs.rs/serde", "prompt": "Find the derive attributes" }),
+        );
+        assert_eq!(fetch.target, "https://docs.rs/serde");
+        assert_eq!(fetch.description.as_deref(), Some("Find the derive attributes"));
+    }
+}
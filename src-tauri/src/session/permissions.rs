@@ -33,15 +33,14 @@ enum AllowPattern {
 }
 
 impl PermissionChecker {
-    /// Load permissions from settings file
-    pub fn from_settings_file() -> Self {
-        let home_dir = match dirs::home_dir() {
-            Some(dir) => dir,
-            None => return Self::default(),
-        };
-
-        let settings_path = home_dir.join(".claude").join("settings.json");
-        Self::from_file(&settings_path)
+    /// Build a checker from allow rules as written in `settings.json`
+    pub fn from_rules<S: AsRef<str>>(rules: &[S]) -> Self {
+        Self {
+            allowed_patterns: rules
+                .iter()
+                .filter_map(|rule| Self::parse_pattern(rule.as_ref()))
+                .collect(),
+        }
     }
 
     /// Load permissions from a specific file
@@ -61,11 +60,7 @@ impl PermissionChecker {
             .and_then(|p| p.allow)
             .unwrap_or_default();
 
-        let patterns = allowed.iter().filter_map(|s| Self::parse_pattern(s)).collect();
-
-        Self {
-            allowed_patterns: patterns,
-        }
+        Self::from_rules(&allowed)
     }
 
     /// Parse a permission pattern string into an AllowPattern
@@ -285,7 +280,10 @@ mod tests {
     #[test]
     fn test_load_from_real_settings() {
         // This test uses the real settings file if available
-        let checker = PermissionChecker::from_settings_file();
+        let Some(root) = crate::session::claude_dirs().primary() else {
+            return;
+        };
+        let checker = PermissionChecker::from_file(&root.join("settings.json"));
 
         // Just verify it loads without crashing
         println!("Loaded {} patterns", checker.allowed_patterns.len());
@@ -0,0 +1,132 @@
+use super::parser::{parse_jsonl_entries, SessionEntry};
+use super::permissions::PermissionChecker;
+use super::status::{FixedClock, SessionStatus, StatusContext};
+use crate::config::StatusConfig;
+use chrono::{DateTime, Utc};
+use std::fs;
+use std::path::Path;
+
+/// A recorded session transcript that can be replayed as it was written
+///
+/// Each JSONL line keeps its own `timestamp` (if any), so the transcript can
+/// be cut at any instant to reproduce what c9watch would have read from disk
+/// at that moment.
+#[derive(Debug, Clone)]
+pub struct Transcript {
+    lines: Vec<(Option<DateTime<Utc>>, String)>,
+}
+
+impl Transcript {
+    /// Load a JSONL transcript from disk
+    pub fn from_file<P: AsRef<Path>>(path: P) -> Result<Self, String> {
+        let content = fs::read_to_string(path.as_ref())
+            .map_err(|e| format!("Failed to read transcript: {}", e))?;
+        Ok(Self::from_lines(content.lines().map(str::to_string)))
+    }
+
+    /// Build a transcript from raw JSONL lines (blank lines are skipped)
+    pub fn from_lines<I: IntoIterator<Item = String>>(lines: I) -> Self {
+        let lines = lines
+            .into_iter()
+            .filter(|line| !line.trim().is_empty())
+            .map(|line| (line_timestamp(&line), line))
+            .collect();
+        Self { lines }
+    }
+
+    /// Timestamp of the first timestamped line
+    pub fn start(&self) -> Option<DateTime<Utc>> {
+        self.lines.iter().find_map(|(ts, _)| *ts)
+    }
+
+    /// Timestamp of the last timestamped line
+    pub fn end(&self) -> Option<DateTime<Utc>> {
+        self.lines.iter().rev().find_map(|(ts, _)| *ts)
+    }
+
+    /// Raw lines written up to and including `at`
+    ///
+    /// The file only ever grows, so this is the longest prefix whose lines
+    /// are not newer than `at`; untimestamped lines follow their predecessor.
+    pub fn lines_at(&self, at: DateTime<Utc>) -> Vec<String> {
+        self.lines
+            .iter()
+            .take_while(|(ts, _)| ts.is_none_or(|ts| ts <= at))
+            .map(|(_, line)| line.clone())
+            .collect()
+    }
+
+    /// The last `tail` entries as the poller would have parsed them at `at`
+    pub fn entries_at(&self, at: DateTime<Utc>, tail: usize) -> Vec<SessionEntry> {
+        let lines = self.lines_at(at);
+        let start = lines.len().saturating_sub(tail);
+        parse_jsonl_entries(lines[start..].to_vec())
+    }
+
+    /// Status the engine reports at `at`, with the clock frozen at that instant
+    pub fn status_at(
+        &self,
+        at: DateTime<Utc>,
+        tail: usize,
+        thresholds: &StatusConfig,
+        permissions: &PermissionChecker,
+    ) -> SessionStatus {
+        let clock = FixedClock(at);
+        StatusContext {
+            clock: &clock,
+            thresholds,
+            permissions,
+        }
+        .determine(&self.entries_at(at, tail))
+    }
+}
+
+fn line_timestamp(line: &str) -> Option<DateTime<Utc>> {
+    let value: serde_json::Value = serde_json::from_str(line).ok()?;
+    let timestamp = value.get("timestamp")?.as_str()?;
+    DateTime::parse_from_rfc3339(timestamp)
+        .ok()
+        .map(|ts| ts.with_timezone(&Utc))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn at(ts: &str) -> DateTime<Utc> {
+        DateTime::parse_from_rfc3339(ts).unwrap().with_timezone(&Utc)
+    }
+
+    fn transcript() -> Transcript {
+        Transcript::from_lines([
+            r#"{"type":"user","uuid":"u1","timestamp":"2026-03-01T12:00:00Z","message":{"role":"user","content":"hi"}}"#.to_string(),
+            r#"{"type":"summary","summary":"Greeting","leafUuid":"u1"}"#.to_string(),
+            r#"{"type":"assistant","uuid":"a1","timestamp":"2026-03-01T12:00:04Z","message":{"model":"m","id":"msg_1","role":"assistant","content":[{"type":"text","text":"hello"}],"stop_reason":null,"stop_sequence":null,"usage":null}}"#.to_string(),
+        ])
+    }
+
+    #[test]
+    fn test_lines_at_cuts_the_transcript() {
+        let transcript = transcript();
+        assert_eq!(transcript.start(), Some(at("2026-03-01T12:00:00Z")));
+        assert_eq!(transcript.end(), Some(at("2026-03-01T12:00:04Z")));
+
+        assert!(transcript.lines_at(at("2026-03-01T11:59:59Z")).is_empty());
+        // The untimestamped summary follows the user line
+        assert_eq!(transcript.lines_at(at("2026-03-01T12:00:02Z")).len(), 2);
+        assert_eq!(transcript.lines_at(at("2026-03-01T12:00:04Z")).len(), 3);
+    }
+
+    #[test]
+    fn test_status_timeline() {
+        let transcript = transcript();
+        let thresholds = StatusConfig::default();
+        let permissions = PermissionChecker::default();
+        let status = |ts| transcript.status_at(at(ts), 20, &thresholds, &permissions);
+
+        assert_eq!(status("2026-03-01T11:59:00Z"), SessionStatus::Connecting);
+        assert_eq!(status("2026-03-01T12:00:02Z"), SessionStatus::Working);
+        assert_eq!(status("2026-03-01T12:00:05Z"), SessionStatus::Working);
+        assert_eq!(status("2026-03-01T12:01:00Z"), SessionStatus::WaitingForInput);
+    }
+}
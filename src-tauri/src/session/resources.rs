@@ -0,0 +1,332 @@
+use crate::config::ResourceConfig;
+use serde::Serialize;
+use std::collections::{HashMap, VecDeque};
+use std::sync::Mutex;
+use sysinfo::{Pid, ProcessRefreshKind, ProcessesToUpdate, System, UpdateKind};
+
+/// CPU, memory and uptime of a claude process together with every process it spawned
+#[derive(Debug, Clone, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ResourceUsage {
+    /// CPU use of the whole tree since the previous sample; 100 is one full core
+    pub cpu_percent: f64,
+    /// Average of `cpu_percent` over the sampling window
+    pub avg_cpu_percent: f64,
+    /// Resident memory of the whole tree
+    pub rss_bytes: u64,
+    /// Highest `rss_bytes` seen since the session was first sampled
+    pub peak_rss_bytes: u64,
+    /// Seconds since the claude process started
+    pub uptime_secs: u64,
+    /// Processes in the tree, the claude process included
+    pub process_count: u32,
+}
+
+/// A process started, directly or not, by a claude process
+#[derive(Debug, Clone, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ChildProcess {
+    pub pid: u32,
+    pub parent_pid: u32,
+    /// 1 for processes claude started itself, 2 for their children, and so on
+    pub depth: u32,
+    pub name: String,
+    /// Full command line, arguments joined with spaces
+    pub command: String,
+    /// Seconds since the process started
+    pub age_secs: u64,
+    /// CPU use since the previous sample; 100 is one full core
+    pub cpu_percent: f64,
+    pub rss_bytes: u64,
+}
+
+/// A resource budget from [`ResourceConfig`]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize)]
+pub enum ResourceBudget {
+    Memory,
+    Cpu,
+}
+
+impl ResourceUsage {
+    /// Budgets this usage is over; CPU is judged on the average so a
+    /// short burst doesn't count
+    pub fn exceeded_budgets(&self, config: &ResourceConfig) -> Vec<ResourceBudget> {
+        let mut exceeded = Vec::new();
+        if config.memory_budget_mb > 0 && self.rss_bytes > config.memory_budget_mb * 1024 * 1024 {
+            exceeded.push(ResourceBudget::Memory);
+        }
+        if config.cpu_budget_percent > 0 && self.avg_cpu_percent > config.cpu_budget_percent as f64
+        {
+            exceeded.push(ResourceBudget::Cpu);
+        }
+        exceeded
+    }
+}
+
+/// Samples process trees once per polling cycle, keeping the history CPU
+/// averages and peaks need
+///
+/// CPU use is measured between two refreshes of the same `System`, so the
+/// monitor has to outlive a single detection pass, and only the polling loop
+/// may refresh it or the window stops matching `sample_window` cycles.
+struct ResourceMonitor {
+    system: System,
+    cpu_samples: HashMap<u32, VecDeque<f64>>,
+    peak_rss: HashMap<u32, u64>,
+    /// Usage from the last sample, for callers outside the polling loop
+    latest: HashMap<u32, ResourceUsage>,
+}
+
+impl ResourceMonitor {
+    fn new() -> Self {
+        Self {
+            system: System::new(),
+            cpu_samples: HashMap::new(),
+            peak_rss: HashMap::new(),
+            latest: HashMap::new(),
+        }
+    }
+
+    fn usage(&mut self, pid: u32, window: usize) -> Option<ResourceUsage> {
+        let root = self.system.process(Pid::from_u32(pid))?;
+        let uptime_secs = root.run_time();
+
+        let parents = parents(&self.system);
+
+        let mut cpu_percent = 0.0;
+        let mut rss_bytes = 0;
+        let tree = process_tree(&parents, pid);
+        for member in &tree {
+            if let Some(process) = self.system.process(Pid::from_u32(*member)) {
+                cpu_percent += process.cpu_usage() as f64;
+                rss_bytes += process.memory();
+            }
+        }
+
+        let samples = self.cpu_samples.entry(pid).or_default();
+        samples.push_back(cpu_percent);
+        while samples.len() > window.max(1) {
+            samples.pop_front();
+        }
+        let avg_cpu_percent = samples.iter().sum::<f64>() / samples.len() as f64;
+
+        let peak = self.peak_rss.entry(pid).or_default();
+        *peak = (*peak).max(rss_bytes);
+
+        Some(ResourceUsage {
+            cpu_percent: round(cpu_percent),
+            avg_cpu_percent: round(avg_cpu_percent),
+            rss_bytes,
+            peak_rss_bytes: *peak,
+            uptime_secs,
+            process_count: tree.len() as u32,
+        })
+    }
+
+    fn retain(&mut self, pids: &[u32]) {
+        self.cpu_samples.retain(|pid, _| pids.contains(pid));
+        self.peak_rss.retain(|pid, _| pids.contains(pid));
+    }
+}
+
+fn refresh(system: &mut System) {
+    system.refresh_processes_specifics(
+        ProcessesToUpdate::All,
+        true,
+        ProcessRefreshKind::new()
+            .with_cpu()
+            .with_memory()
+            .with_cmd(UpdateKind::OnlyIfNotSet),
+    );
+}
+
+/// (pid, parent pid) of every process, leaving out threads
+///
+/// Threads show up as processes on Linux and share their parent's memory.
+fn parents(system: &System) -> Vec<(u32, Option<u32>)> {
+    system
+        .processes()
+        .values()
+        .filter(|process| process.thread_kind().is_none())
+        .map(|process| (process.pid().as_u32(), process.parent().map(|p| p.as_u32())))
+        .collect()
+}
+
+fn children(system: &System, pid: u32) -> Option<Vec<ChildProcess>> {
+    system.process(Pid::from_u32(pid))?;
+
+    let parents = parents(system);
+    let mut depths: HashMap<u32, u32> = HashMap::from([(pid, 0)]);
+    let mut children = Vec::new();
+    // The tree lists parents before their children
+    for member in process_tree(&parents, pid).into_iter().skip(1) {
+        let Some(process) = system.process(Pid::from_u32(member)) else {
+            continue;
+        };
+        let parent_pid = process.parent().map(|p| p.as_u32()).unwrap_or_default();
+        let depth = depths.get(&parent_pid).copied().unwrap_or_default() + 1;
+        depths.insert(member, depth);
+
+        let command = process
+            .cmd()
+            .iter()
+            .map(|arg| arg.to_string_lossy())
+            .collect::<Vec<_>>()
+            .join(" ");
+        children.push(ChildProcess {
+            pid: member,
+            parent_pid,
+            depth,
+            name: process.name().to_string_lossy().to_string(),
+            command,
+            age_secs: process.run_time(),
+            cpu_percent: round(process.cpu_usage() as f64),
+            rss_bytes: process.memory(),
+        });
+    }
+    Some(children)
+}
+
+fn round(percent: f64) -> f64 {
+    (percent * 10.0).round() / 10.0
+}
+
+/// `root` and all of its descendants, given (pid, parent pid) pairs
+fn process_tree(parents: &[(u32, Option<u32>)], root: u32) -> Vec<u32> {
+    let mut children: HashMap<u32, Vec<u32>> = HashMap::new();
+    for (pid, parent) in parents {
+        if let Some(parent) = parent {
+            children.entry(*parent).or_default().push(*pid);
+        }
+    }
+
+    let mut tree = vec![root];
+    let mut next = 0;
+    while next < tree.len() {
+        if let Some(kids) = children.get(&tree[next]) {
+            let unseen: Vec<u32> = kids.iter().copied().filter(|kid| !tree.contains(kid)).collect();
+            tree.extend(unseen);
+        }
+        next += 1;
+    }
+    tree
+}
+
+static MONITOR: Mutex<Option<ResourceMonitor>> = Mutex::new(None);
+
+/// Process table for listing children, kept apart from [`MONITOR`] so
+/// listing doesn't add samples to the CPU averages
+static PROCESSES: Mutex<Option<System>> = Mutex::new(None);
+
+/// Sample the process tree of each pid, averaging CPU over `window` samples
+///
+/// Meant for the polling loop only, once per cycle; everything else reads
+/// [`latest_resources`]. History of pids not passed in is dropped, so call
+/// it with every live session at once.
+pub fn sample_resources(pids: &[u32], window: usize) -> HashMap<u32, ResourceUsage> {
+    let mut guard = MONITOR.lock().unwrap_or_else(|p| p.into_inner());
+    let monitor = guard.get_or_insert_with(ResourceMonitor::new);
+
+    refresh(&mut monitor.system);
+    monitor.retain(pids);
+    let usage: HashMap<u32, ResourceUsage> = pids
+        .iter()
+        .filter_map(|pid| Some((*pid, monitor.usage(*pid, window)?)))
+        .collect();
+    monitor.latest = usage.clone();
+    usage
+}
+
+/// Usage of `pid` as of the last [`sample_resources`], without sampling again
+pub fn latest_resources(pid: u32) -> Option<ResourceUsage> {
+    let guard = MONITOR.lock().unwrap_or_else(|p| p.into_inner());
+    guard.as_ref()?.latest.get(&pid).cloned()
+}
+
+/// Processes descending from `pid`, parents before their children
+///
+/// CPU use is since the previous listing. Returns None when `pid` isn't running.
+pub fn child_processes(pid: u32) -> Option<Vec<ChildProcess>> {
+    let mut guard = PROCESSES.lock().unwrap_or_else(|p| p.into_inner());
+    let system = guard.get_or_insert_with(System::new);
+
+    refresh(system);
+    children(system, pid)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_process_tree() {
+        let parents = [
+            (1, None),
+            (100, Some(1)),
+            (101, Some(100)),
+            (102, Some(101)),
+            (103, Some(100)),
+            (200, Some(1)),
+        ];
+
+        let mut tree = process_tree(&parents, 100);
+        tree.sort();
+        assert_eq!(tree, vec![100, 101, 102, 103]);
+        assert_eq!(process_tree(&parents, 999), vec![999]);
+    }
+
+    #[test]
+    fn test_exceeded_budgets() {
+        let config = ResourceConfig {
+            memory_budget_mb: 1024,
+            cpu_budget_percent: 200,
+            sample_window: 5,
+        };
+        let usage = ResourceUsage {
+            cpu_percent: 390.0,
+            avg_cpu_percent: 150.0,
+            rss_bytes: 2048 * 1024 * 1024,
+            peak_rss_bytes: 2048 * 1024 * 1024,
+            uptime_secs: 60,
+            process_count: 3,
+        };
+
+        // A spike above the CPU budget doesn't count until it lasts
+        assert_eq!(usage.exceeded_budgets(&config), vec![ResourceBudget::Memory]);
+
+        let disabled = ResourceConfig {
+            memory_budget_mb: 0,
+            cpu_budget_percent: 0,
+            sample_window: 5,
+        };
+        assert!(usage.exceeded_budgets(&disabled).is_empty());
+    }
+
+    #[test]
+    fn test_sample_own_process() {
+        let pid = std::process::id();
+        let usage = sample_resources(&[pid], 3).remove(&pid).unwrap();
+        assert!(usage.rss_bytes > 0);
+        assert!(usage.process_count >= 1);
+        assert_eq!(usage.peak_rss_bytes, usage.rss_bytes);
+
+        // Reading the last sample doesn't take another one
+        assert_eq!(latest_resources(pid), Some(usage));
+        assert_eq!(latest_resources(u32::MAX), None);
+    }
+
+    #[test]
+    fn test_child_processes() {
+        let mut child = std::process::Command::new("sleep").arg("30").spawn().unwrap();
+
+        let children = child_processes(std::process::id()).unwrap();
+        let sleep = children.iter().find(|c| c.pid == child.id()).unwrap();
+        assert_eq!(sleep.parent_pid, std::process::id());
+        assert_eq!(sleep.depth, 1);
+        assert_eq!(sleep.command, "sleep 30");
+
+        let _ = child.kill();
+        let _ = child.wait();
+        assert_eq!(child_processes(u32::MAX), None);
+    }
+}
@@ -0,0 +1,283 @@
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+
+/// What a tool call could do, from least to most worrying
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum RiskLevel {
+    /// Only looks at files or the web
+    ReadOnly,
+    /// Changes files in the project, or runs a command that might
+    LocalWrite,
+    /// Sends data or code off the machine
+    Network,
+    /// Reads or writes credentials
+    Secrets,
+    /// Deletes or rewrites work that may not be recoverable
+    Destructive,
+}
+
+impl RiskLevel {
+    /// Lowercase name for notifications and reasons
+    pub fn label(&self) -> &'static str {
+        match self {
+            RiskLevel::ReadOnly => "read-only",
+            RiskLevel::LocalWrite => "local write",
+            RiskLevel::Network => "network",
+            RiskLevel::Secrets => "secrets",
+            RiskLevel::Destructive => "destructive",
+        }
+    }
+}
+
+/// A rule raising the risk of the tool calls it matches
+///
+/// Exactly one of `command` and `contains` is set.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct RiskRule {
+    /// Tools the rule applies to ("Bash", "Edit", "mcp__github__create_pr", ...); empty for all
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub tools: Vec<String>,
+
+    /// A command the Bash line runs, matched word by word at the start of each
+    /// command in it, so "rm" matches `make && rm -rf out` but not `npm run format`
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub command: Option<String>,
+
+    /// Text anywhere in the call's target (command, file path or URL), ignoring case
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub contains: Option<String>,
+
+    pub level: RiskLevel,
+}
+
+impl RiskRule {
+    fn command(command: &str, level: RiskLevel) -> Self {
+        Self {
+            tools: vec!["Bash".to_string()],
+            command: Some(command.to_string()),
+            contains: None,
+            level,
+        }
+    }
+
+    fn contains(text: &str, level: RiskLevel) -> Self {
+        Self {
+            tools: vec![],
+            command: None,
+            contains: Some(text.to_string()),
+            level,
+        }
+    }
+
+    /// Rules used when the config doesn't list any
+    pub fn defaults() -> Vec<Self> {
+        use RiskLevel::*;
+
+        let destructive = [
+            "rm",
+            "rmdir",
+            "shred",
+            "dd",
+            "mkfs",
+            "git reset --hard",
+            "git clean",
+            "git checkout --",
+            "git restore",
+            "git branch -D",
+            "git push --force",
+            "git push -f",
+        ];
+        let network = [
+            "git push",
+            "curl",
+            "wget",
+            "ssh",
+            "scp",
+            "rsync",
+            "docker push",
+            "npm publish",
+            "yarn publish",
+            "pnpm publish",
+            "cargo publish",
+            "twine upload",
+            "gh release",
+        ];
+        let destructive_sql = ["drop table", "drop database", "truncate table"];
+        let secrets = [
+            ".env",
+            ".ssh/",
+            "id_rsa",
+            "id_ed25519",
+            ".aws/",
+            ".netrc",
+            ".npmrc",
+            ".pem",
+            "credentials",
+        ];
+
+        destructive
+            .iter()
+            .map(|c| Self::command(c, Destructive))
+            .chain(destructive_sql.iter().map(|t| Self::contains(t, Destructive)))
+            .chain(network.iter().map(|c| Self::command(c, Network)))
+            .chain(secrets.iter().map(|t| Self::contains(t, Secrets)))
+            .collect()
+    }
+
+    /// Whether the rule has exactly one non-empty pattern
+    pub fn is_valid(&self) -> bool {
+        match (&self.command, &self.contains) {
+            (Some(pattern), None) | (None, Some(pattern)) => !pattern.trim().is_empty(),
+            _ => false,
+        }
+    }
+
+    fn matches(&self, tool: &str, target: &str) -> bool {
+        if !self.tools.is_empty() && !self.tools.iter().any(|t| t == tool) {
+            return false;
+        }
+        match (&self.command, &self.contains) {
+            (Some(command), _) => tool == "Bash" && runs_command(target, command),
+            (None, Some(text)) => target.to_lowercase().contains(&text.to_lowercase()),
+            (None, None) => false,
+        }
+    }
+
+    /// The pattern, for showing why a call got its level
+    fn pattern(&self) -> &str {
+        self.command.as_deref().or(self.contains.as_deref()).unwrap_or_default()
+    }
+}
+
+/// Risk of a pending tool call and the rules that decided it
+#[derive(Debug, Clone, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ToolRisk {
+    pub level: RiskLevel,
+    /// Patterns of the rules that matched at that level, e.g. "git push" or ".env"
+    pub matched: Vec<String>,
+}
+
+/// What a tool call acts on: the command for Bash, the path for file tools,
+/// the URL for WebFetch, and the raw input for anything else
+pub fn tool_target(tool: &str, input: &Value) -> String {
+    let field = |name: &str| input.get(name).and_then(Value::as_str).map(str::to_string);
+    let target = match tool {
+        "Bash" => field("command"),
+        "Read" | "Write" | "Edit" | "MultiEdit" => field("file_path"),
+        "NotebookEdit" => field("notebook_path"),
+        "Glob" | "Grep" => field("pattern"),
+        "WebFetch" => field("url"),
+        "WebSearch" => field("query"),
+        _ => None,
+    };
+    target.unwrap_or_else(|| input.to_string())
+}
+
+/// Classify a tool call with `rules`; the highest level among the tool's own and the matching rules wins
+pub fn classify_tool(tool: &str, input: &Value, rules: &[RiskRule]) -> ToolRisk {
+    let target = tool_target(tool, input);
+    let mut risk = ToolRisk {
+        level: base_level(tool),
+        matched: vec![],
+    };
+
+    for rule in rules.iter().filter(|rule| rule.matches(tool, &target)) {
+        if rule.level > risk.level {
+            risk.level = rule.level;
+            risk.matched.clear();
+        }
+        if rule.level == risk.level {
+            risk.matched.push(rule.pattern().to_string());
+        }
+    }
+    risk
+}
+
+/// Level of a tool before any rule, from what the tool can do at most
+fn base_level(tool: &str) -> RiskLevel {
+    match tool {
+        "Read" | "Glob" | "Grep" | "LS" | "WebSearch" | "TodoWrite" | "Task" | "TaskList"
+        | "TaskGet" | "AskUserQuestion" => RiskLevel::ReadOnly,
+        "WebFetch" => RiskLevel::Network,
+        _ => RiskLevel::LocalWrite,
+    }
+}
+
+/// Whether any command of a shell line starts with the words of `command`
+fn runs_command(line: &str, command: &str) -> bool {
+    let prefix: Vec<&str> = command.split_whitespace().collect();
+    line.split(['&', '|', ';', '\n', '(', ')', '`'])
+        .map(|part| part.split_whitespace().collect::<Vec<_>>())
+        .any(|words| {
+            // Look through wrappers so "sudo rm" counts as rm
+            let start = words
+                .iter()
+                .position(|word| !matches!(*word, "sudo" | "xargs" | "env" | "time" | "nohup"))
+                .unwrap_or(words.len());
+            words[start..].starts_with(&prefix)
+        })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn level(tool: &str, input: Value) -> RiskLevel {
+        classify_tool(tool, &input, &RiskRule::defaults()).level
+    }
+
+    #[test]
+    fn test_default_rules() {
+        use RiskLevel::*;
+
+        assert_eq!(level("Read", json!({ "file_path": "/work/src/lib.rs" })), ReadOnly);
+        assert_eq!(level("Edit", json!({ "file_path": "/work/src/lib.rs" })), LocalWrite);
+        assert_eq!(level("Bash", json!({ "command": "npm run format" })), LocalWrite);
+        assert_eq!(level("Bash", json!({ "command": "cargo build && sudo rm -rf target" })), Destructive);
+        assert_eq!(level("Bash", json!({ "command": "git reset --hard HEAD~1" })), Destructive);
+        assert_eq!(level("Bash", json!({ "command": "psql -c 'DROP TABLE users'" })), Destructive);
+        assert_eq!(level("Bash", json!({ "command": "git commit -m wip; git push" })), Network);
+        assert_eq!(level("Bash", json!({ "command": "echo curl" })), LocalWrite);
+        assert_eq!(level("WebFetch", json!({ "url": "https://example.com" })), Network);
+        assert_eq!(level("Edit", json!({ "file_path": "/work/.env.local" })), Secrets);
+        assert_eq!(level("Bash", json!({ "command": "cat ~/.ssh/id_rsa" })), Secrets);
+    }
+
+    #[test]
+    fn test_matched_rules_explain_the_level() {
+        let risk = classify_tool(
+            "Bash",
+            &json!({ "command": "git push --force origin main" }),
+            &RiskRule::defaults(),
+        );
+        assert_eq!(risk.level, RiskLevel::Destructive);
+        assert_eq!(risk.matched, vec!["git push --force"]);
+
+        // A custom rule scoped to one tool
+        let rules = vec![RiskRule {
+            tools: vec!["mcp__db__query".to_string()],
+            command: None,
+            contains: Some("delete from".to_string()),
+            level: RiskLevel::Destructive,
+        }];
+        let query = json!({ "sql": "DELETE FROM sessions" });
+        assert_eq!(classify_tool("mcp__db__query", &query, &rules).level, RiskLevel::Destructive);
+        assert_eq!(classify_tool("mcp__db__read", &query, &rules).level, RiskLevel::LocalWrite);
+    }
+
+    #[test]
+    fn test_rule_validity() {
+        assert!(RiskRule::defaults().iter().all(RiskRule::is_valid));
+        let rule = RiskRule {
+            tools: vec![],
+            command: Some("rm".to_string()),
+            contains: Some("rm".to_string()),
+            level: RiskLevel::Destructive,
+        };
+        assert!(!rule.is_valid());
+        assert!(!RiskRule { command: None, contains: Some(" ".to_string()), ..rule }.is_valid());
+    }
+}
@@ -1,14 +1,76 @@
 use serde::{Deserialize, Serialize};
 use chrono::{DateTime, Utc};
-use std::sync::OnceLock;
-use super::parser::{SessionEntry, MessageContent, AssistantMessage};
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, Mutex};
+use std::time::SystemTime;
+use super::parser::{SessionEntry, MessageContent, AssistantMessage, ProgressData, SystemEvent, UserMessage};
 use super::permissions::PermissionChecker;
+use crate::config::StatusConfig;
 
-/// Global permission checker (loaded once from settings)
-static PERMISSION_CHECKER: OnceLock<PermissionChecker> = OnceLock::new();
+/// Permission rules of one Claude root, as of the settings file's mtime
+struct LoadedSettings {
+    modified: Option<SystemTime>,
+    checker: Arc<PermissionChecker>,
+}
+
+static PERMISSION_CHECKERS: Mutex<Option<HashMap<PathBuf, LoadedSettings>>> = Mutex::new(None);
+
+/// Permission rules from the `settings.json` of the Claude root `root`
+///
+/// Each profile has its own rules, so sessions are checked against the root
+/// their transcript lives in. The file is read again once it changes.
+pub fn permission_checker(root: &Path) -> Arc<PermissionChecker> {
+    let settings = root.join("settings.json");
+    let modified = std::fs::metadata(&settings).and_then(|m| m.modified()).ok();
+
+    let mut guard = PERMISSION_CHECKERS.lock().unwrap_or_else(|p| p.into_inner());
+    let loaded = guard.get_or_insert_with(HashMap::new);
+    if let Some(cached) = loaded.get(root) {
+        if cached.modified == modified {
+            return Arc::clone(&cached.checker);
+        }
+    }
+
+    let checker = Arc::new(PermissionChecker::from_file(&settings));
+    loaded.insert(
+        root.to_path_buf(),
+        LoadedSettings {
+            modified,
+            checker: Arc::clone(&checker),
+        },
+    );
+    checker
+}
+
+/// Source of "now" for recency checks
+pub trait Clock {
+    fn now(&self) -> DateTime<Utc>;
+}
+
+/// The wall clock
+pub struct SystemClock;
+
+impl Clock for SystemClock {
+    fn now(&self) -> DateTime<Utc> {
+        Utc::now()
+    }
+}
+
+/// A clock frozen at a given instant, for tests and transcript replay
+pub struct FixedClock(pub DateTime<Utc>);
+
+impl Clock for FixedClock {
+    fn now(&self) -> DateTime<Utc> {
+        self.0
+    }
+}
 
-fn get_permission_checker() -> &'static PermissionChecker {
-    PERMISSION_CHECKER.get_or_init(PermissionChecker::from_settings_file)
+/// Everything status detection depends on besides the entries themselves
+pub struct StatusContext<'a> {
+    pub clock: &'a dyn Clock,
+    pub thresholds: &'a StatusConfig,
+    pub permissions: &'a PermissionChecker,
 }
 
 /// Represents the current status of a Claude Code session
@@ -32,108 +94,147 @@ pub enum SessionStatus {
 ///
 /// # Arguments
 /// * `entries` - Recent session entries (typically last 10-20 entries)
+/// * `clock` - Source of "now" for recency checks
+/// * `root` - Claude root whose settings decide which tools are auto-approved
 ///
 /// # Returns
 /// The determined session status
-pub fn determine_status(entries: &[SessionEntry]) -> SessionStatus {
-    // If no entries, session is likely starting up
-    if entries.is_empty() {
-        return SessionStatus::Connecting;
+pub fn determine_status(entries: &[SessionEntry], clock: &dyn Clock, root: &Path) -> SessionStatus {
+    let config = crate::config::current();
+    StatusContext {
+        clock,
+        thresholds: &config.status,
+        permissions: &permission_checker(root),
     }
+    .determine(entries)
+}
 
-    // Find the last meaningful entry (User or Assistant), skipping progress,
-    // file-history-snapshot, summary, and other non-status-bearing entries.
-    // Claude Code writes "progress" entries during tool execution (e.g., bash_progress)
-    // which must not override the actual session status.
-    let last_meaningful = entries.iter().rev().find(|entry| {
-        matches!(entry, SessionEntry::User { .. } | SessionEntry::Assistant { .. })
-    });
+impl StatusContext<'_> {
+    /// Same as [`determine_status`], with every input made explicit
+    pub fn determine(&self, entries: &[SessionEntry]) -> SessionStatus {
+        let thresholds = self.thresholds;
+        let now = self.clock.now();
 
-    let last_entry = match last_meaningful {
-        Some(entry) => entry,
-        None => return SessionStatus::Connecting,
-    };
-
-    // Also check if there are any recent progress entries AFTER the last meaningful entry.
-    // Progress entries (e.g., bash_progress) indicate active tool execution.
-    let last_meaningful_idx = entries.iter().rposition(|entry| {
-        matches!(entry, SessionEntry::User { .. } | SessionEntry::Assistant { .. })
-    }).unwrap_or(0);
-    let has_trailing_progress = entries[last_meaningful_idx + 1..].iter().any(|entry| {
-        matches!(entry, SessionEntry::Unknown)
-    });
+        // If no entries, session is likely starting up
+        if entries.is_empty() {
+            return SessionStatus::Connecting;
+        }
 
-    match last_entry {
-        SessionEntry::User { base, message } => {
-            // Check if this is a tool_result or an actual user prompt.
-            // Tool results mean Claude is still processing.
-            if message.is_tool_result {
-                // This is a tool result - Claude should be generating its next response
-                // But if it's old, the session might be idle (process died, etc.)
-                if is_entry_recent(&base.timestamp, 15) {
+        // Find the last meaningful entry (User or Assistant), skipping progress,
+        // file-history-snapshot, summary, and other non-status-bearing entries.
+        // Claude Code writes "progress" entries during tool execution (e.g., bash_progress)
+        // which must not override the actual session status.
+        let last_meaningful = entries.iter().rev().find(|entry| {
+            matches!(entry, SessionEntry::User { .. } | SessionEntry::Assistant { .. })
+        });
+
+        let last_entry = match last_meaningful {
+            Some(entry) => entry,
+            None => return SessionStatus::Connecting,
+        };
+
+        // Entries written after the last message: live tool output, hook runs,
+        // and Claude Code's own notices (e.g. the end of a turn).
+        let last_meaningful_idx = entries.iter().rposition(|entry| {
+            matches!(entry, SessionEntry::User { .. } | SessionEntry::Assistant { .. })
+        }).unwrap_or(0);
+        let trailing = &entries[last_meaningful_idx + 1..];
+        let turn_ended = trailing.iter().any(|entry| {
+            matches!(entry, SessionEntry::System { event: SystemEvent::TurnDuration { .. }, .. })
+        });
+
+        // Parallel tool calls are written one block per entry, so the last
+        // entry alone may be an auto-approved call while an earlier one (or one
+        // whose siblings already returned) is still waiting for the user
+        let awaiting_permission = || !self.pending_tool_uses(entries).is_empty();
+
+        match last_entry {
+            SessionEntry::User { base, message } => {
+                // Check if this is a tool_result or an actual user prompt.
+                // Tool results mean Claude is still processing.
+                if is_interruption(message) {
+                    // Interrupted with Esc or Ctrl-C; Claude is back at the prompt
+                    SessionStatus::WaitingForInput
+                } else if message.is_tool_result {
+                    // This is a tool result - Claude should be generating its next response
+                    // But if it's old, the session might be idle (process died, etc.)
+                    if awaiting_permission() {
+                        SessionStatus::NeedsPermission
+                    } else if is_entry_recent(&base.timestamp, thresholds.tool_result_recent_secs, now) {
+                        SessionStatus::Working
+                    } else {
+                        SessionStatus::WaitingForInput
+                    }
+                } else if is_entry_recent(&base.timestamp, thresholds.user_prompt_recent_secs, now) {
+                    // Recent user prompt - Claude should be responding
                     SessionStatus::Working
                 } else {
+                    // Old user prompt with no response - session is likely idle
                     SessionStatus::WaitingForInput
                 }
-            } else if is_entry_recent(&base.timestamp, 30) {
-                // Recent user prompt - Claude should be responding
-                SessionStatus::Working
-            } else {
-                // Old user prompt with no response - session is likely idle
-                SessionStatus::WaitingForInput
             }
-        }
-        SessionEntry::Assistant { base, message } => {
-            // Analyze the assistant message content
-            let raw_status = analyze_assistant_message(message);
-
-            match raw_status {
-                SessionStatus::Working => {
-                    // "Working" from analyze_assistant_message means either:
-                    // 1. Pending tool_use (auto-approved) - check for trailing progress
-                    // 2. Text with no stop_reason (but stop_reason is always None in JSONL)
-                    //
-                    // Use recency + trailing progress to distinguish active from idle
-                    let has_pending_tools = has_pending_tool_uses(&message.content);
-
-                    if has_pending_tools {
-                        // Tool is pending - check if there's active progress or recent activity
-                        if has_trailing_progress || is_entry_recent(&base.timestamp, 10) {
+            SessionEntry::Assistant { .. } if awaiting_permission() => {
+                SessionStatus::NeedsPermission
+            }
+            SessionEntry::Assistant { base, message } => {
+                // Analyze the assistant message content
+                let raw_status = analyze_assistant_message(message, self.permissions);
+
+                match raw_status {
+                    SessionStatus::Working => {
+                        // "Working" from analyze_assistant_message means either:
+                        // 1. Pending tool_use (auto-approved) - the tool is running
+                        // 2. Text with no stop_reason (but stop_reason is always None in JSONL)
+                        if has_pending_tool_uses(&message.content) {
                             SessionStatus::Working
+                        } else if turn_ended {
+                            // Claude Code marked the end of the turn
+                            SessionStatus::WaitingForInput
                         } else {
-                            // Pending tool but no recent activity - likely stale
-                            SessionStatus::Working
+                            // No pending tools, just text/thinking content.
+                            // Since stop_reason is always None in JSONL, we use recency:
+                            // if the entry was written recently, Claude is likely still
+                            // streaming or about to write more. If old, session is idle.
+                            if is_entry_recent(&base.timestamp, thresholds.assistant_recent_secs, now) {
+                                SessionStatus::Working
+                            } else {
+                                SessionStatus::WaitingForInput
+                            }
                         }
-                    } else {
-                        // No pending tools, just text/thinking content.
-                        // Since stop_reason is always None in JSONL, we use recency:
-                        // if the entry was written recently, Claude is likely still
-                        // streaming or about to write more. If old, session is idle.
-                        if is_entry_recent(&base.timestamp, 10) {
+                    }
+                    SessionStatus::NeedsPermission => {
+                        // Output from the tool means the user already approved it
+                        if has_started_pending_tools(&message.content, trailing, self.permissions) {
                             SessionStatus::Working
                         } else {
-                            SessionStatus::WaitingForInput
+                            SessionStatus::NeedsPermission
                         }
                     }
+                    _ => raw_status,
                 }
-                SessionStatus::NeedsPermission => {
-                    // Permission-needing tool - return immediately, no delay
-                    SessionStatus::NeedsPermission
-                }
-                _ => raw_status,
             }
-        }
-        _ => {
-            // Should not reach here since we filtered for User/Assistant above
-            SessionStatus::WaitingForInput
+            _ => {
+                // Should not reach here since we filtered for User/Assistant above
+                SessionStatus::WaitingForInput
+            }
         }
     }
 }
 
-/// Checks if a timestamp is within the last N seconds
-fn is_entry_recent(timestamp: &str, seconds: i64) -> bool {
+/// Marker Claude Code writes as a user message when a turn is interrupted
+const INTERRUPTION_MARKER: &str = "[Request interrupted by user";
+
+/// Whether a user entry records an interrupted turn rather than a prompt
+///
+/// Covers both "[Request interrupted by user]" and the
+/// "... for tool use]" variant written when a tool call was cut short.
+pub fn is_interruption(message: &UserMessage) -> bool {
+    !message.is_tool_result && message.content.trim_start().starts_with(INTERRUPTION_MARKER)
+}
+
+/// Checks if a timestamp is within N seconds before `now`
+fn is_entry_recent(timestamp: &str, seconds: i64, now: DateTime<Utc>) -> bool {
     if let Ok(entry_time) = DateTime::parse_from_rfc3339(timestamp) {
-        let now = Utc::now();
         let age = now.signed_duration_since(entry_time.with_timezone(&Utc));
         age.num_seconds() < seconds
     } else {
@@ -143,7 +244,7 @@ fn is_entry_recent(timestamp: &str, seconds: i64) -> bool {
 }
 
 /// Analyzes an assistant message to determine status
-fn analyze_assistant_message(message: &AssistantMessage) -> SessionStatus {
+fn analyze_assistant_message(message: &AssistantMessage, checker: &PermissionChecker) -> SessionStatus {
     // Check if the message contains any tool uses
     let has_tool_use = message.content.iter().any(|content| {
         matches!(content, MessageContent::ToolUse { .. })
@@ -168,7 +269,7 @@ fn analyze_assistant_message(message: &AssistantMessage) -> SessionStatus {
         } else {
             // Tool use present but not all completed
             // Check if pending tools are auto-approved
-            if are_pending_tools_auto_approved(&message.content) {
+            if are_pending_tools_auto_approved(&message.content, checker) {
                 // All pending tools will be auto-approved, so status is Working
                 SessionStatus::Working
             } else {
@@ -190,10 +291,54 @@ fn analyze_assistant_message(message: &AssistantMessage) -> SessionStatus {
     }
 }
 
-/// Checks if all pending (incomplete) tool uses are auto-approved
-fn are_pending_tools_auto_approved(content: &[MessageContent]) -> bool {
-    let checker = get_permission_checker();
+/// Ids of tools that have produced output in `entries`
+///
+/// Hook progress doesn't count: `PreToolUse` hooks run before the user is
+/// asked for permission.
+fn started_tool_ids(entries: &[SessionEntry]) -> Vec<&str> {
+    entries
+        .iter()
+        .filter_map(|entry| match entry {
+            SessionEntry::Progress { data: ProgressData::HookProgress { .. }, .. } => None,
+            SessionEntry::Progress { tool_use_id, parent_tool_use_id, .. } => {
+                parent_tool_use_id.as_deref().or(tool_use_id.as_deref())
+            }
+            _ => None,
+        })
+        .collect()
+}
 
+/// Checks if every pending tool that needs permission is already running
+fn has_started_pending_tools(
+    content: &[MessageContent],
+    trailing: &[SessionEntry],
+    checker: &PermissionChecker,
+) -> bool {
+    let started = started_tool_ids(trailing);
+    if started.is_empty() {
+        return false;
+    }
+
+    let completed_ids: Vec<&str> = content
+        .iter()
+        .filter_map(|c| match c {
+            MessageContent::ToolResult { tool_use_id, .. } => Some(tool_use_id.as_str()),
+            _ => None,
+        })
+        .collect();
+
+    content.iter().all(|item| match item {
+        MessageContent::ToolUse { id, name, input } => {
+            completed_ids.contains(&id.as_str())
+                || checker.is_auto_approved(name, input)
+                || started.contains(&id.as_str())
+        }
+        _ => true,
+    })
+}
+
+/// Checks if all pending (incomplete) tool uses are auto-approved
+fn are_pending_tools_auto_approved(content: &[MessageContent], checker: &PermissionChecker) -> bool {
     // Get IDs of tools that have results
     let completed_ids: Vec<&str> = content
         .iter()
@@ -235,54 +380,121 @@ fn are_pending_tools_auto_approved(content: &[MessageContent]) -> bool {
 ///
 /// # Arguments
 /// * `entries` - Session entries to search through
+/// * `root` - Claude root whose settings decide which tools are auto-approved
 ///
 /// # Returns
 /// The name of the first pending tool that needs permission, or None if:
 /// - No assistant messages found
 /// - No pending tools found
 /// - All pending tools are auto-approved
-pub fn get_pending_tool_name(entries: &[SessionEntry]) -> Option<String> {
-    // Find the last assistant message entry
-    let last_assistant = entries.iter().rev().find_map(|entry| {
-        if let SessionEntry::Assistant { message, .. } = entry {
-            Some(message)
-        } else {
-            None
-        }
-    })?;
+pub fn get_pending_tool_name(entries: &[SessionEntry], root: &Path) -> Option<String> {
+    pending_tool_name(entries, &permission_checker(root))
+}
 
-    let checker = get_permission_checker();
+/// A tool call from the latest assistant message that is waiting for permission
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct PendingToolUse<'a> {
+    /// `tool_use` block ID
+    pub id: &'a str,
+    pub name: &'a str,
+    pub input: &'a serde_json::Value,
+}
 
-    // Get IDs of tools that have results
-    let completed_ids: Vec<&str> = last_assistant.content
+/// Every pending tool that needs permission, in the order Claude requested
+/// them; the first is the one [`get_pending_tool_name`] returns
+pub fn get_pending_tool_uses<'a>(
+    entries: &'a [SessionEntry],
+    root: &Path,
+) -> Vec<PendingToolUse<'a>> {
+    pending_tool_uses(entries, &permission_checker(root))
+}
+
+impl StatusContext<'_> {
+    /// Same as [`get_pending_tool_name`], with this context's permission rules
+    pub fn pending_tool_name(&self, entries: &[SessionEntry]) -> Option<String> {
+        pending_tool_name(entries, self.permissions)
+    }
+
+    /// Same as [`get_pending_tool_uses`], with this context's permission rules
+    pub fn pending_tool_uses<'a>(&self, entries: &'a [SessionEntry]) -> Vec<PendingToolUse<'a>> {
+        pending_tool_uses(entries, self.permissions)
+    }
+}
+
+fn pending_tool_name(entries: &[SessionEntry], checker: &PermissionChecker) -> Option<String> {
+    pending_tool_uses(entries, checker)
+        .first()
+        .map(|tool| tool.name.to_string())
+}
+
+fn pending_tool_uses<'a>(
+    entries: &'a [SessionEntry],
+    checker: &PermissionChecker,
+) -> Vec<PendingToolUse<'a>> {
+    // Find the last assistant message entry
+    let Some(last_idx) = entries
         .iter()
-        .filter_map(|c| {
-            if let MessageContent::ToolResult { tool_use_id, .. } = c {
-                Some(tool_use_id.as_str())
-            } else {
-                None
+        .rposition(|entry| matches!(entry, SessionEntry::Assistant { .. }))
+    else {
+        return vec![];
+    };
+    let SessionEntry::Assistant { message: last_assistant, .. } = &entries[last_idx] else {
+        return vec![];
+    };
+
+    // Claude Code writes each content block of a message as its own entry, so
+    // parallel tool calls are spread over the trailing entries sharing its ID
+    let first_idx = entries[..last_idx]
+        .iter()
+        .rposition(|entry| match entry {
+            SessionEntry::Assistant { message, .. } => message.id != last_assistant.id,
+            SessionEntry::User { message, .. } => !message.is_tool_result,
+            _ => false,
+        })
+        .map_or(0, |idx| idx + 1);
+    let message_entries = &entries[first_idx..];
+    let content: Vec<&MessageContent> = message_entries
+        .iter()
+        .filter_map(|entry| match entry {
+            SessionEntry::Assistant { message, .. } if message.id == last_assistant.id => {
+                Some(&message.content)
             }
+            _ => None,
         })
+        .flatten()
         .collect();
 
-    // Find the first pending tool that needs permission
-    for item in &last_assistant.content {
+    // Tools with output or a result since they were requested have already been approved
+    let started_ids = started_tool_ids(message_entries);
+    let completed_ids: Vec<&str> = message_entries
+        .iter()
+        .flat_map(|entry| match entry {
+            SessionEntry::User { message, .. } => message.tool_results.as_slice(),
+            _ => &[],
+        })
+        .map(|result| result.tool_use_id.as_str())
+        .chain(content.iter().filter_map(|c| match c {
+            MessageContent::ToolResult { tool_use_id, .. } => Some(tool_use_id.as_str()),
+            _ => None,
+        }))
+        .collect();
+
+    // Collect the pending tools that need permission
+    let mut pending = Vec::new();
+    for item in content {
         if let MessageContent::ToolUse { id, name, input } = item {
-            // Skip if already completed
-            if completed_ids.contains(&id.as_str()) {
+            // Skip if already completed or running
+            if completed_ids.contains(&id.as_str()) || started_ids.contains(&id.as_str()) {
                 continue;
             }
 
             // This tool is pending - check if it needs permission
             if !checker.is_auto_approved(name, input) {
-                // Found a tool that needs permission
-                return Some(name.clone());
+                pending.push(PendingToolUse { id, name, input });
             }
         }
     }
-
-    // No pending tools need permission
-    None
+    pending
 }
 
 /// Checks if there are any pending (incomplete) tool uses
@@ -328,7 +540,11 @@ fn check_all_tools_completed(content: &[MessageContent]) -> bool {
 /// This function looks at the last few entries to get more context about
 /// the session state, which can be more accurate than just looking at the
 /// last entry alone.
-pub fn determine_status_with_context(entries: &[SessionEntry]) -> SessionStatus {
+pub fn determine_status_with_context(
+    entries: &[SessionEntry],
+    clock: &dyn Clock,
+    root: &Path,
+) -> SessionStatus {
     if entries.is_empty() {
         return SessionStatus::Connecting;
     }
@@ -339,7 +555,7 @@ pub fn determine_status_with_context(entries: &[SessionEntry]) -> SessionStatus
     }
 
     // Get the basic status from the last entry
-    let basic_status = determine_status(entries);
+    let basic_status = determine_status(entries, clock, root);
 
     // If we detect Working status, but the previous entry was also an assistant
     // message with completed tools, we might actually be waiting for input
@@ -371,12 +587,23 @@ mod tests {
     use super::*;
     use crate::session::parser::{SessionEntryBase, UserMessage};
 
+    /// Instant the test clock is frozen at
+    const NOW: &str = "2026-03-01T12:00:05Z";
+
+    fn clock() -> FixedClock {
+        FixedClock(DateTime::parse_from_rfc3339(NOW).unwrap().with_timezone(&Utc))
+    }
+
+    /// A Claude root without settings, so only the built-in auto-approvals apply
+    fn root() -> PathBuf {
+        std::env::temp_dir().join("c9watch-status-no-settings")
+    }
+
     fn create_base() -> SessionEntryBase {
-        // Use current time so recency checks pass in tests
-        let now = Utc::now().to_rfc3339();
+        // A few seconds before the test clock so recency checks pass
         SessionEntryBase {
             uuid: "test-uuid".to_string(),
-            timestamp: now,
+            timestamp: "2026-03-01T12:00:00Z".to_string(),
             session_id: Some("test-session".to_string()),
             cwd: None,
             version: None,
@@ -405,7 +632,7 @@ mod tests {
     #[test]
     fn test_empty_entries() {
         let entries: Vec<SessionEntry> = vec![];
-        assert_eq!(determine_status(&entries), SessionStatus::Connecting);
+        assert_eq!(determine_status(&entries, &clock(), &root()), SessionStatus::Connecting);
     }
 
     #[test]
@@ -417,10 +644,11 @@ mod tests {
                     role: "user".to_string(),
                     content: "Hello".to_string(),
                     is_tool_result: false,
+                    tool_results: vec![],
                 },
             }
         ];
-        assert_eq!(determine_status(&entries), SessionStatus::Working);
+        assert_eq!(determine_status(&entries, &clock(), &root()), SessionStatus::Working);
     }
 
     #[test]
@@ -444,7 +672,7 @@ mod tests {
                 },
             }
         ];
-        assert_eq!(determine_status(&entries), SessionStatus::WaitingForInput);
+        assert_eq!(determine_status(&entries, &clock(), &root()), SessionStatus::WaitingForInput);
     }
 
     #[test]
@@ -467,7 +695,7 @@ mod tests {
                 },
             }
         ];
-        assert_eq!(determine_status(&entries), SessionStatus::Working);
+        assert_eq!(determine_status(&entries, &clock(), &root()), SessionStatus::Working);
     }
 
     #[test]
@@ -493,7 +721,7 @@ mod tests {
                 },
             }
         ];
-        assert_eq!(determine_status(&entries), SessionStatus::Working);
+        assert_eq!(determine_status(&entries, &clock(), &root()), SessionStatus::Working);
     }
 
     #[test]
@@ -519,7 +747,7 @@ mod tests {
                 },
             }
         ];
-        assert_eq!(determine_status(&entries), SessionStatus::NeedsPermission);
+        assert_eq!(determine_status(&entries, &clock(), &root()), SessionStatus::NeedsPermission);
     }
 
     #[test]
@@ -549,7 +777,7 @@ mod tests {
                 },
             }
         ];
-        assert_eq!(determine_status(&entries), SessionStatus::WaitingForInput);
+        assert_eq!(determine_status(&entries, &clock(), &root()), SessionStatus::WaitingForInput);
     }
 
     #[test]
@@ -585,7 +813,7 @@ mod tests {
                 },
             }
         ];
-        assert_eq!(determine_status(&entries), SessionStatus::Working);
+        assert_eq!(determine_status(&entries, &clock(), &root()), SessionStatus::Working);
     }
 
     #[test]
@@ -621,7 +849,7 @@ mod tests {
                 },
             }
         ];
-        assert_eq!(determine_status(&entries), SessionStatus::NeedsPermission);
+        assert_eq!(determine_status(&entries, &clock(), &root()), SessionStatus::NeedsPermission);
     }
 
     #[test]
@@ -661,7 +889,7 @@ mod tests {
                 },
             }
         ];
-        assert_eq!(determine_status(&entries), SessionStatus::WaitingForInput);
+        assert_eq!(determine_status(&entries, &clock(), &root()), SessionStatus::WaitingForInput);
     }
 
     #[test]
@@ -692,7 +920,7 @@ mod tests {
 
     #[test]
     fn test_unknown_entries_after_tool_use_dont_override_status() {
-        // Simulates: assistant(tool_use Bash) followed by Unknown entries (progress)
+        // Simulates: assistant(tool_use Bash) followed by entries of unknown types
         // Status should still reflect the pending Bash tool, not WaitingForInput
         let entries = vec![
             SessionEntry::Assistant {
@@ -713,12 +941,12 @@ mod tests {
                     usage: None,
                 },
             },
-            // Progress entries (parsed as Unknown from "progress" type in JSONL)
+            // e.g. a "type" introduced by a newer Claude Code
             SessionEntry::Unknown,
             SessionEntry::Unknown,
         ];
         // Should NOT be WaitingForInput - should see the pending Bash tool
-        let status = determine_status(&entries);
+        let status = determine_status(&entries, &clock(), &root());
         assert_ne!(status, SessionStatus::WaitingForInput);
         // Bash with "cargo build" is not in default auto-approved list
         assert_eq!(status, SessionStatus::NeedsPermission);
@@ -726,7 +954,7 @@ mod tests {
 
     #[test]
     fn test_unknown_entries_after_user_message_still_working() {
-        // Simulates: user message followed by Unknown entries (progress from sub-agent)
+        // Simulates: user message followed by entries of unknown types
         let entries = vec![
             SessionEntry::User {
                 base: create_base(),
@@ -734,22 +962,105 @@ mod tests {
                     role: "user".to_string(),
                     content: "Hello".to_string(),
                     is_tool_result: false,
+                    tool_results: vec![],
                 },
             },
             SessionEntry::Unknown,
             SessionEntry::Unknown,
         ];
-        assert_eq!(determine_status(&entries), SessionStatus::Working);
+        assert_eq!(determine_status(&entries, &clock(), &root()), SessionStatus::Working);
     }
 
     #[test]
     fn test_only_unknown_entries_means_connecting() {
-        // If all entries are Unknown (e.g., all progress), treat as Connecting
+        // If no entry is a message, treat as Connecting
         let entries = vec![
             SessionEntry::Unknown,
             SessionEntry::Unknown,
         ];
-        assert_eq!(determine_status(&entries), SessionStatus::Connecting);
+        assert_eq!(determine_status(&entries, &clock(), &root()), SessionStatus::Connecting);
+    }
+
+    fn progress(parent_tool_use_id: &str, data: ProgressData) -> SessionEntry {
+        SessionEntry::Progress {
+            base: create_base(),
+            tool_use_id: Some("bash-progress-0".to_string()),
+            parent_tool_use_id: Some(parent_tool_use_id.to_string()),
+            data,
+        }
+    }
+
+    fn bash_tool_use(command: &str) -> SessionEntry {
+        SessionEntry::Assistant {
+            base: create_base(),
+            message: AssistantMessage {
+                model: "claude-opus-4-5-20251101".to_string(),
+                id: "msg_test".to_string(),
+                role: "assistant".to_string(),
+                content: vec![MessageContent::ToolUse {
+                    id: "toolu_123".to_string(),
+                    name: "Bash".to_string(),
+                    input: serde_json::json!({ "command": command }),
+                }],
+                stop_reason: None,
+                stop_sequence: None,
+                usage: None,
+            },
+        }
+    }
+
+    #[test]
+    fn test_tool_output_means_permission_was_granted() {
+        let bash = ProgressData::BashProgress {
+            output: Some("Compiling".to_string()),
+            full_output: None,
+            elapsed_time_seconds: Some(2.0),
+            total_lines: Some(1),
+        };
+        let entries = vec![bash_tool_use("cargo build"), progress("toolu_123", bash)];
+        assert_eq!(determine_status(&entries, &clock(), &root()), SessionStatus::Working);
+        assert_eq!(get_pending_tool_name(&entries, &root()), None);
+
+        // PreToolUse hooks run before the prompt, so they don't count
+        let hook = ProgressData::HookProgress {
+            hook_event: Some("PreToolUse".to_string()),
+            hook_name: Some("PreToolUse:Bash".to_string()),
+            command: Some("./check.sh".to_string()),
+        };
+        let entries = vec![bash_tool_use("cargo build"), progress("toolu_123", hook)];
+        assert_eq!(determine_status(&entries, &clock(), &root()), SessionStatus::NeedsPermission);
+        assert_eq!(get_pending_tool_name(&entries, &root()).as_deref(), Some("Bash"));
+    }
+
+    #[test]
+    fn test_turn_duration_ends_the_turn() {
+        let entries = vec![
+            SessionEntry::Assistant {
+                base: create_base(),
+                message: AssistantMessage {
+                    model: "claude-opus-4-5-20251101".to_string(),
+                    id: "msg_test".to_string(),
+                    role: "assistant".to_string(),
+                    content: vec![MessageContent::Text {
+                        text: "Done.".to_string(),
+                    }],
+                    stop_reason: None,
+                    stop_sequence: None,
+                    usage: None,
+                },
+            },
+            SessionEntry::System {
+                base: create_base(),
+                level: Some("info".to_string()),
+                content: None,
+                event: SystemEvent::TurnDuration {
+                    duration_ms: Some(4200),
+                },
+            },
+        ];
+        // Recent text alone would still read as Working
+        assert_eq!(determine_status(&entries[..1], &clock(), &root()), SessionStatus::Working);
+        assert_eq!(determine_status(&entries, &clock(), &root()), SessionStatus::WaitingForInput);
     }
 
     #[test]
@@ -774,7 +1085,7 @@ mod tests {
                 },
             }
         ];
-        assert_eq!(determine_status(&entries), SessionStatus::WaitingForInput);
+        assert_eq!(determine_status(&entries, &clock(), &root()), SessionStatus::WaitingForInput);
     }
 
     #[test]
@@ -799,7 +1110,28 @@ mod tests {
                 },
             }
         ];
-        assert_eq!(determine_status(&entries), SessionStatus::Working);
+        assert_eq!(determine_status(&entries, &clock(), &root()), SessionStatus::Working);
+    }
+
+    #[test]
+    fn test_interruption_ends_the_turn() {
+        // A recent prompt means Claude is working on it...
+        let user = |content: &str| SessionEntry::User {
+            base: create_base(),
+            message: UserMessage {
+                role: "user".to_string(),
+                content: content.to_string(),
+                is_tool_result: false,
+                tool_results: vec![],
+            },
+        };
+        assert_eq!(determine_status(&[user("Fix it")], &clock(), &root()), SessionStatus::Working);
+
+        // ...but the interruption marker means it stopped
+        let entries = vec![user("Fix it"), user("[Request interrupted by user]")];
+        assert_eq!(determine_status(&entries, &clock(), &root()), SessionStatus::WaitingForInput);
+        let entries = vec![user("[Request interrupted by user for tool use]")];
+        assert_eq!(determine_status(&entries, &clock(), &root()), SessionStatus::WaitingForInput);
     }
 
     #[test]
@@ -812,10 +1144,11 @@ mod tests {
                     role: "user".to_string(),
                     content: "Hello".to_string(),
                     is_tool_result: false,
+                    tool_results: vec![],
                 },
             }
         ];
-        assert_eq!(determine_status(&entries), SessionStatus::WaitingForInput);
+        assert_eq!(determine_status(&entries, &clock(), &root()), SessionStatus::WaitingForInput);
     }
 
     #[test]
@@ -841,7 +1174,7 @@ mod tests {
                 },
             }
         ];
-        assert_eq!(get_pending_tool_name(&entries), Some("Bash".to_string()));
+        assert_eq!(get_pending_tool_name(&entries, &root()), Some("Bash".to_string()));
     }
 
     #[test]
@@ -867,7 +1200,7 @@ mod tests {
                 },
             }
         ];
-        assert_eq!(get_pending_tool_name(&entries), None);
+        assert_eq!(get_pending_tool_name(&entries, &root()), None);
     }
 
     #[test]
@@ -891,7 +1224,7 @@ mod tests {
                 },
             }
         ];
-        assert_eq!(get_pending_tool_name(&entries), None);
+        assert_eq!(get_pending_tool_name(&entries, &root()), None);
     }
 
     #[test]
@@ -922,7 +1255,7 @@ mod tests {
                 },
             }
         ];
-        assert_eq!(get_pending_tool_name(&entries), None);
+        assert_eq!(get_pending_tool_name(&entries, &root()), None);
     }
 
     #[test]
@@ -954,7 +1287,7 @@ mod tests {
                 },
             }
         ];
-        assert_eq!(get_pending_tool_name(&entries), Some("Bash".to_string()));
+        assert_eq!(get_pending_tool_name(&entries, &root()), Some("Bash".to_string()));
     }
 
     #[test]
@@ -967,17 +1300,18 @@ mod tests {
                     role: "user".to_string(),
                     content: "Hello".to_string(),
                     is_tool_result: false,
+                    tool_results: vec![],
                 },
             }
         ];
-        assert_eq!(get_pending_tool_name(&entries), None);
+        assert_eq!(get_pending_tool_name(&entries, &root()), None);
     }
 
     #[test]
     fn test_get_pending_tool_name_empty_entries() {
         // Empty entries
         let entries: Vec<SessionEntry> = vec![];
-        assert_eq!(get_pending_tool_name(&entries), None);
+        assert_eq!(get_pending_tool_name(&entries, &root()), None);
     }
 
     #[test]
@@ -1013,6 +1347,30 @@ mod tests {
                 },
             }
         ];
-        assert_eq!(get_pending_tool_name(&entries), Some("Bash".to_string()));
+        assert_eq!(get_pending_tool_name(&entries, &root()), Some("Bash".to_string()));
+    }
+
+    #[test]
+    fn test_permission_checker_follows_each_roots_settings() {
+        let base = std::env::temp_dir().join(format!("c9watch-perm-roots-{}", std::process::id()));
+        let (work, personal) = (base.join("work"), base.join("personal"));
+        std::fs::create_dir_all(&work).unwrap();
+        std::fs::create_dir_all(&personal).unwrap();
+        let settings = work.join("settings.json");
+        std::fs::write(&settings, r#"{"permissions":{"allow":["Bash(npm test:*)"]}}"#).unwrap();
+
+        let npm_test = serde_json::json!({"command": "npm test"});
+        assert!(permission_checker(&work).is_auto_approved("Bash", &npm_test));
+        // Another profile's rules don't apply
+        assert!(!permission_checker(&personal).is_auto_approved("Bash", &npm_test));
+
+        // Picked up again once the file changes
+        std::fs::write(&settings, r#"{"permissions":{"allow":[]}}"#).unwrap();
+        let file = std::fs::File::options().write(true).open(&settings).unwrap();
+        file.set_modified(SystemTime::now() + std::time::Duration::from_secs(60))
+            .unwrap();
+        assert!(!permission_checker(&work).is_auto_approved("Bash", &npm_test));
+
+        let _ = std::fs::remove_dir_all(&base);
     }
 }
@@ -0,0 +1,107 @@
+use rusqlite::Connection;
+
+use super::StoreError;
+
+/// Ordered schema migrations
+///
+/// Each entry upgrades the schema by one version. The database tracks the
+/// applied version in `PRAGMA user_version`, so migrations only ever run once
+/// and must never be edited after release - add a new entry instead.
+const MIGRATIONS: &[&str] = &[
+    // 1: initial schema
+    r#"
+    CREATE TABLE meta (
+        key TEXT PRIMARY KEY,
+        value TEXT NOT NULL
+    );
+
+    CREATE TABLE sessions (
+        id TEXT PRIMARY KEY,
+        pid INTEGER,
+        project_path TEXT NOT NULL,
+        git_branch TEXT,
+        first_prompt TEXT NOT NULL,
+        status TEXT NOT NULL,
+        first_seen TEXT NOT NULL,
+        last_seen TEXT NOT NULL
+    );
+
+    CREATE TABLE session_meta (
+        session_id TEXT PRIMARY KEY,
+        custom_name TEXT,
+        custom_title TEXT
+    );
+
+    CREATE TABLE session_tags (
+        session_id TEXT NOT NULL,
+        tag TEXT NOT NULL,
+        PRIMARY KEY (session_id, tag)
+    );
+
+    CREATE TABLE status_transitions (
+        id INTEGER PRIMARY KEY AUTOINCREMENT,
+        session_id TEXT NOT NULL,
+        from_status TEXT,
+        to_status TEXT NOT NULL,
+        at TEXT NOT NULL
+    );
+    CREATE INDEX idx_status_transitions_session
+        ON status_transitions (session_id, at);
+
+    CREATE TABLE token_usage (
+        session_id TEXT NOT NULL,
+        message_id TEXT NOT NULL,
+        model TEXT NOT NULL,
+        input_tokens INTEGER NOT NULL,
+        output_tokens INTEGER NOT NULL,
+        cache_creation_input_tokens INTEGER NOT NULL,
+        cache_read_input_tokens INTEGER NOT NULL,
+        recorded_at TEXT NOT NULL,
+        PRIMARY KEY (session_id, message_id)
+    );
+
+    CREATE TABLE notifications (
+        id INTEGER PRIMARY KEY AUTOINCREMENT,
+        session_id TEXT NOT NULL,
+        status TEXT NOT NULL,
+        title TEXT NOT NULL,
+        body TEXT NOT NULL,
+        fired_at TEXT NOT NULL
+    );
+    CREATE INDEX idx_notifications_fired_at ON notifications (fired_at);
+    "#,
+    // 2: notes, pinning and priority
+    r#"
+    ALTER TABLE session_meta ADD COLUMN note TEXT;
+    ALTER TABLE session_meta ADD COLUMN pinned INTEGER NOT NULL DEFAULT 0;
+    ALTER TABLE session_meta ADD COLUMN priority INTEGER NOT NULL DEFAULT 0;
+    "#,
+];
+
+/// Latest schema version known to this build
+pub const LATEST_VERSION: u32 = MIGRATIONS.len() as u32;
+
+/// Bring the database schema up to date
+///
+/// Each pending migration runs in its own transaction together with the
+/// `user_version` bump, so a failure leaves the schema at the last good version.
+pub fn run(conn: &mut Connection) -> Result<(), StoreError> {
+    let current: u32 = conn.query_row("PRAGMA user_version", [], |row| row.get(0))?;
+
+    if current > LATEST_VERSION {
+        return Err(StoreError::SchemaTooNew {
+            found: current,
+            supported: LATEST_VERSION,
+        });
+    }
+
+    for (idx, sql) in MIGRATIONS.iter().enumerate().skip(current as usize) {
+        let version = idx as u32 + 1;
+        let tx = conn.transaction()?;
+        tx.execute_batch(sql)?;
+        tx.pragma_update(None, "user_version", version)?;
+        tx.commit()?;
+    }
+
+    Ok(())
+}
@@ -0,0 +1,785 @@
+mod migrations;
+
+use crate::session::parser::Usage;
+use crate::session::{
+    backup_legacy_files, claude_dirs, ClaudeDirs, CustomNames, CustomTitles, SessionStatus,
+};
+use chrono::Utc;
+use rusqlite::{params, Connection, OptionalExtension};
+use serde::Serialize;
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+use std::sync::{Mutex, MutexGuard, OnceLock};
+use thiserror::Error;
+
+pub use migrations::LATEST_VERSION;
+
+/// Meta key set once the legacy JSON name/title files have been imported
+const LEGACY_IMPORT_KEY: &str = "legacy_json_imported";
+
+#[derive(Error, Debug)]
+pub enum StoreError {
+    #[error("Database error: {0}")]
+    Sqlite(#[from] rusqlite::Error),
+
+    #[error("Failed to create data directory: {0}")]
+    Io(#[from] std::io::Error),
+
+    #[error("Failed to get data directory")]
+    DataDirectoryNotFound,
+
+    #[error("Database schema version {found} is newer than this build supports ({supported})")]
+    SchemaTooNew { found: u32, supported: u32 },
+}
+
+/// Snapshot of a session as last seen by the polling loop
+#[derive(Debug, Clone)]
+pub struct SessionRecord<'a> {
+    pub id: &'a str,
+    pub pid: u32,
+    pub project_path: &'a str,
+    pub git_branch: Option<&'a str>,
+    pub first_prompt: &'a str,
+    pub status: &'a SessionStatus,
+}
+
+/// User-assigned metadata attached to a session
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct SessionMeta {
+    pub custom_name: Option<String>,
+    pub custom_title: Option<String>,
+    pub tags: Vec<String>,
+    pub note: Option<String>,
+    pub pinned: bool,
+    pub priority: i32,
+}
+
+/// A recorded change of session status
+#[derive(Debug, Clone, Serialize, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct StatusTransition {
+    pub session_id: String,
+    /// Previous status, or None when the session was first observed
+    pub from_status: Option<SessionStatus>,
+    pub to_status: SessionStatus,
+    pub at: String,
+}
+
+/// Token usage summed over all recorded assistant messages of a session
+#[derive(Debug, Clone, Default, Serialize, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct UsageTotals {
+    pub message_count: u64,
+    pub input_tokens: u64,
+    pub output_tokens: u64,
+    pub cache_creation_input_tokens: u64,
+    pub cache_read_input_tokens: u64,
+}
+
+/// A notification previously shown to the user
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct NotificationRecord {
+    pub id: i64,
+    pub session_id: String,
+    pub status: SessionStatus,
+    pub title: String,
+    pub body: String,
+    pub fired_at: String,
+}
+
+/// Embedded SQLite database holding everything c9watch remembers about sessions
+///
+/// The connection is guarded by a mutex so the store can be shared between the
+/// polling thread and Tauri command handlers.
+pub struct Store {
+    conn: Mutex<Connection>,
+}
+
+static STORE: OnceLock<Result<Store, String>> = OnceLock::new();
+
+/// Get the process-wide store, opening it (and importing legacy files) on first use
+pub fn global() -> Result<&'static Store, String> {
+    STORE
+        .get_or_init(|| {
+            let path = Store::default_path().map_err(|e| e.to_string())?;
+            let store = Store::open(&path).map_err(|e| e.to_string())?;
+            store.import_legacy_files(claude_dirs());
+            Ok(store)
+        })
+        .as_ref()
+        .map_err(|e| e.clone())
+}
+
+impl Store {
+    /// Location of the database in the platform's app data directory
+    pub fn default_path() -> Result<PathBuf, StoreError> {
+        let data_dir = dirs::data_dir().ok_or(StoreError::DataDirectoryNotFound)?;
+        Ok(data_dir.join("com.minchenlee.c9watch").join("c9watch.db"))
+    }
+
+    /// Open (or create) the database at `path` and apply pending migrations
+    pub fn open(path: &Path) -> Result<Self, StoreError> {
+        if let Some(parent) = path.parent() {
+            std::fs::create_dir_all(parent)?;
+        }
+
+        let conn = Connection::open(path)?;
+        conn.pragma_update(None, "journal_mode", "WAL")?;
+        conn.busy_timeout(std::time::Duration::from_secs(5))?;
+        Self::from_connection(conn)
+    }
+
+    /// Open a throwaway in-memory database (used by tests)
+    pub fn open_in_memory() -> Result<Self, StoreError> {
+        Self::from_connection(Connection::open_in_memory()?)
+    }
+
+    fn from_connection(mut conn: Connection) -> Result<Self, StoreError> {
+        migrations::run(&mut conn)?;
+        Ok(Self {
+            conn: Mutex::new(conn),
+        })
+    }
+
+    fn conn(&self) -> MutexGuard<'_, Connection> {
+        // A panic while holding the lock can't leave SQLite in a bad state,
+        // so recover the guard rather than propagating the poison
+        self.conn.lock().unwrap_or_else(|poisoned| poisoned.into_inner())
+    }
+
+    /// Current schema version of the open database
+    pub fn schema_version(&self) -> Result<u32, StoreError> {
+        Ok(self
+            .conn()
+            .query_row("PRAGMA user_version", [], |row| row.get(0))?)
+    }
+
+    /// Import the legacy names and titles files found in `dirs`
+    ///
+    /// An unreadable legacy file is reported and skipped without marking
+    /// the import as done, so it is retried once the file is fixed. The files
+    /// are copied to `.bak` first and left in place.
+    pub fn import_legacy_files(&self, dirs: &ClaudeDirs) {
+        match self.legacy_imported() {
+            Ok(false) => {}
+            Ok(true) => return,
+            Err(e) => {
+                eprintln!("[store] Failed to check the legacy import: {}", e);
+                return;
+            }
+        }
+
+        match (CustomNames::load(dirs), CustomTitles::load(dirs)) {
+            (Ok(names), Ok(titles)) => {
+                if let Err(e) = backup_legacy_files(dirs) {
+                    eprintln!("[store] Skipping legacy import: {}", e);
+                    return;
+                }
+                if let Err(e) = self.import_legacy(&names, &titles) {
+                    eprintln!("[store] Failed to import legacy names/titles: {}", e);
+                }
+            }
+            (Err(e), _) | (_, Err(e)) => {
+                eprintln!("[store] Skipping legacy import: {}", e);
+            }
+        }
+    }
+
+    fn legacy_imported(&self) -> Result<bool, StoreError> {
+        Ok(self
+            .conn()
+            .query_row(
+                "SELECT 1 FROM meta WHERE key = ?1",
+                params![LEGACY_IMPORT_KEY],
+                |_| Ok(()),
+            )
+            .optional()?
+            .is_some())
+    }
+
+    /// One-time import of `session-monitor-names.json` / `session-monitor-titles.json`
+    ///
+    /// Values already present in the database win over the legacy files.
+    /// Returns false if the import had already been done.
+    pub fn import_legacy(
+        &self,
+        names: &CustomNames,
+        titles: &CustomTitles,
+    ) -> Result<bool, StoreError> {
+        let mut conn = self.conn();
+        let tx = conn.transaction()?;
+
+        let already_imported: Option<String> = tx
+            .query_row(
+                "SELECT value FROM meta WHERE key = ?1",
+                params![LEGACY_IMPORT_KEY],
+                |row| row.get(0),
+            )
+            .optional()?;
+        if already_imported.is_some() {
+            return Ok(false);
+        }
+
+        for (session_id, name) in &names.names {
+            tx.execute(
+                "INSERT INTO session_meta (session_id, custom_name) VALUES (?1, ?2)
+                 ON CONFLICT(session_id) DO UPDATE
+                 SET custom_name = COALESCE(custom_name, excluded.custom_name)",
+                params![session_id, name],
+            )?;
+        }
+        for (session_id, title) in &titles.titles {
+            tx.execute(
+                "INSERT INTO session_meta (session_id, custom_title) VALUES (?1, ?2)
+                 ON CONFLICT(session_id) DO UPDATE
+                 SET custom_title = COALESCE(custom_title, excluded.custom_title)",
+                params![session_id, title],
+            )?;
+        }
+
+        tx.execute(
+            "INSERT INTO meta (key, value) VALUES (?1, ?2)",
+            params![LEGACY_IMPORT_KEY, Utc::now().to_rfc3339()],
+        )?;
+        tx.commit()?;
+        Ok(true)
+    }
+
+    /// Insert or refresh a session, recording a status transition if it changed
+    ///
+    /// Returns the transition that was recorded, if any.
+    pub fn upsert_session(
+        &self,
+        session: &SessionRecord,
+    ) -> Result<Option<StatusTransition>, StoreError> {
+        let now = Utc::now().to_rfc3339();
+        let status = status_to_sql(session.status);
+
+        let mut conn = self.conn();
+        let tx = conn.transaction()?;
+
+        let previous: Option<String> = tx
+            .query_row(
+                "SELECT status FROM sessions WHERE id = ?1",
+                params![session.id],
+                |row| row.get(0),
+            )
+            .optional()?;
+
+        tx.execute(
+            "INSERT INTO sessions
+                (id, pid, project_path, git_branch, first_prompt, status, first_seen, last_seen)
+             VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?7)
+             ON CONFLICT(id) DO UPDATE SET
+                pid = excluded.pid,
+                project_path = excluded.project_path,
+                git_branch = excluded.git_branch,
+                first_prompt = excluded.first_prompt,
+                status = excluded.status,
+                last_seen = excluded.last_seen",
+            params![
+                session.id,
+                session.pid,
+                session.project_path,
+                session.git_branch,
+                session.first_prompt,
+                status,
+                now,
+            ],
+        )?;
+
+        let transition = if previous.as_deref() == Some(status.as_str()) {
+            None
+        } else {
+            tx.execute(
+                "INSERT INTO status_transitions (session_id, from_status, to_status, at)
+                 VALUES (?1, ?2, ?3, ?4)",
+                params![session.id, previous, status, now],
+            )?;
+            Some(StatusTransition {
+                session_id: session.id.to_string(),
+                from_status: previous.as_deref().and_then(status_from_sql),
+                to_status: session.status.clone(),
+                at: now,
+            })
+        };
+
+        tx.commit()?;
+        Ok(transition)
+    }
+
+    /// All recorded status transitions for a session, oldest first
+    pub fn status_history(&self, session_id: &str) -> Result<Vec<StatusTransition>, StoreError> {
+        let conn = self.conn();
+        let mut stmt = conn.prepare(
+            "SELECT from_status, to_status, at FROM status_transitions
+             WHERE session_id = ?1 ORDER BY id",
+        )?;
+
+        let rows = stmt.query_map(params![session_id], |row| {
+            let from: Option<String> = row.get(0)?;
+            let to: String = row.get(1)?;
+            let at: String = row.get(2)?;
+            Ok((from, to, at))
+        })?;
+
+        let mut history = Vec::new();
+        for row in rows {
+            let (from, to, at) = row?;
+            // Skip rows written by a build with statuses this one doesn't know
+            if let Some(to_status) = status_from_sql(&to) {
+                history.push(StatusTransition {
+                    session_id: session_id.to_string(),
+                    from_status: from.as_deref().and_then(status_from_sql),
+                    to_status,
+                    at,
+                });
+            }
+        }
+        Ok(history)
+    }
+
+    /// When each session entered its current status, keyed by session ID
+    pub fn status_since(&self) -> Result<HashMap<String, String>, StoreError> {
+        let conn = self.conn();
+        let mut stmt = conn.prepare(
+            "SELECT session_id, at FROM status_transitions
+             WHERE id IN (SELECT MAX(id) FROM status_transitions GROUP BY session_id)",
+        )?;
+        let rows = stmt.query_map([], |row| Ok((row.get(0)?, row.get(1)?)))?;
+        Ok(rows.collect::<Result<_, _>>()?)
+    }
+
+    /// Custom display names keyed by session ID
+    pub fn custom_names(&self) -> Result<HashMap<String, String>, StoreError> {
+        self.meta_column("custom_name")
+    }
+
+    /// Custom titles keyed by session ID
+    pub fn custom_titles(&self) -> Result<HashMap<String, String>, StoreError> {
+        self.meta_column("custom_title")
+    }
+
+    fn meta_column(&self, column: &str) -> Result<HashMap<String, String>, StoreError> {
+        let conn = self.conn();
+        let mut stmt = conn.prepare(&format!(
+            "SELECT session_id, {0} FROM session_meta WHERE {0} IS NOT NULL",
+            column
+        ))?;
+        let rows = stmt.query_map([], |row| Ok((row.get(0)?, row.get(1)?)))?;
+        Ok(rows.collect::<Result<_, _>>()?)
+    }
+
+    pub fn set_custom_name(&self, session_id: &str, name: &str) -> Result<(), StoreError> {
+        self.conn().execute(
+            "INSERT INTO session_meta (session_id, custom_name) VALUES (?1, ?2)
+             ON CONFLICT(session_id) DO UPDATE SET custom_name = excluded.custom_name",
+            params![session_id, name],
+        )?;
+        Ok(())
+    }
+
+    pub fn set_custom_title(&self, session_id: &str, title: &str) -> Result<(), StoreError> {
+        self.conn().execute(
+            "INSERT INTO session_meta (session_id, custom_title) VALUES (?1, ?2)
+             ON CONFLICT(session_id) DO UPDATE SET custom_title = excluded.custom_title",
+            params![session_id, title],
+        )?;
+        Ok(())
+    }
+
+    /// Metadata of every session that has any, keyed by session ID
+    pub fn all_session_meta(&self) -> Result<HashMap<String, SessionMeta>, StoreError> {
+        let conn = self.conn();
+        let mut all: HashMap<String, SessionMeta> = HashMap::new();
+
+        let mut stmt = conn.prepare(
+            "SELECT session_id, custom_name, custom_title, note, pinned, priority
+             FROM session_meta",
+        )?;
+        let rows = stmt.query_map([], |row| {
+            Ok((
+                row.get::<_, String>(0)?,
+                SessionMeta {
+                    custom_name: row.get(1)?,
+                    custom_title: row.get(2)?,
+                    tags: Vec::new(),
+                    note: row.get(3)?,
+                    pinned: row.get(4)?,
+                    priority: row.get(5)?,
+                },
+            ))
+        })?;
+        for row in rows {
+            let (session_id, meta) = row?;
+            all.insert(session_id, meta);
+        }
+
+        let mut stmt = conn.prepare("SELECT session_id, tag FROM session_tags ORDER BY tag")?;
+        let rows = stmt.query_map([], |row| {
+            Ok((row.get::<_, String>(0)?, row.get::<_, String>(1)?))
+        })?;
+        for row in rows {
+            let (session_id, tag) = row?;
+            all.entry(session_id).or_default().tags.push(tag);
+        }
+
+        Ok(all)
+    }
+
+    /// Set or clear the free-form note of a session
+    pub fn set_note(&self, session_id: &str, note: Option<&str>) -> Result<(), StoreError> {
+        self.conn().execute(
+            "INSERT INTO session_meta (session_id, note) VALUES (?1, ?2)
+             ON CONFLICT(session_id) DO UPDATE SET note = excluded.note",
+            params![session_id, note],
+        )?;
+        Ok(())
+    }
+
+    pub fn set_pinned(&self, session_id: &str, pinned: bool) -> Result<(), StoreError> {
+        self.conn().execute(
+            "INSERT INTO session_meta (session_id, pinned) VALUES (?1, ?2)
+             ON CONFLICT(session_id) DO UPDATE SET pinned = excluded.pinned",
+            params![session_id, pinned],
+        )?;
+        Ok(())
+    }
+
+    pub fn set_priority(&self, session_id: &str, priority: i32) -> Result<(), StoreError> {
+        self.conn().execute(
+            "INSERT INTO session_meta (session_id, priority) VALUES (?1, ?2)
+             ON CONFLICT(session_id) DO UPDATE SET priority = excluded.priority",
+            params![session_id, priority],
+        )?;
+        Ok(())
+    }
+
+    /// Tags attached to a session, sorted alphabetically
+    pub fn tags(&self, session_id: &str) -> Result<Vec<String>, StoreError> {
+        let conn = self.conn();
+        let mut stmt =
+            conn.prepare("SELECT tag FROM session_tags WHERE session_id = ?1 ORDER BY tag")?;
+        let rows = stmt.query_map(params![session_id], |row| row.get(0))?;
+        Ok(rows.collect::<Result<_, _>>()?)
+    }
+
+    /// Replace the full tag set of a session
+    pub fn set_tags(&self, session_id: &str, tags: &[String]) -> Result<(), StoreError> {
+        let mut conn = self.conn();
+        let tx = conn.transaction()?;
+        tx.execute(
+            "DELETE FROM session_tags WHERE session_id = ?1",
+            params![session_id],
+        )?;
+        for tag in tags {
+            tx.execute(
+                "INSERT OR IGNORE INTO session_tags (session_id, tag) VALUES (?1, ?2)",
+                params![session_id, tag],
+            )?;
+        }
+        tx.commit()?;
+        Ok(())
+    }
+
+    /// Record token usage for an assistant message
+    ///
+    /// Messages are keyed by their API message ID, so re-reading the same
+    /// transcript tail on every poll doesn't double count.
+    pub fn record_usage(
+        &self,
+        session_id: &str,
+        message_id: &str,
+        model: &str,
+        usage: &Usage,
+    ) -> Result<(), StoreError> {
+        self.conn().execute(
+            "INSERT OR IGNORE INTO token_usage
+                (session_id, message_id, model, input_tokens, output_tokens,
+                 cache_creation_input_tokens, cache_read_input_tokens, recorded_at)
+             VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8)",
+            params![
+                session_id,
+                message_id,
+                model,
+                usage.input_tokens.unwrap_or(0),
+                usage.output_tokens.unwrap_or(0),
+                usage.cache_creation_input_tokens.unwrap_or(0),
+                usage.cache_read_input_tokens.unwrap_or(0),
+                Utc::now().to_rfc3339(),
+            ],
+        )?;
+        Ok(())
+    }
+
+    /// Total token usage recorded for a session
+    pub fn usage_totals(&self, session_id: &str) -> Result<UsageTotals, StoreError> {
+        Ok(self.conn().query_row(
+            "SELECT COUNT(*),
+                    COALESCE(SUM(input_tokens), 0),
+                    COALESCE(SUM(output_tokens), 0),
+                    COALESCE(SUM(cache_creation_input_tokens), 0),
+                    COALESCE(SUM(cache_read_input_tokens), 0)
+             FROM token_usage WHERE session_id = ?1",
+            params![session_id],
+            |row| {
+                Ok(UsageTotals {
+                    message_count: row.get(0)?,
+                    input_tokens: row.get(1)?,
+                    output_tokens: row.get(2)?,
+                    cache_creation_input_tokens: row.get(3)?,
+                    cache_read_input_tokens: row.get(4)?,
+                })
+            },
+        )?)
+    }
+
+    /// Record a notification that was shown to the user
+    pub fn record_notification(
+        &self,
+        session_id: &str,
+        status: &SessionStatus,
+        title: &str,
+        body: &str,
+    ) -> Result<(), StoreError> {
+        self.conn().execute(
+            "INSERT INTO notifications (session_id, status, title, body, fired_at)
+             VALUES (?1, ?2, ?3, ?4, ?5)",
+            params![
+                session_id,
+                status_to_sql(status),
+                title,
+                body,
+                Utc::now().to_rfc3339()
+            ],
+        )?;
+        Ok(())
+    }
+
+    /// Most recent notifications, newest first
+    pub fn notification_history(&self, limit: u32) -> Result<Vec<NotificationRecord>, StoreError> {
+        let conn = self.conn();
+        let mut stmt = conn.prepare(
+            "SELECT id, session_id, status, title, body, fired_at FROM notifications
+             ORDER BY id DESC LIMIT ?1",
+        )?;
+
+        let rows = stmt.query_map(params![limit], |row| {
+            Ok((
+                row.get::<_, i64>(0)?,
+                row.get::<_, String>(1)?,
+                row.get::<_, String>(2)?,
+                row.get::<_, String>(3)?,
+                row.get::<_, String>(4)?,
+                row.get::<_, String>(5)?,
+            ))
+        })?;
+
+        let mut records = Vec::new();
+        for row in rows {
+            let (id, session_id, status, title, body, fired_at) = row?;
+            if let Some(status) = status_from_sql(&status) {
+                records.push(NotificationRecord {
+                    id,
+                    session_id,
+                    status,
+                    title,
+                    body,
+                    fired_at,
+                });
+            }
+        }
+        Ok(records)
+    }
+}
+
+/// Statuses are stored by their serialized (PascalCase) name
+fn status_to_sql(status: &SessionStatus) -> String {
+    serde_json::to_value(status)
+        .ok()
+        .and_then(|v| v.as_str().map(str::to_string))
+        .unwrap_or_default()
+}
+
+fn status_from_sql(value: &str) -> Option<SessionStatus> {
+    serde_json::from_value(serde_json::Value::String(value.to_string())).ok()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn record<'a>(id: &'a str, status: &'a SessionStatus) -> SessionRecord<'a> {
+        SessionRecord {
+            id,
+            pid: 42,
+            project_path: "/tmp/project",
+            git_branch: Some("main"),
+            first_prompt: "Fix the bug",
+            status,
+        }
+    }
+
+    #[test]
+    fn test_migrations_reach_latest_version() {
+        let store = Store::open_in_memory().unwrap();
+        assert_eq!(store.schema_version().unwrap(), LATEST_VERSION);
+    }
+
+    #[test]
+    fn test_reopen_does_not_rerun_migrations() {
+        let dir = std::env::temp_dir().join(format!("c9watch-store-test-{}", std::process::id()));
+        let path = dir.join("c9watch.db");
+
+        {
+            let store = Store::open(&path).unwrap();
+            store.set_custom_name("s1", "api").unwrap();
+        }
+        let store = Store::open(&path).unwrap();
+        assert_eq!(store.schema_version().unwrap(), LATEST_VERSION);
+        assert_eq!(store.custom_names().unwrap().get("s1"), Some(&"api".to_string()));
+
+        drop(store);
+        let _ = std::fs::remove_dir_all(&dir);
+    }
+
+    #[test]
+    fn test_status_transitions_only_recorded_on_change() {
+        let store = Store::open_in_memory().unwrap();
+
+        let first = store
+            .upsert_session(&record("s1", &SessionStatus::Working))
+            .unwrap();
+        assert_eq!(first.map(|t| t.from_status), Some(None));
+
+        let unchanged = store
+            .upsert_session(&record("s1", &SessionStatus::Working))
+            .unwrap();
+        assert!(unchanged.is_none());
+
+        store
+            .upsert_session(&record("s1", &SessionStatus::NeedsPermission))
+            .unwrap();
+
+        let history = store.status_history("s1").unwrap();
+        assert_eq!(history.len(), 2);
+        assert_eq!(history[1].from_status, Some(SessionStatus::Working));
+        assert_eq!(history[1].to_status, SessionStatus::NeedsPermission);
+        assert_eq!(store.status_since().unwrap().get("s1"), Some(&history[1].at));
+    }
+
+    #[test]
+    fn test_legacy_import_runs_once_and_keeps_existing_values() {
+        let store = Store::open_in_memory().unwrap();
+        store.set_custom_title("s1", "Renamed in app").unwrap();
+
+        let mut names = CustomNames::default();
+        names.set("s1".to_string(), "backend".to_string());
+        let mut titles = CustomTitles::default();
+        titles.set("s1".to_string(), "Old title".to_string());
+        titles.set("s2".to_string(), "Second".to_string());
+
+        assert!(store.import_legacy(&names, &titles).unwrap());
+        assert!(!store.import_legacy(&names, &titles).unwrap());
+
+        let stored_titles = store.custom_titles().unwrap();
+        assert_eq!(stored_titles.get("s1"), Some(&"Renamed in app".to_string()));
+        assert_eq!(stored_titles.get("s2"), Some(&"Second".to_string()));
+        assert_eq!(
+            store.custom_names().unwrap().get("s1"),
+            Some(&"backend".to_string())
+        );
+    }
+
+    #[test]
+    fn test_legacy_files_are_read_from_the_given_root() {
+        let root = std::env::temp_dir().join(format!("c9watch-legacy-test-{}", std::process::id()));
+        std::fs::create_dir_all(&root).unwrap();
+        std::fs::write(
+            root.join("session-monitor-names.json"),
+            r#"{"names":{"s1":"backend"}}"#,
+        )
+        .unwrap();
+
+        let store = Store::open_in_memory().unwrap();
+        store.import_legacy_files(&ClaudeDirs::new([root.clone()]));
+        assert_eq!(
+            store.custom_names().unwrap().get("s1"),
+            Some(&"backend".to_string())
+        );
+        assert!(root.join("session-monitor-names.json.bak").is_file());
+        assert!(!root.join("session-monitor-titles.json.bak").exists());
+
+        // A corrupt file leaves the import to be retried
+        let store = Store::open_in_memory().unwrap();
+        std::fs::write(root.join("session-monitor-titles.json"), "{").unwrap();
+        store.import_legacy_files(&ClaudeDirs::new([root.clone()]));
+        assert!(store.custom_names().unwrap().is_empty());
+        assert!(store.import_legacy(&CustomNames::default(), &CustomTitles::default()).unwrap());
+
+        let _ = std::fs::remove_dir_all(&root);
+    }
+
+    #[test]
+    fn test_usage_is_not_double_counted() {
+        let store = Store::open_in_memory().unwrap();
+        let usage = Usage {
+            input_tokens: Some(10),
+            output_tokens: Some(5),
+            cache_creation_input_tokens: None,
+            cache_read_input_tokens: Some(100),
+        };
+
+        store.record_usage("s1", "msg_1", "claude-opus", &usage).unwrap();
+        store.record_usage("s1", "msg_1", "claude-opus", &usage).unwrap();
+        store.record_usage("s1", "msg_2", "claude-opus", &usage).unwrap();
+
+        let totals = store.usage_totals("s1").unwrap();
+        assert_eq!(totals.message_count, 2);
+        assert_eq!(totals.input_tokens, 20);
+        assert_eq!(totals.cache_read_input_tokens, 200);
+    }
+
+    #[test]
+    fn test_all_session_meta_combines_columns_and_tags() {
+        let store = Store::open_in_memory().unwrap();
+        store.set_custom_title("s1", "Release prep").unwrap();
+        store.set_note("s1", Some("waiting on CI")).unwrap();
+        store.set_pinned("s1", true).unwrap();
+        store.set_priority("s1", 2).unwrap();
+        store.set_tags("s1", &["release-blocker".to_string()]).unwrap();
+        store.set_tags("s2", &["experiment".to_string()]).unwrap();
+
+        let all = store.all_session_meta().unwrap();
+        let s1 = &all["s1"];
+        assert_eq!(s1.custom_title.as_deref(), Some("Release prep"));
+        assert_eq!(s1.note.as_deref(), Some("waiting on CI"));
+        assert!(s1.pinned);
+        assert_eq!(s1.priority, 2);
+        assert_eq!(s1.tags, vec!["release-blocker"]);
+
+        // Tags alone are enough for a session to show up
+        assert_eq!(all["s2"].tags, vec!["experiment"]);
+        assert!(!all["s2"].pinned);
+
+        store.set_note("s1", None).unwrap();
+        assert_eq!(store.all_session_meta().unwrap()["s1"].note, None);
+    }
+
+    #[test]
+    fn test_tags_and_notifications() {
+        let store = Store::open_in_memory().unwrap();
+        store
+            .set_tags("s1", &["experiment".to_string(), "blocker".to_string()])
+            .unwrap();
+        assert_eq!(store.tags("s1").unwrap(), vec!["blocker", "experiment"]);
+
+        store
+            .record_notification("s1", &SessionStatus::WaitingForInput, "t", "b")
+            .unwrap();
+        let history = store.notification_history(10).unwrap();
+        assert_eq!(history.len(), 1);
+        assert_eq!(history[0].status, SessionStatus::WaitingForInput);
+    }
+}
@@ -0,0 +1,256 @@
+//! Hermetic stand-ins for a running Claude Code, for end-to-end tests
+//!
+//! [`SimulatedClaudeHome`] is a throwaway `~/.claude` with its own `projects/`
+//! directory, and [`FakeClaude`] is a dummy process named `claude` running in a
+//! chosen cwd that appends a synthetic transcript to that directory, so tests
+//! can drive `detect_and_enrich_sessions_with` through a whole session.
+
+use crate::session::{encode_project_dir, ClaudeDirs};
+use chrono::Utc;
+use serde_json::{json, Value};
+use std::fs::{self, OpenOptions};
+use std::io::{self, Write};
+use std::path::{Path, PathBuf};
+use std::process::{Child, Command, Stdio};
+use std::sync::{Mutex, MutexGuard};
+use std::thread;
+use std::time::{Duration, Instant};
+
+/// Serializes tests that run processes named `claude`
+///
+/// Detection sees every such process on the machine, so a test expecting an
+/// exact set of sessions must not overlap with one spawning others.
+pub fn claude_processes_lock() -> MutexGuard<'static, ()> {
+    static LOCK: Mutex<()> = Mutex::new(());
+    LOCK.lock().unwrap_or_else(|p| p.into_inner())
+}
+
+/// A temporary Claude data directory, removed on drop
+pub struct SimulatedClaudeHome {
+    root: PathBuf,
+}
+
+impl SimulatedClaudeHome {
+    pub fn new(name: &str) -> Self {
+        let root = std::env::temp_dir().join(format!("c9watch-sim-{}-{}", name, std::process::id()));
+        let _ = fs::remove_dir_all(&root);
+        fs::create_dir_all(root.join("claude").join("projects")).unwrap();
+        Self {
+            root: root.canonicalize().unwrap(),
+        }
+    }
+
+    /// Directories to hand to the detector: just this simulated root
+    pub fn dirs(&self) -> ClaudeDirs {
+        ClaudeDirs::new([self.root.join("claude")])
+    }
+
+    /// Create a project working directory for a fake session to run in
+    pub fn workspace(&self, name: &str) -> PathBuf {
+        let path = self.root.join("work").join(name);
+        fs::create_dir_all(&path).unwrap();
+        path
+    }
+
+    /// `projects/<encoded cwd>/` as Claude Code names it
+    fn project_dir(&self, cwd: &Path) -> PathBuf {
+        self.root.join("claude").join("projects").join(encode_project_dir(cwd))
+    }
+
+    /// Symlink named `claude` to `sleep`, so the process shows up as `claude`
+    fn fake_binary(&self) -> io::Result<PathBuf> {
+        let bin = self.root.join("bin").join("claude");
+        if bin.exists() {
+            return Ok(bin);
+        }
+
+        let sleep = ["/bin/sleep", "/usr/bin/sleep"]
+            .iter()
+            .map(Path::new)
+            .find(|path| path.exists())
+            .ok_or_else(|| io::Error::new(io::ErrorKind::NotFound, "no sleep binary"))?;
+        fs::create_dir_all(bin.parent().unwrap())?;
+        std::os::unix::fs::symlink(sleep, &bin)?;
+        Ok(bin)
+    }
+
+    /// Script named `claude` that accepts any arguments and keeps running,
+    /// for code that builds a real claude command line
+    pub fn fake_cli(&self) -> io::Result<PathBuf> {
+        use std::os::unix::fs::PermissionsExt;
+
+        let cli = self.root.join("cli").join("claude");
+        fs::create_dir_all(cli.parent().unwrap())?;
+        // A loop rather than a plain `sleep`, which the shell could exec into
+        fs::write(&cli, "#!/bin/sh\nwhile :; do sleep 1; done\n")?;
+        fs::set_permissions(&cli, fs::Permissions::from_mode(0o755))?;
+        Ok(cli)
+    }
+}
+
+impl Drop for SimulatedClaudeHome {
+    fn drop(&mut self) {
+        let _ = fs::remove_dir_all(&self.root);
+    }
+}
+
+/// A dummy `claude` process plus the transcript it "writes"
+///
+/// Entries are stamped with the current time, like the real CLI does.
+pub struct FakeClaude {
+    child: Option<Child>,
+    session_id: String,
+    transcript: PathBuf,
+    cwd: PathBuf,
+    last_uuid: Option<String>,
+    next_id: u32,
+}
+
+impl FakeClaude {
+    /// Start the process in `cwd`; the transcript appears with the first entry
+    pub fn spawn(home: &SimulatedClaudeHome, cwd: &Path, session_id: &str) -> io::Result<Self> {
+        use std::os::unix::process::CommandExt;
+
+        let child = Command::new(home.fake_binary()?)
+            .arg0("sleep")
+            .arg("3600")
+            .current_dir(cwd)
+            .stdin(Stdio::null())
+            .stdout(Stdio::null())
+            .stderr(Stdio::null())
+            .spawn()?;
+        wait_for_exec(child.id(), "claude")?;
+
+        let project_dir = home.project_dir(cwd);
+        fs::create_dir_all(&project_dir)?;
+
+        Ok(Self {
+            child: Some(child),
+            session_id: session_id.to_string(),
+            transcript: project_dir.join(format!("{}.jsonl", session_id)),
+            cwd: cwd.to_path_buf(),
+            last_uuid: None,
+            next_id: 0,
+        })
+    }
+
+    pub fn pid(&self) -> u32 {
+        self.child.as_ref().map(Child::id).unwrap_or_default()
+    }
+
+    pub fn session_id(&self) -> &str {
+        &self.session_id
+    }
+
+    /// The user submits a prompt
+    pub fn prompt(&mut self, text: &str) {
+        self.append("user", json!({ "role": "user", "content": text }));
+    }
+
+    /// Claude calls a tool; returns the tool use id
+    pub fn call_tool(&mut self, name: &str, input: Value) -> String {
+        let id = self.next_id("toolu");
+        let content = json!([{ "type": "tool_use", "id": id, "name": name, "input": input }]);
+        self.append_assistant(content);
+        id
+    }
+
+    /// A tool finishes and its result is sent back to Claude
+    pub fn tool_result(&mut self, tool_use_id: &str, output: &str) {
+        self.append(
+            "user",
+            json!({
+                "role": "user",
+                "content": [{ "type": "tool_result", "tool_use_id": tool_use_id, "content": output }]
+            }),
+        );
+    }
+
+    /// Claude answers with text
+    pub fn reply(&mut self, text: &str) {
+        self.append_assistant(json!([{ "type": "text", "text": text }]));
+    }
+
+    /// The process exits (Claude Code leaves its transcript behind)
+    pub fn exit(mut self) {
+        self.kill();
+    }
+
+    fn kill(&mut self) {
+        if let Some(mut child) = self.child.take() {
+            let _ = child.kill();
+            let _ = child.wait();
+        }
+    }
+
+    fn next_id(&mut self, prefix: &str) -> String {
+        self.next_id += 1;
+        format!("{}_{:04}", prefix, self.next_id)
+    }
+
+    fn append_assistant(&mut self, content: Value) {
+        let message_id = self.next_id("msg");
+        self.append(
+            "assistant",
+            json!({
+                "model": "claude-sonnet-4-5",
+                "id": message_id,
+                "role": "assistant",
+                "content": content,
+                "stop_reason": null,
+                "stop_sequence": null,
+                "usage": { "input_tokens": 10, "output_tokens": 20 }
+            }),
+        );
+    }
+
+    fn append(&mut self, kind: &str, message: Value) {
+        let uuid = self.next_id("uuid");
+        let entry = json!({
+            "type": kind,
+            "uuid": uuid,
+            "parentUuid": self.last_uuid,
+            "sessionId": self.session_id,
+            "cwd": self.cwd,
+            "version": "2.1.3",
+            "gitBranch": "main",
+            "isSidechain": false,
+            "timestamp": Utc::now().to_rfc3339(),
+            "message": message,
+        });
+        self.last_uuid = Some(uuid);
+
+        let mut file = OpenOptions::new()
+            .create(true)
+            .append(true)
+            .open(&self.transcript)
+            .unwrap();
+        writeln!(file, "{}", entry).unwrap();
+    }
+}
+
+impl Drop for FakeClaude {
+    fn drop(&mut self) {
+        self.kill();
+    }
+}
+
+/// Wait until `pid` has exec'd into a process named `name`
+///
+/// Right after `spawn` the child may still carry the test binary's name.
+fn wait_for_exec(pid: u32, name: &str) -> io::Result<()> {
+    let comm_path = format!("/proc/{}/comm", pid);
+    let deadline = Instant::now() + Duration::from_secs(5);
+
+    while Instant::now() < deadline {
+        if fs::read_to_string(&comm_path).is_ok_and(|comm| comm.trim() == name) {
+            return Ok(());
+        }
+        thread::sleep(Duration::from_millis(10));
+    }
+
+    Err(io::Error::new(
+        io::ErrorKind::TimedOut,
+        format!("process {} never became {}", pid, name),
+    ))
+}
@@ -0,0 +1,125 @@
+//! Replays the transcripts in `tests/fixtures/transcripts` and checks the
+//! status reported at each instant listed in the matching `.expected.json`.
+
+use c9watch_lib::config::{PollingConfig, StatusConfig};
+use c9watch_lib::session::{
+    FixedClock, PermissionChecker, SessionStatus, StatusContext, Transcript,
+};
+use chrono::{DateTime, Utc};
+use serde::Deserialize;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+/// Expected status timeline for one transcript
+#[derive(Debug, Deserialize)]
+struct Expectations {
+    description: String,
+    /// Allow rules, as in the `permissions.allow` list of `settings.json`
+    #[serde(default)]
+    allow: Vec<String>,
+    timeline: Vec<Checkpoint>,
+}
+
+#[derive(Debug, Deserialize)]
+struct Checkpoint {
+    /// RFC 3339 instant
+    at: String,
+    status: SessionStatus,
+}
+
+impl Checkpoint {
+    fn instant(&self) -> DateTime<Utc> {
+        DateTime::parse_from_rfc3339(&self.at)
+            .unwrap_or_else(|e| panic!("invalid checkpoint time {}: {}", self.at, e))
+            .with_timezone(&Utc)
+    }
+}
+
+fn corpus_dir() -> PathBuf {
+    Path::new(env!("CARGO_MANIFEST_DIR")).join("tests/fixtures/transcripts")
+}
+
+fn transcripts() -> Vec<PathBuf> {
+    let mut paths: Vec<PathBuf> = fs::read_dir(corpus_dir())
+        .expect("transcript corpus should exist")
+        .flatten()
+        .map(|entry| entry.path())
+        .filter(|path| path.extension().is_some_and(|ext| ext == "jsonl"))
+        .collect();
+    paths.sort();
+    paths
+}
+
+#[test]
+fn test_status_timelines_match_corpus() {
+    let transcripts = transcripts();
+    assert!(!transcripts.is_empty(), "transcript corpus is empty");
+
+    let thresholds = StatusConfig::default();
+    let tail = PollingConfig::default().tail_entries;
+    let mut failures = Vec::new();
+
+    for path in &transcripts {
+        let name = path.file_stem().unwrap().to_string_lossy().to_string();
+        let expected_path = path.with_extension("expected.json");
+        let expectations: Expectations = serde_json::from_str(
+            &fs::read_to_string(&expected_path)
+                .unwrap_or_else(|e| panic!("{}: missing expectations: {}", name, e)),
+        )
+        .unwrap_or_else(|e| panic!("{}: invalid expectations: {}", name, e));
+
+        let transcript = Transcript::from_file(path).unwrap();
+        let permissions = PermissionChecker::from_rules(&expectations.allow);
+
+        for checkpoint in &expectations.timeline {
+            let actual = transcript.status_at(checkpoint.instant(), tail, &thresholds, &permissions);
+            if actual != checkpoint.status {
+                failures.push(format!(
+                    "{} ({}) at {}: expected {:?}, got {:?}",
+                    name, expectations.description, checkpoint.at, checkpoint.status, actual
+                ));
+            }
+        }
+    }
+
+    assert!(failures.is_empty(), "status regressions:\n{}", failures.join("\n"));
+}
+
+#[test]
+fn test_every_transcript_has_expectations() {
+    for path in transcripts() {
+        assert!(
+            path.with_extension("expected.json").exists(),
+            "{} has no .expected.json",
+            path.display()
+        );
+    }
+}
+
+#[test]
+fn test_parallel_tool_calls_are_pending_together() {
+    let transcript = Transcript::from_file(corpus_dir().join("parallel_tools.jsonl")).unwrap();
+    let thresholds = StatusConfig::default();
+    let permissions = PermissionChecker::default();
+    let pending_at = |at: &str| {
+        let at = DateTime::parse_from_rfc3339(at).unwrap().with_timezone(&Utc);
+        let entries = transcript.entries_at(at, PollingConfig::default().tail_entries);
+        let context = StatusContext {
+            clock: &FixedClock(at),
+            thresholds: &thresholds,
+            permissions: &permissions,
+        };
+        context
+            .pending_tool_uses(&entries)
+            .iter()
+            .map(|tool| format!("{} {}", tool.id, tool.name))
+            .collect::<Vec<_>>()
+    };
+
+    // Each block is its own entry; the auto-approved Read isn't waiting
+    assert_eq!(
+        pending_at("2026-03-01T10:00:05.000Z"),
+        vec!["toolu_par_1 Edit", "toolu_par_3 Bash"]
+    );
+    assert!(pending_at("2026-03-01T10:00:31.000Z").is_empty());
+}
@@ -4,19 +4,42 @@
 
 import { invoke } from '@tauri-apps/api/core';
 import { get } from 'svelte/store';
-import type { Session, Conversation } from './types';
+import type {
+  Session,
+  SessionFilter,
+  SessionMetadataUpdate,
+  Conversation,
+  StatusTransition,
+  NotificationRecord,
+  AppConfig,
+  ReplayInfo,
+  ParseDiagnostics,
+  VersionUsage,
+  FilesTouched,
+  FileConflict,
+  ChildProcess,
+  ProcessSignal,
+  StopOutcome,
+  InterruptOutcome,
+  LaunchRequest,
+  LaunchTarget,
+  LaunchedSession,
+  AttentionItem
+} from './types';
+import { SessionStatus } from './types';
 import { isDemoMode } from './demo';
-import { getDemoSessions, demoConversations } from './demo/data';
+import { getDemoSessions, demoConversations, demoConfig } from './demo/data';
 
 /**
  * Get all active Claude Code sessions
+ * @param filter - Optional criteria (tags, pinned, statuses, ...) to narrow the list
  * @returns Promise resolving to array of sessions
  */
-export async function getSessions(): Promise<Session[]> {
+export async function getSessions(filter?: SessionFilter): Promise<Session[]> {
   if (get(isDemoMode)) {
     return getDemoSessions();
   }
-  return await invoke<Session[]>('get_sessions');
+  return await invoke<Session[]>('get_sessions', { filter });
 }
 
 /**
@@ -32,13 +55,138 @@ export async function getConversation(sessionId: string): Promise<Conversation>
 }
 
 /**
- * Stop a running session by sending SIGTERM
+ * Stop a running session, escalating from SIGINT to SIGTERM to SIGKILL
  * @param pid - The process ID of the Claude session
- * @returns Promise resolving when the stop signal has been sent
+ * @returns Promise resolving once the process has exited, with how it ended
  */
-export async function stopSession(pid: number): Promise<void> {
+export async function stopSession(pid: number): Promise<StopOutcome> {
+  if (get(isDemoMode)) {
+    return { pid, endedBy: 'Interrupt', elapsedMs: 0, processGroup: false, orphansStopped: 0 };
+  }
+  return await invoke<StopOutcome>('stop_session', { pid });
+}
+
+/**
+ * List the processes a session spawned (dev servers, test runners, builds)
+ * @param pid - The process ID of the Claude session
+ * @returns Promise resolving to the descendants, parents before their children
+ */
+export async function getChildProcesses(pid: number): Promise<ChildProcess[]> {
+  if (get(isDemoMode)) return [];
+  return await invoke<ChildProcess[]>('get_child_processes', { pid });
+}
+
+/**
+ * Signal one process a session spawned, leaving the session running
+ * @param sessionPid - The process ID of the Claude session
+ * @param childPid - A descendant of the session process
+ * @param signal - Signal to send
+ * @returns Promise resolving when the signal has been sent
+ */
+export async function signalChildProcess(
+  sessionPid: number,
+  childPid: number,
+  signal: ProcessSignal
+): Promise<void> {
+  if (get(isDemoMode)) return;
+  await invoke<void>('signal_child_process', { sessionPid, childPid, signal });
+}
+
+/**
+ * Rank the sessions waiting on the user by status, waiting time, tool risk and importance
+ * @returns Promise resolving to the queue, the session to handle first at the top
+ */
+export async function getAttentionQueue(): Promise<AttentionItem[]> {
+  if (get(isDemoMode)) {
+    const waiting = getDemoSessions().filter(
+      (s) => s.status === SessionStatus.NeedsPermission || s.status === SessionStatus.WaitingForInput
+    );
+    return waiting
+      .map((s) => ({
+        sessionId: s.id,
+        pid: s.pid,
+        projectPath: s.projectPath,
+        status: s.status,
+        waitingSecs: 0,
+        score: s.status === SessionStatus.NeedsPermission ? 100 : 50,
+        reasons: []
+      }))
+      .sort((a, b) => b.score - a.score);
+  }
+  return await invoke<AttentionItem[]>('get_attention_queue');
+}
+
+/**
+ * Type into the terminal of a session c9watch launched detached
+ * @param pid - The process ID of the Claude session
+ * @param data - Keys as terminal input ("\r" for Enter, "\x1b" for Escape)
+ * @returns Promise resolving when the input has been written
+ */
+export async function ptyWrite(pid: number, data: string): Promise<void> {
+  if (get(isDemoMode)) return;
+  await invoke<void>('pty_write', { pid, data });
+}
+
+/**
+ * Resize the terminal of a detached session
+ * @param pid - The process ID of the Claude session
+ * @param cols - Columns
+ * @param rows - Rows
+ * @returns Promise resolving once the process has been told the new size
+ */
+export async function ptyResize(pid: number, cols: number, rows: number): Promise<void> {
   if (get(isDemoMode)) return;
-  await invoke<void>('stop_session', { pid });
+  await invoke<void>('pty_resize', { pid, cols, rows });
+}
+
+/**
+ * Get the recent output of a detached session's terminal; `pty-output` events carry the rest
+ * @param pid - The process ID of the Claude session
+ * @returns Promise resolving to the output, escape sequences included
+ */
+export async function getPtyScrollback(pid: number): Promise<string> {
+  if (get(isDemoMode)) return '';
+  return await invoke<string>('get_pty_scrollback', { pid });
+}
+
+/**
+ * Start a new Claude Code session; its card shows as Connecting until the first entry
+ * @param request - Project directory, optional prompt/model/permission mode, and where to run it
+ * @returns Promise resolving to the new session's ID (and PID when known)
+ */
+export async function launchSession(request: LaunchRequest): Promise<LaunchedSession> {
+  if (get(isDemoMode)) {
+    return { sessionId: crypto.randomUUID(), pid: null, target: request.target };
+  }
+  return await invoke<LaunchedSession>('launch_session', { request });
+}
+
+/**
+ * Continue a finished session with `claude --resume` in the project directory it was started in
+ * @param sessionId - The session UUID; it keeps its ID, name and title
+ * @param target - Where to run the resumed session
+ * @returns Promise resolving to the resumed session (PID when known)
+ */
+export async function resumeSession(
+  sessionId: string,
+  target: LaunchTarget
+): Promise<LaunchedSession> {
+  if (get(isDemoMode)) {
+    return { sessionId, pid: null, target };
+  }
+  return await invoke<LaunchedSession>('resume_session', { sessionId, target });
+}
+
+/**
+ * Stop a session's current turn (like pressing Esc) without ending the session
+ * @param sessionId - The session UUID
+ * @returns Promise resolving once the transcript shows the turn ended, or the wait timed out
+ */
+export async function interruptSession(sessionId: string): Promise<InterruptOutcome> {
+  if (get(isDemoMode)) {
+    return { method: { kind: 'signal' }, confirmed: true, status: 'WaitingForInput' };
+  }
+  return await invoke<InterruptOutcome>('interrupt_session', { sessionId });
 }
 
 /**
@@ -52,3 +200,144 @@ export async function openSession(pid: number, projectPath: string): Promise<voi
   await invoke<void>('open_session', { pid, projectPath });
 }
 
+
+/**
+ * Update the tags, note, pinned flag and/or priority of a session
+ * @param sessionId - The session UUID
+ * @param update - Fields to change; omitted fields are left as they are
+ * @returns Promise resolving when the metadata has been saved
+ */
+export async function updateSessionMetadata(
+  sessionId: string,
+  update: SessionMetadataUpdate
+): Promise<void> {
+  if (get(isDemoMode)) return;
+  await invoke<void>('update_session_metadata', { sessionId, ...update });
+}
+
+/**
+ * Get the recorded status transitions of a session
+ * @param sessionId - The session UUID
+ * @returns Promise resolving to transitions, oldest first
+ */
+export async function getStatusHistory(sessionId: string): Promise<StatusTransition[]> {
+  if (get(isDemoMode)) return [];
+  return await invoke<StatusTransition[]>('get_status_history', { sessionId });
+}
+
+/**
+ * Get the most recent notifications
+ * @param limit - Maximum number of records (defaults to 50)
+ * @returns Promise resolving to notifications, newest first
+ */
+export async function getNotificationHistory(limit?: number): Promise<NotificationRecord[]> {
+  if (get(isDemoMode)) return [];
+  return await invoke<NotificationRecord[]>('get_notification_history', { limit });
+}
+
+/**
+ * Get the active app configuration
+ * @returns Promise resolving to the config
+ */
+export async function getConfig(): Promise<AppConfig> {
+  if (get(isDemoMode)) return demoConfig;
+  return await invoke<AppConfig>('get_config');
+}
+
+/**
+ * Validate, save and apply a new app configuration
+ * @param config - The complete config to store
+ * @returns Promise resolving to the applied config (rejects with the validation error)
+ */
+export async function setConfig(config: AppConfig): Promise<AppConfig> {
+  if (get(isDemoMode)) return config;
+  return await invoke<AppConfig>('set_config', { config });
+}
+
+/**
+ * Replay a session transcript as a live synthetic session
+ * @param path - JSONL transcript to replay; omit for the built-in demo session
+ * @param speed - Transcript seconds per wall-clock second (defaults to 1)
+ * @param looping - Restart from the beginning when the end is reached
+ * @returns Promise resolving to the replay state
+ */
+export async function startReplay(
+  path?: string,
+  speed?: number,
+  looping?: boolean
+): Promise<ReplayInfo> {
+  return await invoke<ReplayInfo>('start_replay', { path, speed, looping });
+}
+
+/**
+ * Stop the running replay and remove its session from the list
+ */
+export async function stopReplay(): Promise<void> {
+  await invoke<void>('stop_replay');
+}
+
+/**
+ * Change the replay speed
+ * @param speed - Transcript seconds per wall-clock second (0 pauses)
+ */
+export async function setReplaySpeed(speed: number): Promise<ReplayInfo> {
+  return await invoke<ReplayInfo>('set_replay_speed', { speed });
+}
+
+/**
+ * Jump to a point in the replayed transcript
+ * @param offsetMs - Milliseconds from the start of the transcript
+ */
+export async function seekReplay(offsetMs: number): Promise<ReplayInfo> {
+  return await invoke<ReplayInfo>('seek_replay', { offsetMs });
+}
+
+/**
+ * Get the state of the running replay
+ * @returns Promise resolving to the replay state, or null when none is running
+ */
+export async function getReplayState(): Promise<ReplayInfo | null> {
+  return await invoke<ReplayInfo | null>('get_replay_state');
+}
+
+/**
+ * Get the JSONL lines and content blocks the parser didn't understand
+ * @returns Promise resolving to the recorded shapes, most recent first
+ */
+export async function getParseDiagnostics(): Promise<ParseDiagnostics> {
+  if (get(isDemoMode)) return { shapes: [] };
+  return await invoke<ParseDiagnostics>('get_parse_diagnostics');
+}
+
+/**
+ * Get how many running sessions use each Claude Code version
+ * @returns Promise resolving to the versions, newest first
+ */
+export async function getVersionDistribution(): Promise<VersionUsage[]> {
+  if (get(isDemoMode)) {
+    return [
+      { version: '2.1.3', sessionIds: ['demo-1', 'demo-2', 'demo-3', 'demo-5'], outdated: false },
+      { version: '2.0.76', sessionIds: ['demo-4', 'demo-6'], outdated: true }
+    ];
+  }
+  return await invoke<VersionUsage[]>('get_version_distribution');
+}
+
+/**
+ * Get the files a session created, edited and deleted
+ * @param sessionId - The session ID
+ * @returns Promise resolving to the per-file summary
+ */
+export async function getFilesTouched(sessionId: string): Promise<FilesTouched> {
+  if (get(isDemoMode)) return { files: [], created: 0, edited: 0, deleted: 0 };
+  return await invoke<FilesTouched>('get_files_touched', { sessionId });
+}
+
+/**
+ * Get the files that more than one live session has changed
+ * @returns Promise resolving to the conflicting files
+ */
+export async function getFileConflicts(): Promise<FileConflict[]> {
+  if (get(isDemoMode)) return [];
+  return await invoke<FileConflict[]>('get_file_conflicts');
+}
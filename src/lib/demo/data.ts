@@ -2,13 +2,23 @@
  * Demo session and conversation data for UI exploration
  */
 
-import type { Session, Conversation } from '../types';
+import type { Session, Conversation, AppConfig, ContextUsage } from '../types';
 import { SessionStatus } from '../types';
 
 function minutesAgo(minutes: number): string {
 	return new Date(Date.now() - minutes * 60 * 1000).toISOString();
 }
 
+function demoContext(tokens: number): ContextUsage {
+	const windowTokens = 200000;
+	return {
+		model: 'claude-opus-4-5-20251101',
+		tokens,
+		windowTokens,
+		percent: Math.round((tokens / windowTokens) * 1000) / 10
+	};
+}
+
 /**
  * Status-specific messages that make transitions feel realistic.
  * Each session ID maps to messages for each possible status.
@@ -85,7 +95,31 @@ export function getDemoSessions(): Session[] {
 			modified: minutesAgo(2),
 			status: SessionStatus.NeedsPermission,
 			latestMessage: 'I need to write to src/auth/providers.ts — may I proceed?',
-			pendingToolName: 'Write'
+			pendingToolName: 'Write',
+			pendingTools: [
+				{
+					id: 'toolu_demo_write',
+					name: 'Write',
+					input: { file_path: '/Users/demo/projects/web-app/src/auth/providers.ts' },
+					target: '/Users/demo/projects/web-app/src/auth/providers.ts',
+					description: null,
+					diff: null,
+					risk: { level: 'localWrite', matched: [] }
+				}
+			],
+			tags: ['release-blocker'],
+			note: null,
+			pinned: true,
+			priority: 0,
+			context: demoContext(62000),
+			compactionCount: 0,
+			model: 'claude-opus-4-5-20251101',
+			modelSwitches: [{ model: 'claude-opus-4-5-20251101', at: minutesAgo(90) }],
+			cliVersion: '2.1.3',
+			conflicts: [],
+			git: null,
+			resources: null,
+			embeddedTerminal: false
 		},
 		{
 			id: 'demo-2',
@@ -100,7 +134,24 @@ export function getDemoSessions(): Session[] {
 			modified: minutesAgo(5),
 			status: SessionStatus.Working,
 			latestMessage: 'Running the profiler on the VirtualizedTable component to identify the bottleneck...',
-			pendingToolName: null
+			pendingToolName: null,
+			pendingTools: [],
+			tags: [],
+			note: null,
+			pinned: false,
+			priority: 0,
+			context: demoContext(141000),
+			compactionCount: 1,
+			model: 'claude-opus-4-5-20251101',
+			modelSwitches: [
+				{ model: 'claude-sonnet-4-5-20250929', at: minutesAgo(120) },
+				{ model: 'claude-opus-4-5-20251101', at: minutesAgo(30) }
+			],
+			cliVersion: '2.1.3',
+			conflicts: [],
+			git: null,
+			resources: null,
+			embeddedTerminal: false
 		},
 
 		// Project 2: api-server — NeedsPermission + Working
@@ -117,7 +168,31 @@ export function getDemoSessions(): Session[] {
 			modified: minutesAgo(1),
 			status: SessionStatus.NeedsPermission,
 			latestMessage: 'I need to run `npm install ioredis` — allow?',
-			pendingToolName: 'Bash'
+			pendingToolName: 'Bash',
+			pendingTools: [
+				{
+					id: 'toolu_demo_install',
+					name: 'Bash',
+					input: { command: 'npm install ioredis', description: 'Add the Redis client' },
+					target: 'npm install ioredis',
+					description: 'Add the Redis client',
+					diff: null,
+					risk: { level: 'localWrite', matched: [] }
+				}
+			],
+			tags: ['experiment'],
+			note: null,
+			pinned: false,
+			priority: 0,
+			context: demoContext(38000),
+			compactionCount: 0,
+			model: 'claude-opus-4-5-20251101',
+			modelSwitches: [{ model: 'claude-opus-4-5-20251101', at: minutesAgo(90) }],
+			cliVersion: '2.1.3',
+			conflicts: [],
+			git: null,
+			resources: null,
+			embeddedTerminal: false
 		},
 		{
 			id: 'demo-4',
@@ -132,7 +207,21 @@ export function getDemoSessions(): Session[] {
 			modified: minutesAgo(8),
 			status: SessionStatus.Working,
 			latestMessage: 'Writing test case for subscription renewal webhook with idempotency check...',
-			pendingToolName: null
+			pendingToolName: null,
+			pendingTools: [],
+			tags: [],
+			note: null,
+			pinned: false,
+			priority: 0,
+			context: demoContext(168000),
+			compactionCount: 2,
+			model: 'claude-opus-4-5-20251101',
+			modelSwitches: [{ model: 'claude-opus-4-5-20251101', at: minutesAgo(90) }],
+			cliVersion: '2.0.76',
+			conflicts: [],
+			git: null,
+			resources: null,
+			embeddedTerminal: false
 		},
 
 		// Project 3: cli-tools — WaitingForInput x2
@@ -149,7 +238,21 @@ export function getDemoSessions(): Session[] {
 			modified: minutesAgo(15),
 			status: SessionStatus.WaitingForInput,
 			latestMessage: 'Done! The wizard is at src/commands/init.ts. Run `cli-tools init` to try it.',
-			pendingToolName: null
+			pendingToolName: null,
+			pendingTools: [],
+			tags: [],
+			note: null,
+			pinned: false,
+			priority: 0,
+			context: demoContext(97000),
+			compactionCount: 0,
+			model: 'claude-opus-4-5-20251101',
+			modelSwitches: [{ model: 'claude-opus-4-5-20251101', at: minutesAgo(90) }],
+			cliVersion: '2.1.3',
+			conflicts: [],
+			git: null,
+			resources: null,
+			embeddedTerminal: false
 		},
 		{
 			id: 'demo-6',
@@ -164,7 +267,21 @@ export function getDemoSessions(): Session[] {
 			modified: minutesAgo(60),
 			status: SessionStatus.WaitingForInput,
 			latestMessage: 'All 14 command handlers have been migrated to the Result pattern. Tests pass.',
-			pendingToolName: null
+			pendingToolName: null,
+			pendingTools: [],
+			tags: [],
+			note: null,
+			pinned: false,
+			priority: 0,
+			context: null,
+			compactionCount: 1,
+			model: 'claude-opus-4-5-20251101',
+			modelSwitches: [{ model: 'claude-opus-4-5-20251101', at: minutesAgo(90) }],
+			cliVersion: '2.0.76',
+			conflicts: [],
+			git: null,
+			resources: null,
+			embeddedTerminal: false
 		}
 	];
 }
@@ -324,3 +441,36 @@ export const demoConversations: Record<string, Conversation> = {
 		]
 	}
 };
+
+/**
+ * Config shown in demo mode (same values as the backend defaults)
+ */
+export const demoConfig: AppConfig = {
+	polling: { interval_ms: 2000, tail_entries: 20 },
+	status: { tool_result_recent_secs: 15, user_prompt_recent_secs: 30, assistant_recent_secs: 10 },
+	display: { first_prompt_max_chars: 100, latest_message_max_chars: 200, notification_title_max_chars: 60 },
+	notifications: {
+		transitions: [
+			{ from: SessionStatus.Working, to: SessionStatus.NeedsPermission },
+			{ from: SessionStatus.Working, to: SessionStatus.WaitingForInput }
+		]
+	},
+	context: { notify_at_percent: 80, default_window_tokens: 200000, model_windows: {} },
+	resources: { memory_budget_mb: 8192, cpu_budget_percent: 400, sample_window: 15 },
+	stop: { interrupt_grace_ms: 1500, terminate_grace_ms: 3000 },
+	launch: {
+		claude_command: 'claude',
+		tmux_session: 'c9watch',
+		terminal_command: ['x-terminal-emulator', '-e']
+	},
+	attention: {
+		project_importance: {}
+	},
+	risk: {
+		rules: [
+			{ tools: ['Bash'], command: 'rm', level: 'destructive' },
+			{ tools: ['Bash'], command: 'git push', level: 'network' },
+			{ contains: '.env', level: 'secrets' }
+		]
+	}
+};
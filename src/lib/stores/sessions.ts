@@ -108,6 +108,13 @@ export async function initializeSessionListeners() {
 		currentConversation.set(event.payload);
 	});
 
+	// "Jump to Next Session" on a detached session opens its card here
+	await listen<string>('expand-session', (event) => {
+		if (!get(isDemoMode)) {
+			expandedSessionId.set(event.payload);
+		}
+	});
+
 	// Listen for notification-fired events to track metadata for click-to-focus
 	await listen<NotificationMetadata>('notification-fired', (event) => {
 		if (!get(isDemoMode)) {
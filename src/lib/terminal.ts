@@ -0,0 +1,296 @@
+/**
+ * Minimal screen model for the embedded terminal of detached sessions.
+ *
+ * Claude Code redraws its UI with cursor movement and erase sequences, so
+ * output can't just be appended as text. This keeps a grid of cells and
+ * applies the subset of VT100/xterm control sequences a TUI relies on;
+ * colors and other attributes are dropped.
+ */
+
+/** Lines kept above the visible screen */
+const MAX_HISTORY = 5000;
+
+export class TerminalScreen {
+	cols: number;
+	rows: number;
+
+	/** Lines that scrolled off the top, oldest first */
+	history: string[] = [];
+
+	/** The program asked for pasted text to be wrapped in markers */
+	bracketedPaste = false;
+
+	private grid: string[][];
+	private x = 0;
+	private y = 0;
+	private savedX = 0;
+	private savedY = 0;
+
+	/** Start of an escape sequence split across writes */
+	private pending = '';
+
+	constructor(cols: number, rows: number) {
+		this.cols = cols;
+		this.rows = rows;
+		this.grid = Array.from({ length: rows }, () => this.blankRow());
+	}
+
+	/** Apply terminal output */
+	write(data: string) {
+		const input = this.pending + data;
+		this.pending = '';
+
+		let i = 0;
+		while (i < input.length) {
+			if (input[i] === '\x1b') {
+				const end = this.escapeEnd(input, i);
+				if (end === -1) {
+					this.pending = input.slice(i);
+					return;
+				}
+				this.escape(input.slice(i, end));
+				i = end;
+				continue;
+			}
+
+			const ch = String.fromCodePoint(input.codePointAt(i)!);
+			i += ch.length;
+			switch (ch) {
+				case '\r':
+					this.x = 0;
+					break;
+				case '\n':
+					this.lineFeed();
+					break;
+				case '\b':
+					this.x = Math.max(0, this.x - 1);
+					break;
+				case '\t':
+					this.x = Math.min(this.cols - 1, (Math.floor(this.x / 8) + 1) * 8);
+					break;
+				default:
+					if (ch >= ' ' && ch !== '\x7f') this.put(ch);
+			}
+		}
+	}
+
+	/** Change the grid size; rows pushed off the top go to the history */
+	resize(cols: number, rows: number) {
+		while (this.grid.length > rows) {
+			this.history.push(this.rowText(this.grid.shift()!));
+			this.y = Math.max(0, this.y - 1);
+		}
+		while (this.grid.length < rows) this.grid.push([]);
+		this.cols = cols;
+		this.rows = rows;
+		this.grid = this.grid.map((row) => {
+			const resized = row.slice(0, cols);
+			while (resized.length < cols) resized.push(' ');
+			return resized;
+		});
+		this.x = Math.min(this.x, cols - 1);
+		this.y = Math.min(this.y, rows - 1);
+		this.trimHistory();
+	}
+
+	/** History and screen as text, without trailing blank lines */
+	text(): string {
+		const lines = [...this.history, ...this.grid.map((row) => this.rowText(row))];
+		while (lines.length > 0 && lines[lines.length - 1] === '') lines.pop();
+		return lines.join('\n');
+	}
+
+	private blankRow(): string[] {
+		return new Array(this.cols).fill(' ');
+	}
+
+	private rowText(row: string[]): string {
+		return row.join('').trimEnd();
+	}
+
+	private put(ch: string) {
+		if (this.x >= this.cols) {
+			this.x = 0;
+			this.lineFeed();
+		}
+		this.grid[this.y][this.x] = ch;
+		this.x++;
+	}
+
+	private lineFeed() {
+		if (this.y < this.rows - 1) {
+			this.y++;
+			return;
+		}
+		this.history.push(this.rowText(this.grid.shift()!));
+		this.grid.push(this.blankRow());
+		this.trimHistory();
+	}
+
+	private trimHistory() {
+		if (this.history.length > MAX_HISTORY) {
+			this.history.splice(0, this.history.length - MAX_HISTORY);
+		}
+	}
+
+	/** Index just past the escape sequence at `start`, or -1 if it isn't complete yet */
+	private escapeEnd(input: string, start: number): number {
+		const kind = input[start + 1];
+		if (kind === undefined) return -1;
+
+		if (kind === '[') {
+			// CSI: parameters, then a final byte in @..~
+			for (let i = start + 2; i < input.length; i++) {
+				const code = input.charCodeAt(i);
+				if (code >= 0x40 && code <= 0x7e) return i + 1;
+			}
+			return -1;
+		}
+		if (kind === ']') {
+			// OSC (window title, hyperlinks): ends with BEL or ESC \
+			for (let i = start + 2; i < input.length; i++) {
+				if (input[i] === '\x07') return i + 1;
+				if (input[i] === '\x1b' && input[i + 1] === '\\') return i + 2;
+			}
+			return -1;
+		}
+		if (kind === '(' || kind === ')') {
+			// Character set selection takes one more byte
+			return start + 3 <= input.length ? start + 3 : -1;
+		}
+		return start + 2;
+	}
+
+	private escape(seq: string) {
+		switch (seq[1]) {
+			case '[':
+				this.csi(seq.slice(2, -1), seq[seq.length - 1]);
+				break;
+			case '7':
+				this.savedX = this.x;
+				this.savedY = this.y;
+				break;
+			case '8':
+				this.x = this.savedX;
+				this.y = this.savedY;
+				break;
+			case 'M':
+				this.y = Math.max(0, this.y - 1);
+				break;
+		}
+	}
+
+	private csi(params: string, final: string) {
+		if (params.startsWith('?')) {
+			const enable = final === 'h';
+			for (const mode of params.slice(1).split(';')) {
+				if (mode === '2004') this.bracketedPaste = enable;
+				// Alternate screen: start from a blank grid either way
+				if (mode === '1049' || mode === '47') this.clear(0, this.rows);
+			}
+			return;
+		}
+
+		const args = params.split(';').map((n) => parseInt(n, 10) || 0);
+		const count = Math.max(1, args[0]);
+		switch (final) {
+			case 'A':
+				this.y = Math.max(0, this.y - count);
+				break;
+			case 'B':
+				this.y = Math.min(this.rows - 1, this.y + count);
+				break;
+			case 'C':
+				this.x = Math.min(this.cols - 1, this.x + count);
+				break;
+			case 'D':
+				this.x = Math.max(0, this.x - count);
+				break;
+			case 'G':
+				this.x = Math.min(this.cols - 1, count - 1);
+				break;
+			case 'H':
+			case 'f':
+				this.y = Math.min(this.rows - 1, Math.max(1, args[0]) - 1);
+				this.x = Math.min(this.cols - 1, Math.max(1, args[1] ?? 1) - 1);
+				break;
+			case 'J':
+				if (args[0] === 0) {
+					this.eraseLine(this.x, this.cols);
+					this.clear(this.y + 1, this.rows);
+				} else if (args[0] === 1) {
+					this.clear(0, this.y);
+					this.eraseLine(0, this.x + 1);
+				} else {
+					this.clear(0, this.rows);
+				}
+				break;
+			case 'K':
+				if (args[0] === 0) this.eraseLine(this.x, this.cols);
+				else if (args[0] === 1) this.eraseLine(0, this.x + 1);
+				else this.eraseLine(0, this.cols);
+				break;
+			case 's':
+				this.savedX = this.x;
+				this.savedY = this.y;
+				break;
+			case 'u':
+				this.x = this.savedX;
+				this.y = this.savedY;
+				break;
+			// Colors (m) and anything else don't change the text
+		}
+	}
+
+	private clear(fromRow: number, toRow: number) {
+		for (let row = fromRow; row < toRow; row++) this.grid[row] = this.blankRow();
+	}
+
+	private eraseLine(fromCol: number, toCol: number) {
+		this.grid[this.y].fill(' ', fromCol, toCol);
+	}
+}
+
+/**
+ * Terminal input for a key press, or null to leave the key to the app
+ */
+export function keySequence(e: KeyboardEvent): string | null {
+	// Cmd shortcuts (copy, close) stay with the webview
+	if (e.metaKey) return null;
+
+	switch (e.key) {
+		case 'Enter':
+			return '\r';
+		case 'Backspace':
+			return '\x7f';
+		case 'Tab':
+			return e.shiftKey ? '\x1b[Z' : '\t';
+		case 'Escape':
+			return '\x1b';
+		case 'ArrowUp':
+			return '\x1b[A';
+		case 'ArrowDown':
+			return '\x1b[B';
+		case 'ArrowRight':
+			return '\x1b[C';
+		case 'ArrowLeft':
+			return '\x1b[D';
+		case 'Home':
+			return '\x1b[H';
+		case 'End':
+			return '\x1b[F';
+		case 'Delete':
+			return '\x1b[3~';
+		case 'PageUp':
+			return '\x1b[5~';
+		case 'PageDown':
+			return '\x1b[6~';
+	}
+
+	if ([...e.key].length !== 1) return null;
+	if (e.ctrlKey) {
+		const code = e.key.toUpperCase().charCodeAt(0);
+		return code >= 0x40 && code <= 0x5f ? String.fromCharCode(code - 0x40) : null;
+	}
+	return e.altKey ? '\x1b' + e.key : e.key;
+}
@@ -37,7 +37,7 @@ export interface Session {
   /** Summary of the first prompt (shown in list view) */
   firstPrompt: string;
 
-  /** AI-generated summary of the session (from sessions-index.json) */
+  /** AI-generated summary of the session (from sessions-index.json, else the transcript) */
   summary: string | null;
 
   /** Total number of messages in the conversation */
@@ -54,12 +54,124 @@ export interface Session {
 
   /** Name of the tool currently awaiting user permission (if status is NeedsPermission) */
   pendingToolName: string | null;
+
+  /** Every tool call waiting for permission, in the order Claude made them */
+  pendingTools: PendingTool[];
+
+  /** User-assigned tags (e.g. "release-blocker") */
+  tags: string[];
+
+  /** Free-form note attached to the session */
+  note: string | null;
+
+  /** Whether the session is pinned */
+  pinned: boolean;
+
+  /** User-assigned priority (higher is more important) */
+  priority: number;
+
+  /** Context window fill as of the latest assistant message */
+  context: ContextUsage | null;
+
+  /** Times the conversation was compacted */
+  compactionCount: number;
+
+  /** Model of the latest assistant message */
+  model: string | null;
+
+  /** Models in the order they were used, starting with the initial one */
+  modelSwitches: ModelSwitch[];
+
+  /** Claude Code version that wrote the latest entry */
+  cliVersion: string | null;
+
+  /** Files this session and others on the same working tree and branch both changed */
+  conflicts: FileConflict[];
+
+  /** Repository, worktree and working state of the session's cwd (null outside git) */
+  git: GitInfo | null;
+
+  /** CPU, memory and uptime of the claude process and its child processes */
+  resources: ResourceUsage | null;
+
+  /** Runs on a terminal owned by c9watch that the app can show and type into */
+  embeddedTerminal: boolean;
+}
+
+/**
+ * A model taking over a session's conversation
+ */
+export interface ModelSwitch {
+  model: string;
+
+  /** ISO 8601 timestamp of the first message written by this model */
+  at: string;
+}
+
+/**
+ * Running sessions grouped by Claude Code version
+ */
+export interface VersionUsage {
+  /** null for sessions that haven't written a versioned entry */
+  version: string | null;
+  sessionIds: string[];
+
+  /** Older than the newest version among the running sessions */
+  outdated: boolean;
+}
+
+/**
+ * How full a session's context window is
+ */
+export interface ContextUsage {
+  model: string;
+
+  /** Input plus cache tokens of the latest request */
+  tokens: number;
+
+  windowTokens: number;
+
+  /** 0-100, one decimal */
+  percent: number;
+}
+
+/**
+ * Criteria for narrowing down the session list; every field set must match
+ */
+export interface SessionFilter {
+  /** Sessions must carry all of these tags */
+  tags?: string[];
+  pinned?: boolean;
+  /** Sessions must be in one of these statuses */
+  statuses?: SessionStatus[];
+  projectPath?: string;
+  minPriority?: number;
+}
+
+/**
+ * Fields of session metadata to update; omitted fields are left unchanged
+ */
+export interface SessionMetadataUpdate {
+  tags?: string[];
+  /** An empty string clears the note */
+  note?: string;
+  pinned?: boolean;
+  priority?: number;
 }
 
 /**
  * Message type in conversation
  */
-export type MessageType = 'User' | 'Assistant' | 'Thinking' | 'ToolUse' | 'ToolResult';
+export type MessageType =
+  | 'User'
+  | 'Assistant'
+  | 'Thinking'
+  | 'ToolUse'
+  | 'ToolResult'
+  /** Live output of a tool that is still running */
+  | 'ToolProgress'
+  /** Notice from Claude Code itself, e.g. a compaction */
+  | 'System';
 
 /**
  * A message in a conversation
@@ -85,3 +197,462 @@ export interface Conversation {
   /** Array of messages in chronological order */
   messages: Message[];
 }
+
+/**
+ * A recorded change of session status (from the local database)
+ */
+export interface StatusTransition {
+  /** Session this transition belongs to */
+  sessionId: string;
+
+  /** Previous status, or null when the session was first observed */
+  fromStatus: SessionStatus | null;
+
+  /** Status the session moved to */
+  toStatus: SessionStatus;
+
+  /** When the transition was observed (ISO 8601 string) */
+  at: string;
+}
+
+/**
+ * A notification previously shown to the user
+ */
+export interface NotificationRecord {
+  id: number;
+  sessionId: string;
+  status: SessionStatus;
+  title: string;
+  body: string;
+
+  /** When the notification fired (ISO 8601 string) */
+  firedAt: string;
+}
+
+/**
+ * Application configuration (mirrors `config.toml`, so keys are snake_case)
+ */
+export interface AppConfig {
+  polling: {
+    /** Delay between two polling cycles */
+    interval_ms: number;
+
+    /** Number of trailing JSONL entries parsed per session */
+    tail_entries: number;
+  };
+
+  /** Recency windows (seconds) used to tell an active session from an idle one */
+  status: {
+    tool_result_recent_secs: number;
+    user_prompt_recent_secs: number;
+    assistant_recent_secs: number;
+  };
+
+  /** Truncation lengths (characters) for text shown in the UI */
+  display: {
+    first_prompt_max_chars: number;
+    latest_message_max_chars: number;
+    notification_title_max_chars: number;
+  };
+
+  notifications: {
+    /** Status transitions that fire a native notification */
+    transitions: { from: SessionStatus; to: SessionStatus }[];
+  };
+
+  context: {
+    /** Notify when a session's context fill reaches this percentage (0 disables) */
+    notify_at_percent: number;
+
+    /** Window size for models without an entry in `model_windows` */
+    default_window_tokens: number;
+
+    /** Window size per model name prefix; the longest matching prefix wins */
+    model_windows: Record<string, number>;
+  };
+
+  resources: {
+    /** Notify when a session's process tree holds more resident memory than this (0 disables) */
+    memory_budget_mb: number;
+
+    /** Notify when a session's process tree averages more CPU than this; 100 is one full core (0 disables) */
+    cpu_budget_percent: number;
+
+    /** Polling cycles the CPU average covers */
+    sample_window: number;
+  };
+
+  stop: {
+    /** Wait after SIGINT (which interrupts the current turn) before sending SIGTERM */
+    interrupt_grace_ms: number;
+
+    /** Wait after SIGTERM before sending SIGKILL */
+    terminate_grace_ms: number;
+  };
+
+  launch: {
+    /** Claude Code executable, looked up on PATH unless it is a path */
+    claude_command: string;
+
+    /** tmux session that new windows are opened in; created when missing */
+    tmux_session: string;
+
+    /** Terminal emulator and the arguments preceding the command it runs; empty uses Terminal.app on macOS */
+    terminal_command: string[];
+  };
+
+  attention: {
+    /** Importance of projects by path, -10 to 10; a session takes the longest path its project is in */
+    project_importance: Record<string, number>;
+  };
+
+  risk: {
+    /** Replaces the built-in rules; a call gets the highest level among its tool's own and every matching rule */
+    rules: RiskRule[];
+  };
+}
+
+/**
+ * State of a running transcript replay (emitted as `replay-updated`)
+ */
+export interface ReplayInfo {
+  /** Id of the synthetic session added to `sessions-updated` */
+  sessionId: string;
+
+  /** Transcript being replayed, or null for the built-in demo */
+  path: string | null;
+
+  /** First and last instants of the replay (ISO 8601 strings) */
+  start: string;
+  end: string;
+
+  /** Transcript time the replay has reached (ISO 8601 string) */
+  position: string;
+
+  /** Transcript seconds per wall-clock second (0 means paused) */
+  speed: number;
+
+  /** Whether the replay restarts when it reaches the end */
+  looping: boolean;
+
+  finished: boolean;
+}
+
+/**
+ * A kind of JSONL line the parser couldn't fully understand
+ */
+export type ParseShape =
+  | { kind: 'parseFailure'; entryType: string | null }
+  | { kind: 'unknownEntryType'; entryType: string }
+  | { kind: 'unknownContentBlock'; entryType: string; blockType: string };
+
+/**
+ * What has been seen of one unexpected shape since startup
+ */
+export type ShapeReport = ParseShape & {
+  /** Distinct lines with this shape */
+  count: number;
+
+  /** ISO 8601 strings */
+  firstSeen: string;
+  lastSeen: string;
+
+  /** Deserialization error of the first failing line, for parse failures */
+  error: string | null;
+
+  /** First line seen with this shape, truncated */
+  sample: string;
+};
+
+/**
+ * Parser diagnostics, most recently seen shape first
+ */
+export interface ParseDiagnostics {
+  shapes: ShapeReport[];
+}
+
+/**
+ * What a session did to a file, as of its latest successful change
+ */
+export type FileChange = 'Created' | 'Edited' | 'Deleted';
+
+/**
+ * One file a session changed
+ */
+export interface FileTouch {
+  /** Absolute path */
+  path: string;
+  change: FileChange;
+
+  /** Successful tool calls that changed the file */
+  touches: number;
+
+  /** ISO 8601 timestamp of the latest change */
+  lastTouched: string;
+}
+
+/**
+ * Files a session created, edited and deleted, most recently touched first
+ */
+export interface FilesTouched {
+  files: FileTouch[];
+  created: number;
+  edited: number;
+  deleted: number;
+}
+
+/**
+ * A file changed by more than one live session
+ */
+export interface FileConflict {
+  path: string;
+  sessionIds: string[];
+}
+
+/**
+ * The commit a working tree has checked out
+ */
+export interface GitHead {
+  /** Checked-out branch, null when HEAD is detached */
+  branch: string | null;
+  commit: string | null;
+}
+
+/**
+ * A path with changes that aren't committed yet
+ */
+export interface UncommittedFile {
+  /** Relative to the working tree root */
+  path: string;
+
+  /** Two-letter porcelain status: staged then unstaged ("M.", ".M", "??", ...) */
+  status: string;
+}
+
+/**
+ * Repository, worktree and working state of a session's cwd
+ */
+export interface GitInfo {
+  /** Main working tree of the repository; worktrees of one repo share it */
+  repoRoot: string;
+
+  /** Working tree the session runs in */
+  worktree: string;
+  isLinkedWorktree: boolean;
+  head: GitHead;
+  dirty: boolean;
+
+  /** Commits ahead of / behind the upstream, null without an upstream */
+  ahead: number | null;
+  behind: number | null;
+  uncommitted: UncommittedFile[];
+}
+
+/**
+ * CPU, memory and uptime of a claude process together with every process it spawned
+ */
+export interface ResourceUsage {
+  /** CPU use of the whole tree since the previous sample; 100 is one full core */
+  cpuPercent: number;
+
+  /** Average of `cpuPercent` over the sampling window */
+  avgCpuPercent: number;
+
+  /** Resident memory of the whole tree */
+  rssBytes: number;
+
+  /** Highest `rssBytes` seen since the session was first sampled */
+  peakRssBytes: number;
+
+  /** Seconds since the claude process started */
+  uptimeSecs: number;
+
+  /** Processes in the tree, the claude process included */
+  processCount: number;
+}
+
+export type ResourceBudget = 'Memory' | 'Cpu';
+
+/**
+ * Payload of the `budget-exceeded` event
+ */
+export interface BudgetAlert {
+  sessionId: string;
+  budget: ResourceBudget;
+  usage: ResourceUsage;
+}
+
+/**
+ * A process started, directly or not, by a claude process
+ */
+export interface ChildProcess {
+  pid: number;
+  parentPid: number;
+
+  /** 1 for processes claude started itself, 2 for their children, and so on */
+  depth: number;
+  name: string;
+
+  /** Full command line, arguments joined with spaces */
+  command: string;
+
+  /** Seconds since the process started */
+  ageSecs: number;
+
+  /** CPU use since the previous sample; 100 is one full core */
+  cpuPercent: number;
+  rssBytes: number;
+}
+
+export type ProcessSignal = 'SIGINT' | 'SIGTERM' | 'SIGKILL' | 'SIGHUP';
+
+/** Signal that finally ended a stopped session */
+export type StopStage = 'Interrupt' | 'Terminate' | 'Kill';
+
+/**
+ * How a stopped session ended
+ */
+export interface StopOutcome {
+  pid: number;
+  endedBy: StopStage;
+
+  /** Time from the first signal until the process was gone */
+  elapsedMs: number;
+
+  /** Whether signals went to the whole process group rather than the process alone */
+  processGroup: boolean;
+
+  /** Processes the session spawned that were still running after it exited and were stopped */
+  orphansStopped: number;
+}
+
+/** How an interrupt reached a session */
+export type InterruptMethod =
+  | { kind: 'tmuxKeys'; pane: string }
+  | { kind: 'signal' };
+
+/**
+ * Result of interrupting a session's turn
+ */
+export interface InterruptOutcome {
+  method: InterruptMethod;
+
+  /** The transcript showed the session back at the prompt in time */
+  confirmed: boolean;
+
+  /** Status as last seen, null if the session exited */
+  status: SessionStatus | null;
+}
+
+/** Where a launched claude process runs */
+export type LaunchTarget = 'tmux' | 'terminal' | 'detached';
+
+/** Claude Code's `--permission-mode` values */
+export type PermissionMode = 'default' | 'acceptEdits' | 'plan' | 'bypassPermissions';
+
+/**
+ * What to start and where
+ */
+export interface LaunchRequest {
+  projectDir: string;
+  prompt?: string | null;
+  model?: string | null;
+  permissionMode?: PermissionMode | null;
+  target: LaunchTarget;
+}
+
+/**
+ * A session that was just started
+ */
+export interface LaunchedSession {
+  sessionId: string;
+
+  /** null when a terminal emulator started claude; the detector finds it by session ID */
+  pid: number | null;
+  target: LaunchTarget;
+}
+
+/**
+ * Payload of the `pty-output` event
+ */
+export interface PtyOutput {
+  pid: number;
+  data: string;
+}
+
+/**
+ * Payload of the `pty-exit` event
+ */
+export interface PtyExit {
+  pid: number;
+
+  /** null when the process was killed by a signal */
+  exitCode: number | null;
+}
+
+/**
+ * A session waiting on the user, from `get_attention_queue`
+ */
+export interface AttentionItem {
+  sessionId: string;
+  pid: number;
+  projectPath: string;
+  status: SessionStatus;
+
+  /** Seconds since the session entered its status */
+  waitingSecs: number;
+  score: number;
+
+  /** Readable parts of the score, largest first, e.g. "Bash destructive command (+40)" */
+  reasons: string[];
+}
+
+/** What a tool call could do, from least to most worrying */
+export type RiskLevel = 'readOnly' | 'localWrite' | 'network' | 'secrets' | 'destructive';
+
+/**
+ * Risk of a pending tool call and the rules that decided it
+ */
+export interface ToolRisk {
+  level: RiskLevel;
+
+  /** Patterns of the rules that matched at that level, e.g. "git push" or ".env" */
+  matched: string[];
+}
+
+/**
+ * A rule raising the risk of the tool calls it matches; exactly one of `command` and `contains` is set
+ */
+export interface RiskRule {
+  /** Tools the rule applies to; empty or absent for all */
+  tools?: string[];
+
+  /** A command the Bash line runs, matched word by word at the start of each command in it */
+  command?: string;
+
+  /** Text anywhere in the call's target (command, file path or URL), ignoring case */
+  contains?: string;
+  level: RiskLevel;
+}
+
+/**
+ * A tool call waiting for the user's permission, with what it would do
+ */
+export interface PendingTool {
+  /** `tool_use` block ID */
+  id: string;
+  name: string;
+
+  /** Input exactly as Claude sent it */
+  input: Record<string, unknown>;
+
+  /** What the call acts on in full: the command, file path or URL */
+  target: string;
+
+  /** Claude's own explanation of the call, when the tool takes one */
+  description: string | null;
+
+  /** Lines removed and added by an Edit or MultiEdit, prefixed with `-` and `+` */
+  diff: string | null;
+  risk: ToolRisk;
+}